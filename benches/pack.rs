@@ -0,0 +1,81 @@
+//! Pack and unpack time of account data, run with `cargo bench`.
+//!
+//! Plain `Instant` loop, criterion is not a dependency of this crate.
+
+use std::{hint::black_box, time::Instant};
+
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+
+use dutch_auction::{
+    state::{BidReceipt, Deposit, FreeClaim, SealedBid},
+    vectors,
+};
+
+const ITERATIONS: u32 = 100_000;
+
+fn bench<T: Pack + IsInitialized + Copy>(name: &str, value: T) {
+    let mut packed = vec![0; T::LEN];
+
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        T::pack(black_box(value), black_box(&mut packed)).expect("failed to pack");
+    }
+    let pack = started.elapsed() / ITERATIONS;
+
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(T::unpack(black_box(&packed)).expect("failed to unpack"));
+    }
+    let unpack = started.elapsed() / ITERATIONS;
+
+    println!(
+        "{:<12} {:>5} bytes  pack {:>8?}  unpack {:>8?}",
+        name,
+        T::LEN,
+        pack,
+        unpack
+    );
+}
+
+fn main() {
+    bench("Auction", vectors::auction());
+    bench(
+        "BidReceipt",
+        BidReceipt {
+            is_initialized: true,
+            token_amount: 10,
+            paid: 10_000_000_000,
+            refunded: false,
+        },
+    );
+    bench(
+        "Deposit",
+        Deposit {
+            is_initialized: true,
+            wallet: Pubkey::new_from_array([1; 32]),
+            order_nonce: 3,
+        },
+    );
+    bench(
+        "FreeClaim",
+        FreeClaim {
+            is_initialized: true,
+            amount: 5,
+        },
+    );
+    bench(
+        "SealedBid",
+        SealedBid {
+            is_initialized: true,
+            commitment: [2; 32],
+            escrow: 10_000_000_000,
+            revealed: true,
+            token_amount: 10,
+            price: 1_000_000_000,
+            level: 0,
+        },
+    );
+}
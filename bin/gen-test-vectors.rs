@@ -0,0 +1,12 @@
+//! Print canonical test vectors as JSON, see `dutch_auction::vectors`.
+
+#[cfg(not(target_arch = "bpf"))]
+fn main() {
+    println!(
+        "{}",
+        dutch_auction::vectors::TestVectors::generate().to_json()
+    );
+}
+
+#[cfg(target_arch = "bpf")]
+fn main() {}
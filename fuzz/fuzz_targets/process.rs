@@ -0,0 +1,77 @@
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+use dutch_auction::processor::Processor;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: [u8; 32],
+    owner: [u8; 32],
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInput {
+    accounts: Vec<FuzzAccount>,
+    instruction_data: Vec<u8>,
+}
+
+fuzz_target!(|input: FuzzInput| {
+    let keys = input
+        .accounts
+        .iter()
+        .map(|account| {
+            (
+                Pubkey::new_from_array(account.key),
+                Pubkey::new_from_array(account.owner),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut lamports = input
+        .accounts
+        .iter()
+        .map(|account| account.lamports)
+        .collect::<Vec<_>>();
+    let mut data = input
+        .accounts
+        .iter()
+        .map(|account| account.data.clone())
+        .collect::<Vec<_>>();
+
+    let lamports_before = lamports.clone();
+    let accounts = input
+        .accounts
+        .iter()
+        .zip(keys.iter())
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|(((account, (key, owner)), lamports), data)| {
+            AccountInfo::new(
+                key,
+                account.is_signer,
+                account.is_writable,
+                lamports,
+                data,
+                owner,
+                false,
+                0,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let _ = Processor::process(&dutch_auction::id(), &accounts, &input.instruction_data);
+
+    // Value can move only through CPI, which is not available off-chain, so
+    // without documented signers nothing should be changed.
+    let unsigned_changes = accounts
+        .iter()
+        .zip(lamports_before.iter())
+        .any(|(account, before)| !account.is_signer && account.lamports() > *before);
+    let signed = accounts.iter().any(|account| account.is_signer);
+    assert!(signed || !unsigned_changes, "lamports moved without signer");
+});
@@ -0,0 +1,168 @@
+//! Helpers for off-chain clients and monitors, not compiled for program.
+
+use std::fmt;
+
+use solana_program::clock::UnixTimestamp;
+
+use crate::state::Auction;
+
+/// Expected slot duration in milliseconds.
+pub const SLOT_MS: i64 = 400;
+
+/// Single field of `Auction` which differs between two observations, values
+/// are formatted with `Debug`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+// Destructuring makes adding field to `Auction` without listing it here a
+// compile error.
+macro_rules! diff_fields {
+    ($before:ident, $after:ident, $($field:ident),* $(,)?) => {{
+        let Auction { $($field: _),* } = $before;
+        let mut changes = vec![];
+        $(
+            if $before.$field != $after.$field {
+                changes.push(FieldChange {
+                    field: stringify!($field),
+                    before: format!("{:?}", $before.$field),
+                    after: format!("{:?}", $after.$field),
+                });
+            }
+        )*
+        changes
+    }};
+}
+
+/// Fields changed between `before` and `after`, in `Auction` field order.
+pub fn diff_auction(before: &Auction, after: &Auction) -> Vec<FieldChange> {
+    diff_fields!(
+        before,
+        after,
+        is_initialized,
+        authority,
+        token,
+        time_start,
+        time_step,
+        price_start,
+        price_step,
+        token_amount_for_sale,
+        escrow_policy,
+        price_cumulative,
+        price_cumulative_updated_at,
+        hook_program,
+        allowlist_enabled,
+        blackout_period,
+        blackout_offset,
+        blackout_duration,
+        blackout_pauses_price,
+        price_lock_skew,
+        finalization_policy,
+        max_tokens_per_step,
+        step_sold_index,
+        step_sold_amount,
+        price_curve,
+        taker_fee_bps,
+        update_delay,
+        listing_marker,
+        cosigner,
+        buyer_profiles,
+        min_buyer_auctions,
+        tokens_sold,
+        proceeds,
+        last_price,
+        allow_zero_price,
+        free_claim_limit,
+        tip_recipient,
+        tips,
+        denied_programs,
+        price_milestones,
+        price_floor,
+        ended_at,
+        buy_now_price,
+        merkle_root,
+        max_bid_amount,
+        payment_mint,
+        price_schedule,
+        price_ceiling,
+        uniform_price,
+        clearing_price,
+        refunded,
+        commit_end,
+        reveal_end,
+        time_end,
+        free_claim_end,
+    )
+}
+
+/// Commitment level at which auction state and time were observed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    // Typical number of slots observed state is behind the cluster.
+    fn slots_behind(self) -> i64 {
+        match self {
+            Self::Processed => 0,
+            Self::Confirmed => 2,
+            Self::Finalized => 32,
+        }
+    }
+}
+
+/// Price range in which bid sent now is expected to land.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LandingEstimate {
+    // Price at the earliest landing time, upper bound for bid price.
+    pub max_price: Option<u64>,
+    // Price at the latest landing time.
+    pub min_price: Option<u64>,
+    // Probability that bid lands after price step boundary, in `[0, 1]`.
+    pub next_step_probability: f64,
+}
+
+/// Estimate landing price of bid sent right after `auction` was observed at
+/// `observed_at` with `commitment`. Landing time is modeled as uniformly
+/// distributed over one slot after observed state lag plus `latency_ms`.
+pub fn estimate_landing_price(
+    auction: &Auction,
+    observed_at: UnixTimestamp,
+    commitment: Commitment,
+    latency_ms: u64,
+) -> LandingEstimate {
+    let lag_ms = commitment.slots_behind() * SLOT_MS + latency_ms.min(i64::MAX as u64) as i64;
+    let start_ms = observed_at.saturating_mul(1_000).saturating_add(lag_ms);
+    let end_ms = start_ms.saturating_add(SLOT_MS);
+
+    let max_price = auction.price_at(start_ms.div_euclid(1_000));
+    let min_price = auction.price_at(end_ms.div_euclid(1_000));
+
+    // Steps change only at whole seconds, find the first change in window
+    let start_step = auction.steps_elapsed(start_ms.div_euclid(1_000));
+    let boundary_ms = (start_ms.div_euclid(1_000) + 1..=end_ms.div_euclid(1_000))
+        .find(|&second| auction.steps_elapsed(second) != start_step)
+        .map(|second| second * 1_000);
+    let next_step_probability = match boundary_ms {
+        Some(boundary_ms) => (end_ms - boundary_ms) as f64 / (end_ms - start_ms) as f64,
+        None => 0.0,
+    };
+
+    LandingEstimate {
+        max_price,
+        min_price,
+        next_step_probability,
+    }
+}
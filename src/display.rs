@@ -0,0 +1,143 @@
+//! Stable text form of state enums for CLI and logs, not compiled for program.
+//! `FromStr` accepts exactly what `Display` renders.
+
+use std::{fmt, str::FromStr};
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    error::ParseError,
+    state::{AdminAction, AmountBasis, Auction, EscrowPolicy, FinalizationPolicy, PriceCurve},
+};
+
+// Unit-only enums are rendered as fixed kebab-case names.
+macro_rules! unit_enum_names {
+    ($enum:ident, $kind:expr, $($variant:ident => $name:expr),* $(,)?) => {
+        impl fmt::Display for $enum {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(match self {
+                    $(Self::$variant => $name,)*
+                })
+            }
+        }
+
+        impl FromStr for $enum {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($name => Ok(Self::$variant),)*
+                    _ => Err(ParseError {
+                        kind: $kind,
+                        value: s.to_owned(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+unit_enum_names!(
+    EscrowPolicy,
+    "escrow policy",
+    IgnoreExtras => "ignore-extras",
+    AddExtrasToSale => "add-extras-to-sale",
+);
+
+unit_enum_names!(
+    PriceCurve,
+    "price curve",
+    Linear => "linear",
+    Exponential => "exponential",
+    Piecewise => "piecewise",
+    Ascending => "ascending",
+);
+
+unit_enum_names!(
+    AmountBasis,
+    "amount basis",
+    BaseUnits => "base-units",
+    WholeTokens => "whole-tokens",
+);
+
+unit_enum_names!(
+    AdminAction,
+    "admin action",
+    WithdrawSOL => "withdraw-sol",
+    WithdrawTokens => "withdraw-tokens",
+    AddToAllowlist => "add-to-allowlist",
+    RemoveFromAllowlist => "remove-from-allowlist",
+    ProposeUpdate => "propose-update",
+    ApplyUpdate => "apply-update",
+    UpdateAuction => "update-auction",
+    SetPriceMilestones => "set-price-milestones",
+    EndAuctionEarly => "end-auction-early",
+    WithdrawPayment => "withdraw-payment",
+    SetPriceSchedule => "set-price-schedule",
+);
+
+impl fmt::Display for FinalizationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ReturnToSeller => f.write_str("return-to-seller"),
+            Self::Burn => f.write_str("burn"),
+            Self::SendTo(owner) => write!(f, "send-to:{}", owner),
+        }
+    }
+}
+
+impl FromStr for FinalizationPolicy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError {
+            kind: "finalization policy",
+            value: s.to_owned(),
+        };
+        match s {
+            "return-to-seller" => Ok(Self::ReturnToSeller),
+            "burn" => Ok(Self::Burn),
+            _ => {
+                let owner = s.strip_prefix("send-to:").ok_or_else(invalid)?;
+                owner
+                    .parse::<Pubkey>()
+                    .map(Self::SendTo)
+                    .map_err(|_| invalid())
+            }
+        }
+    }
+}
+
+/// Single-line `Debug` of `Auction` with schedule and sale progress only,
+/// for logs where derived output is too long to grep.
+pub struct CompactAuction<'a>(pub &'a Auction);
+
+impl fmt::Debug for CompactAuction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let auction = self.0;
+        write!(
+            f,
+            "Auction {{ token: {}, start: {}, step: {}, curve: {}",
+            auction.token, auction.time_start, auction.time_step, auction.price_curve,
+        )?;
+        write!(
+            f,
+            ", price: {}/{}, remaining: {}, sold: {}, proceeds: {}",
+            auction.price_start,
+            auction.price_step,
+            auction.remaining(),
+            auction.tokens_sold,
+            auction.proceeds,
+        )?;
+        if auction.price_floor > 0 {
+            write!(f, ", floor: {}", auction.price_floor)?;
+        }
+        if auction.time_end != 0 {
+            write!(f, ", end: {}", auction.time_end)?;
+        }
+        if auction.ended_at != 0 {
+            write!(f, ", ended_at: {}", auction.ended_at)?;
+        }
+        f.write_str(" }")
+    }
+}
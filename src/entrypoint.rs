@@ -1,9 +1,18 @@
 use solana_program::{
     account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
 };
+use solana_security_txt::security_txt;
 
 entrypoint!(process_instruction);
 
+security_txt! {
+    name: "Dutch Auction",
+    project_url: "https://github.com/fanatid/solana-dutch-auction",
+    contacts: "link:https://github.com/fanatid/solana-dutch-auction/security/advisories/new",
+    policy: "https://github.com/fanatid/solana-dutch-auction/blob/master/SECURITY.md",
+    source_code: "https://github.com/fanatid/solana-dutch-auction"
+}
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
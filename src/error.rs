@@ -2,30 +2,271 @@ use num_derive::FromPrimitive;
 use solana_program::{decode_error::DecodeError, program_error::ProgramError};
 use thiserror::Error;
 
+/// Program error, returned as `ProgramError::Custom` with variant value.
+/// Values are stable: variants are never renumbered or reused, new variants
+/// get next value. Same values are available as `CODE_*` constants.
 #[derive(Clone, Debug, Eq, Error, PartialEq, FromPrimitive)]
 pub enum AuctionError {
-    // 0
     #[error("Already in use")]
-    AlreadyInUse,
+    AlreadyInUse = 0,
     #[error("Invalid instruction")]
-    InvalidInstruction,
+    InvalidInstruction = 1,
     #[error("Invalid UnixTimestamp")]
-    InvalidInitializationTime,
+    InvalidInitializationTime = 2,
     #[error("Invalid derived auction Token owner address")]
-    InvalidAuctionTokenOwnerAddress,
+    InvalidAuctionTokenOwnerAddress = 3,
     #[error("Invalid associated auction Token address")]
-    InvalidAuctionTokenAddress,
-    // 5
+    InvalidAuctionTokenAddress = 4,
     #[error("Auction not started yet")]
-    NotStarted,
+    NotStarted = 5,
     #[error("Auction finished")]
-    Finished,
+    Finished = 6,
     #[error("Everything sold out")]
-    EverythingSoldOut,
+    EverythingSoldOut = 7,
     #[error("Owner does not match")]
-    OwnerMismatch,
+    OwnerMismatch = 8,
     #[error("Auction not finished yet")]
-    NotFinished,
+    NotFinished = 9,
+    #[error("Not enough tokens in auction associated token account")]
+    InsufficientPrefundedTokens = 10,
+    #[error("Invalid fill hook program")]
+    InvalidHookProgram = 11,
+    #[error("Wallet is not in auction allowlist")]
+    NotAllowlisted = 12,
+    #[error("Invalid allowlist entry address")]
+    InvalidAllowlistEntry = 13,
+    #[error("Invalid blackout window")]
+    InvalidBlackoutWindow = 14,
+    #[error("Auction in blackout window")]
+    InBlackout = 15,
+    #[error("Invalid session account")]
+    InvalidSession = 16,
+    #[error("Session expired")]
+    SessionExpired = 17,
+    #[error("Invalid deposit account")]
+    InvalidDeposit = 18,
+    #[error("Bids at quote time are disabled")]
+    PriceLockDisabled = 19,
+    #[error("Invalid bid quote time")]
+    InvalidQuoteTime = 20,
+    #[error("Token mint can not be used for launch")]
+    InvalidLaunchMint = 21,
+    #[error("Everything sold out in current price step")]
+    StepSoldOut = 22,
+    #[error("Invalid admin log address")]
+    InvalidAdminLog = 23,
+    #[error("Token amount overflow")]
+    InvalidAmount = 24,
+    #[error("Bundle cost exceeds limit")]
+    BundleCostExceeded = 25,
+    #[error("Bundle fill is not complete")]
+    BundleNotFilled = 26,
+    #[error("Taker fee should not exceed 100%")]
+    InvalidTakerFee = 27,
+    #[error("Parameter updates are disabled")]
+    UpdatesDisabled = 28,
+    #[error("Update delay not passed yet")]
+    UpdateNotReady = 29,
+    #[error("Invalid pending update account")]
+    InvalidPendingUpdate = 30,
+    #[error("Auction for this token by authority already exists")]
+    DuplicateListing = 31,
+    #[error("Invalid listing marker account")]
+    InvalidListingMarker = 32,
+    #[error("Invalid buyer profile account")]
+    InvalidBuyerProfile = 33,
+    #[error("Not enough auctions in buyer profile")]
+    InsufficientBuyerHistory = 34,
+    #[error("Invalid settlement summary address")]
+    InvalidSettlementSummary = 35,
+    #[error("Invalid free claim")]
+    InvalidFreeClaim = 36,
+    #[error("Wallet already claimed free tokens")]
+    AlreadyClaimed = 37,
+    #[error("Invalid customer associated token account address")]
+    InvalidCustomerTokenAddress = 38,
+    #[error("Invalid tip recipient account")]
+    InvalidTipRecipient = 39,
+    #[error("Bid transaction calls denied program")]
+    DeniedProgram = 40,
+    #[error("Auction already started")]
+    AlreadyStarted = 41,
+    #[error("Invalid price milestones")]
+    InvalidPriceMilestones = 42,
+    #[error("Buy now is disabled")]
+    BuyNowDisabled = 43,
+    #[error("Not enough lamports for transfer")]
+    InsufficientLamports = 44,
+    #[error("Not enough tokens for transfer")]
+    InsufficientTokenBalance = 45,
+    #[error("Token account is frozen")]
+    FrozenTokenAccount = 46,
+    #[error("Account did not pay rent of closed account")]
+    InvalidRentPayer = 47,
+    #[error("Invalid payment mint")]
+    InvalidPaymentMint = 48,
+    #[error("Invalid associated auction payment token address")]
+    InvalidAuctionPaymentAddress = 49,
+    #[error("Decay rate above 10000 bps")]
+    InvalidDecayRate = 50,
+    #[error("Purchase order is not signed by buyer")]
+    InvalidOrderSignature = 51,
+    #[error("Purchase order expired")]
+    OrderExpired = 52,
+    #[error("Invalid purchase order nonce")]
+    InvalidOrderNonce = 53,
+    #[error("Price above purchase order limit")]
+    OrderPriceExceeded = 54,
+    #[error("Invalid price schedule")]
+    InvalidPriceSchedule = 55,
+    #[error("Ascending price should start above zero and not exceed ceiling")]
+    InvalidPriceCeiling = 56,
+    #[error("Invalid bid receipt")]
+    InvalidBidReceipt = 57,
+    #[error("Refund already claimed")]
+    RefundClaimed = 58,
+    #[error("Uniform price needs SOL payments and descending price without free claims")]
+    InvalidUniformPrice = 59,
+    #[error("Auction accepts only sealed bids")]
+    SealedBidsOnly = 60,
+    #[error("Sealed bids need SOL payments and descending price")]
+    InvalidSealedAuction = 61,
+    #[error("Invalid sealed bid account")]
+    InvalidSealedBid = 62,
+    #[error("Sealed bid window is closed")]
+    SealedBidWindowClosed = 63,
+    #[error("Revealed bid does not match commitment")]
+    InvalidCommitment = 64,
+    #[error("Escrow does not cover revealed bid")]
+    InsufficientEscrow = 65,
+    #[error("Invalid sealed book account")]
+    InvalidSealedBook = 66,
+    #[error("Sealed bids are not settled yet")]
+    NotSettled = 67,
+    #[error("Sealed bids are already settled")]
+    AlreadySettled = 68,
+    #[error("Purchase orders not supported with taker fee")]
+    OrderTakerFeeNotSupported = 69,
+    #[error("Free claims window ended")]
+    FreeClaimsEnded = 70,
+    #[error("Launch should be followed by auction initialization")]
+    InvalidLaunchInitialization = 71,
+    #[error("Invalid quote account")]
+    InvalidQuote = 72,
+    #[error("Invalid settlement preview account")]
+    InvalidSettlementPreview = 73,
+    #[error("Invalid relayed order account")]
+    InvalidRelayedOrder = 74,
+    #[error("Token mint does not match auction")]
+    InvalidAuctionToken = 75,
+}
+
+// Numeric values of `AuctionError`, for clients mapping codes to messages.
+pub const CODE_ALREADY_IN_USE: u32 = 0;
+pub const CODE_INVALID_INSTRUCTION: u32 = 1;
+pub const CODE_INVALID_INITIALIZATION_TIME: u32 = 2;
+pub const CODE_INVALID_AUCTION_TOKEN_OWNER_ADDRESS: u32 = 3;
+pub const CODE_INVALID_AUCTION_TOKEN_ADDRESS: u32 = 4;
+pub const CODE_NOT_STARTED: u32 = 5;
+pub const CODE_FINISHED: u32 = 6;
+pub const CODE_EVERYTHING_SOLD_OUT: u32 = 7;
+pub const CODE_OWNER_MISMATCH: u32 = 8;
+pub const CODE_NOT_FINISHED: u32 = 9;
+pub const CODE_INSUFFICIENT_PREFUNDED_TOKENS: u32 = 10;
+pub const CODE_INVALID_HOOK_PROGRAM: u32 = 11;
+pub const CODE_NOT_ALLOWLISTED: u32 = 12;
+pub const CODE_INVALID_ALLOWLIST_ENTRY: u32 = 13;
+pub const CODE_INVALID_BLACKOUT_WINDOW: u32 = 14;
+pub const CODE_IN_BLACKOUT: u32 = 15;
+pub const CODE_INVALID_SESSION: u32 = 16;
+pub const CODE_SESSION_EXPIRED: u32 = 17;
+pub const CODE_INVALID_DEPOSIT: u32 = 18;
+pub const CODE_PRICE_LOCK_DISABLED: u32 = 19;
+pub const CODE_INVALID_QUOTE_TIME: u32 = 20;
+pub const CODE_INVALID_LAUNCH_MINT: u32 = 21;
+pub const CODE_STEP_SOLD_OUT: u32 = 22;
+pub const CODE_INVALID_ADMIN_LOG: u32 = 23;
+pub const CODE_INVALID_AMOUNT: u32 = 24;
+pub const CODE_BUNDLE_COST_EXCEEDED: u32 = 25;
+pub const CODE_BUNDLE_NOT_FILLED: u32 = 26;
+pub const CODE_INVALID_TAKER_FEE: u32 = 27;
+pub const CODE_UPDATES_DISABLED: u32 = 28;
+pub const CODE_UPDATE_NOT_READY: u32 = 29;
+pub const CODE_INVALID_PENDING_UPDATE: u32 = 30;
+pub const CODE_DUPLICATE_LISTING: u32 = 31;
+pub const CODE_INVALID_LISTING_MARKER: u32 = 32;
+pub const CODE_INVALID_BUYER_PROFILE: u32 = 33;
+pub const CODE_INSUFFICIENT_BUYER_HISTORY: u32 = 34;
+pub const CODE_INVALID_SETTLEMENT_SUMMARY: u32 = 35;
+pub const CODE_INVALID_FREE_CLAIM: u32 = 36;
+pub const CODE_ALREADY_CLAIMED: u32 = 37;
+pub const CODE_INVALID_CUSTOMER_TOKEN_ADDRESS: u32 = 38;
+pub const CODE_INVALID_TIP_RECIPIENT: u32 = 39;
+pub const CODE_DENIED_PROGRAM: u32 = 40;
+pub const CODE_ALREADY_STARTED: u32 = 41;
+pub const CODE_INVALID_PRICE_MILESTONES: u32 = 42;
+pub const CODE_BUY_NOW_DISABLED: u32 = 43;
+pub const CODE_INSUFFICIENT_LAMPORTS: u32 = 44;
+pub const CODE_INSUFFICIENT_TOKEN_BALANCE: u32 = 45;
+pub const CODE_FROZEN_TOKEN_ACCOUNT: u32 = 46;
+pub const CODE_INVALID_RENT_PAYER: u32 = 47;
+pub const CODE_INVALID_PAYMENT_MINT: u32 = 48;
+pub const CODE_INVALID_AUCTION_PAYMENT_ADDRESS: u32 = 49;
+pub const CODE_INVALID_DECAY_RATE: u32 = 50;
+pub const CODE_INVALID_ORDER_SIGNATURE: u32 = 51;
+pub const CODE_ORDER_EXPIRED: u32 = 52;
+pub const CODE_INVALID_ORDER_NONCE: u32 = 53;
+pub const CODE_ORDER_PRICE_EXCEEDED: u32 = 54;
+pub const CODE_INVALID_PRICE_SCHEDULE: u32 = 55;
+pub const CODE_INVALID_PRICE_CEILING: u32 = 56;
+pub const CODE_INVALID_BID_RECEIPT: u32 = 57;
+pub const CODE_REFUND_CLAIMED: u32 = 58;
+pub const CODE_INVALID_UNIFORM_PRICE: u32 = 59;
+pub const CODE_SEALED_BIDS_ONLY: u32 = 60;
+pub const CODE_INVALID_SEALED_AUCTION: u32 = 61;
+pub const CODE_INVALID_SEALED_BID: u32 = 62;
+pub const CODE_SEALED_BID_WINDOW_CLOSED: u32 = 63;
+pub const CODE_INVALID_COMMITMENT: u32 = 64;
+pub const CODE_INSUFFICIENT_ESCROW: u32 = 65;
+pub const CODE_INVALID_SEALED_BOOK: u32 = 66;
+pub const CODE_NOT_SETTLED: u32 = 67;
+pub const CODE_ALREADY_SETTLED: u32 = 68;
+pub const CODE_ORDER_TAKER_FEE_NOT_SUPPORTED: u32 = 69;
+pub const CODE_FREE_CLAIMS_ENDED: u32 = 70;
+pub const CODE_INVALID_LAUNCH_INITIALIZATION: u32 = 71;
+pub const CODE_INVALID_QUOTE: u32 = 72;
+pub const CODE_INVALID_SETTLEMENT_PREVIEW: u32 = 73;
+pub const CODE_INVALID_RELAYED_ORDER: u32 = 74;
+pub const CODE_INVALID_AUCTION_TOKEN: u32 = 75;
+
+/// Errors returned by instruction builders, so client mistakes are caught
+/// before transaction is sent.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum BuilderError {
+    #[error("Pubkey `{0}` is not set")]
+    DefaultPubkey(&'static str),
+    #[error("Amount `{0}` should be greater than zero")]
+    ZeroAmount(&'static str),
+    #[error("Time step should be greater than zero")]
+    InvalidTimeStep,
+    #[error("Bundle should have from 1 to MAX_BUNDLE_SIZE fills")]
+    InvalidBundleSize,
+    #[error("Auction can deny at most MAX_DENIED_PROGRAMS programs")]
+    TooManyDeniedPrograms,
+    #[error("Price milestones should be ordered and fit into basis points")]
+    InvalidPriceMilestones,
+    #[error("Merkle proof can have at most MAX_MERKLE_PROOF_LEN hashes")]
+    MerkleProofTooLong,
+    #[error("Price schedule should start at zero offset and be ordered")]
+    InvalidPriceSchedule,
+}
+
+/// Error of parsing state enum from its `Display` form.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("Invalid {kind} `{value}`")]
+pub struct ParseError {
+    pub kind: &'static str,
+    pub value: String,
 }
 
 impl From<AuctionError> for ProgramError {
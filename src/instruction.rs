@@ -5,20 +5,84 @@ use solana_program::{
     pubkey::Pubkey,
     system_program, sysvar,
 };
+use spl_associated_token_account::get_associated_token_address;
 use std::convert::TryInto;
 use std::mem::size_of;
 
-use crate::error::AuctionError;
+use num_traits::FromPrimitive;
+
+use crate::{
+    error::{AuctionError, BuilderError},
+    merkle::MAX_MERKLE_PROOF_LEN,
+    order::PurchaseOrder,
+    state::{
+        AdminLog, AllowlistEntry, AmountBasis, BidReceipt, BuyerProfile, Deposit, EscrowPolicy,
+        FinalizationPolicy, FreeClaim, ListingMarker, PendingUpdate, PriceBreakpoint, PriceCurve,
+        PriceMilestone, PriceMilestones, PriceSchedule, Quote, RelayedOrder, SealedBid, SealedBook,
+        Session, SettlementPreview, SettlementSummary, MAX_DENIED_PROGRAMS, MAX_PRICE_BREAKPOINTS,
+        MAX_PRICE_MILESTONES,
+    },
+};
+
+/// Maximum number of fills in `MakeBundleBid`.
+pub const MAX_BUNDLE_SIZE: usize = 4;
+
+/// Parameters of auction set by `InitializeAuction`, see `state::Auction`
+/// for meaning of fields.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub struct AuctionConfig {
+    pub token_amount: u64,
+    pub time_start: UnixTimestamp,
+    pub time_step: UnixTimestamp,
+    pub price_start: u64,
+    pub price_step: u64,
+    pub assume_prefunded: bool,
+    pub escrow_policy: EscrowPolicy,
+    pub hook_program: Option<Pubkey>,
+    pub allowlist_enabled: bool,
+    pub blackout_period: UnixTimestamp,
+    pub blackout_offset: UnixTimestamp,
+    pub blackout_duration: UnixTimestamp,
+    pub blackout_pauses_price: bool,
+    pub price_lock_skew: UnixTimestamp,
+    pub finalization_policy: FinalizationPolicy,
+    pub max_tokens_per_step: u64,
+    pub amount_basis: AmountBasis,
+    pub price_curve: PriceCurve,
+    pub taker_fee_bps: u16,
+    pub update_delay: UnixTimestamp,
+    pub unique_listing: bool,
+    pub cosigner: Option<Pubkey>,
+    pub buyer_profiles: bool,
+    pub min_buyer_auctions: u64,
+    pub allow_zero_price: bool,
+    pub free_claim_limit: u64,
+    pub tip_recipient: Option<Pubkey>,
+    pub denied_programs: Vec<Pubkey>,
+    pub buy_now_price: u64,
+    pub merkle_root: [u8; 32],
+    pub max_bid_amount: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub price_ceiling: u64,
+    pub uniform_price: bool,
+    pub commit_end: UnixTimestamp,
+    pub reveal_end: UnixTimestamp,
+    pub time_end: UnixTimestamp,
+    pub free_claim_end: UnixTimestamp,
+}
 
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
 pub enum AuctionInstruction {
     // Initialize auction by set auction parameters and transfer tokens for sell.
     // Accounts:
     //  0. `[writeable]` Auction account to initialize.
     //  1. `[]` Auction authority key.
     //  2. `[]` System account
-    //  3. `[writeable]` Funding account.
+    //  3. `[writeable,signer]` Rent payer for created accounts, can differ
+    //     from token source account's owner.
     //  4. `[]` Sysvar Rent account.
     //  5. `[]` `spl-associated-token-account` program account.
     //  6. `[]` Token account.
@@ -27,16 +91,21 @@ pub enum AuctionInstruction {
     //  9. `[writeable]` Auction associated token account.
     // 10. `[writeable]` Owner of auction associated token account.
     // 11. `[writeable,signer]` Token source account's owner/delegate.
+    // 12. `[writeable]` Admin log account.
+    // 13. `[writeable]` Listing marker account, if `unique_listing`.
+    // 14. `[]` Payment mint account, if `payment_mint`.
+    // 15. `[writeable]` Auction associated payment token account, if
+    //     `payment_mint`. Created with rent from funding account.
+    // With `assume_prefunded` auction associated token account should already
+    // exist and hold `token_amount` tokens, source accounts are not used.
     InitializeAuction {
-        token_amount: u64,
-        time_start: UnixTimestamp,
-        time_step: UnixTimestamp,
-        price_start: u64,
-        price_step: u64,
+        // Decoded as fields of instruction itself
+        #[cfg_attr(not(target_arch = "bpf"), serde(flatten))]
+        config: Box<AuctionConfig>,
     },
     // Attempt to buy Token with SOL.
     // Accounts:
-    //  0. `[]` Auction account.
+    //  0. `[writeable]` Auction account.
     //  1. `[]` System account.
     //  2. `[writeable]` Funding account.
     //  3. `[]` Token account.
@@ -44,28 +113,502 @@ pub enum AuctionInstruction {
     //  5. `[writeable]` Auction associated token account.
     //  6. `[writeable]` Owner of auction associated token account.
     //  7. `[writeable]` Customer token account.
+    //  8. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  9. `[]` Allowlist entry of funding account, if auction has allowlist.
+    //  10. `[writeable]` Marketplace account, if auction has taker fee.
+    //  11. `[writeable]` Buyer profile of wallet, if auction has buyer profiles.
+    //  12. `[writeable]` Price milestones account, if auction has milestones.
+    //  13. `[writeable]` Bid receipt of wallet, if auction has uniform price.
+    //  14. `[]` Payment mint account, if auction has payment mint.
+    //  15. `[writeable]` Payment token account of funding account, if auction
+    //      has payment mint.
+    //  16. `[writeable]` Auction associated payment token account, if auction
+    //      has payment mint.
+    // With payment mint cost and taker fee are paid in payment tokens, funding
+    // account should sign and marketplace account should be payment token
+    // account. Other bid instructions are not available for such auctions.
+    // With `create_token_customer` customer wallet, `spl-associated-token-account`
+    // program and Sysvar Rent accounts follow account 7. Customer token account
+    // should be associated token account of wallet, created with rent from
+    // funding account if not exists yet.
+    // Instructions sysvar account should be passed last if auction has denied
+    // programs, same for other bid instructions.
+    // Positive `tip` is transferred on top of bid cost to auction tip
+    // recipient, passed as last account, or to auction SOL account if auction
+    // has no tip recipient.
+    // Auction with merkle root accepts only bids with `merkle_proof` of
+    // funding account, other bid instructions fail.
     MakeBid {
         token_amount: u64,
+        amount_basis: AmountBasis,
+        create_token_customer: bool,
+        tip: u64,
+        merkle_proof: Vec<[u8; 32]>,
     },
     // Withdraw SOL from auction.
     // Accounts:
     //  0. `[]` Auction account.
-    //  1. `[signer]` Auction authority key.
+    //  1. `[signer]` Auction authority key, `[]` if spl-token multisig.
     //  2. `[]` System account.
     //  3. `[]` Token mint account.
     //  4. `[]` Owner of auction associated token account.
     //  5. `[writeable]` Destination account.
+    //  6. `[writeable]` Admin log account.
+    //  7. `[signer]` Co-signer key, if auction has it.
+    //  8. ..8+M `[signer]` M signer keys, if authority is spl-token multisig
+    //     account.
     WithdrawSOL,
-    // Withdraw Tokens from auction when finished.
+    // Withdraw Tokens from auction when finished, according to auction
+    // finalization policy. Authority signature required only for
+    // `ReturnToSeller`, destination account is not used for `Burn`.
     // Accounts:
     //  0. `[]` Auction account.
-    //  1. `[signer]` Auction authority key.
+    //  1. `[signer]` Auction authority key, `[]` if spl-token multisig.
     //  2. `[]` Token account.
-    //  3. `[]` Token mint account.
+    //  3. `[writeable]` Token mint account.
     //  4. `[writeable]` Auction associated token account.
     //  5. `[]` Owner of auction associated token account.
     //  6. `[writeable]` Destination token account.
+    //  7. `[writeable]` Admin log account.
+    //  8. `[writeable]` Listing marker account, if auction holds it. Closed on
+    //     first call.
+    //  9. `[writeable]` Rent payer of listing marker account, if auction holds
+    //     it. Receive listing marker rent.
+    // 10. `[]` Sealed book account, if auction has sealed bids. Tokens owed to
+    //     filled bids are not withdrawn.
+    // 11. `[signer]` Co-signer key, if auction has it and authority signature
+    //     is required.
+    // 12. ..12+M `[signer]` M signer keys, if authority is spl-token multisig
+    //     account and authority signature is required.
     WithdrawTokens,
+    // Reconcile tokens for sale with auction associated token account balance
+    // according to auction escrow policy. Can be called by anyone.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[]` Auction associated token account.
+    SyncEscrow,
+    // Update auction TWAP accumulator. Can be called by anyone.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    Poke,
+    // Allow wallet to make bids in auction with allowlist.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[]` System account.
+    //  3. `[writeable,signer]` Funding account.
+    //  4. `[writeable]` Allowlist entry account.
+    //  5. `[]` Wallet account.
+    //  6. `[writeable]` Admin log account.
+    AddToAllowlist,
+    // Remove wallet from auction allowlist.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[writeable]` Allowlist entry account.
+    //  3. `[]` Wallet account.
+    //  4. `[writeable]` Rent payer of allowlist entry, receive rent.
+    //  5. `[writeable]` Admin log account.
+    RemoveFromAllowlist,
+    // Authorize session key to make bids on behalf of wallet, `lamports` are
+    // moved to session account and can be spent only on bids.
+    // Accounts:
+    //  0. `[writeable,signer]` Wallet account.
+    //  1. `[]` System account.
+    //  2. `[writeable]` Session account.
+    //  3. `[]` Session key.
+    AuthorizeSession {
+        lamports: u64,
+        expires_at: UnixTimestamp,
+    },
+    // Close session and return unspent lamports to wallet.
+    // Accounts:
+    //  0. `[writeable,signer]` Wallet account.
+    //  1. `[writeable]` Session account.
+    RevokeSession,
+    // Same as `MakeBid`, but paid from session account.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Session account.
+    //  2. `[signer]` Session key.
+    //  3. `[]` Token account.
+    //  4. `[]` Token mint account.
+    //  5. `[writeable]` Auction associated token account.
+    //  6. `[writeable]` Owner of auction associated token account.
+    //  7. `[writeable]` Wallet token account.
+    //  8. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  9. `[]` Allowlist entry of wallet, if auction has allowlist.
+    //  10. `[writeable]` Marketplace account, if auction has taker fee.
+    //  11. `[writeable]` Buyer profile of wallet, if auction has buyer profiles.
+    //  12. `[writeable]` Price milestones account, if auction has milestones.
+    //  13. `[writeable]` Bid receipt of wallet, if auction has uniform price.
+    MakeSessionBid {
+        token_amount: u64,
+    },
+    // Deposit lamports for future bids, deposit account created on first call.
+    // Accounts:
+    //  0. `[writeable,signer]` Wallet account.
+    //  1. `[]` System account.
+    //  2. `[writeable]` Deposit account.
+    DepositFunds {
+        lamports: u64,
+    },
+    // Withdraw lamports from deposit back to wallet.
+    // Accounts:
+    //  0. `[writeable,signer]` Wallet account.
+    //  1. `[writeable]` Deposit account.
+    WithdrawFunds {
+        lamports: u64,
+    },
+    // Same as `MakeBid`, but paid from wallet deposit account.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Deposit account.
+    //  2. `[signer]` Wallet account.
+    //  3. `[]` Token account.
+    //  4. `[]` Token mint account.
+    //  5. `[writeable]` Auction associated token account.
+    //  6. `[writeable]` Owner of auction associated token account.
+    //  7. `[writeable]` Customer token account.
+    //  8. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  9. `[]` Allowlist entry of wallet, if auction has allowlist.
+    //  10. `[writeable]` Marketplace account, if auction has taker fee.
+    //  11. `[writeable]` Buyer profile of wallet, if auction has buyer profiles.
+    //  12. `[writeable]` Price milestones account, if auction has milestones.
+    //  13. `[writeable]` Bid receipt of wallet, if auction has uniform price.
+    MakeDepositBid {
+        token_amount: u64,
+    },
+    // Same as `MakeBid`, but price is calculated at `quoted_at` time, which
+    // should be not older than auction price lock skew.
+    // Accounts: same as `MakeBid`.
+    MakeBidAtTime {
+        token_amount: u64,
+        quoted_at: UnixTimestamp,
+    },
+    // Mint whole token supply to auction associated token account and disable
+    // minting. Should be followed by `InitializeAuction` of the same auction
+    // with `assume_prefunded` and `token_amount` in the same transaction,
+    // checked with instructions sysvar.
+    // Accounts:
+    //  0. `[signer]` Auction account, not initialized yet.
+    //  1. `[]` System account
+    //  2. `[writeable,signer]` Funding account.
+    //  3. `[]` Sysvar Rent account.
+    //  4. `[]` `spl-associated-token-account` program account.
+    //  5. `[]` Token account.
+    //  6. `[writeable]` Token mint account, without supply and freeze authority.
+    //  7. `[writeable]` Auction associated token account.
+    //  8. `[writeable]` Owner of auction associated token account.
+    //  9. `[signer]` Token mint authority.
+    //  10. `[]` Instructions sysvar.
+    InitializeLaunch {
+        token_amount: u64,
+    },
+    // Log program name, version and source revision, so deployed program can
+    // be identified by explorers and auditors.
+    // Accounts: none.
+    ProgramInfo,
+    // Buy from up to `MAX_BUNDLE_SIZE` auctions atomically, every fill should
+    // be complete and lamports spent by funding account should not exceed
+    // `max_lamports`, otherwise whole instruction fails.
+    // Accounts:
+    //  0. `[]` System account.
+    //  1. `[writeable,signer]` Funding account.
+    //  2. `[]` Token account.
+    //  Followed by accounts of every fill, in order of `token_amounts`:
+    //  3. `[writeable]` Auction account.
+    //  4. `[]` Token mint account.
+    //  5. `[writeable]` Auction associated token account.
+    //  6. `[writeable]` Owner of auction associated token account.
+    //  7. `[writeable]` Customer token account.
+    //  8. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  9. `[]` Allowlist entry of funding account, if auction has allowlist.
+    //  10. `[writeable]` Marketplace account, if auction has taker fee.
+    //  11. `[writeable]` Buyer profile of wallet, if auction has buyer profiles.
+    //  12. `[writeable]` Price milestones account, if auction has milestones.
+    //  13. `[writeable]` Bid receipt of wallet, if auction has uniform price.
+    MakeBundleBid {
+        max_lamports: u64,
+        token_amounts: Vec<u64>,
+    },
+    // Propose new auction parameters, they can be applied after auction
+    // update delay. New proposal replaces previous one and restarts delay.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[]` System account.
+    //  3. `[writeable,signer]` Funding account.
+    //  4. `[writeable]` Pending update account.
+    //  5. `[writeable]` Admin log account.
+    ProposeUpdate {
+        max_tokens_per_step: u64,
+        taker_fee_bps: u16,
+    },
+    // Apply pending update when delay passed. Can be called by anyone.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Pending update account.
+    //  2. `[writeable]` Rent payer of pending update, receive rent.
+    //  3. `[writeable]` Admin log account.
+    ApplyUpdate,
+    // Create buyer profile of wallet, required for bids in auctions with
+    // buyer profiles.
+    // Accounts:
+    //  0. `[writeable,signer]` Wallet account.
+    //  1. `[]` System account.
+    //  2. `[writeable]` Buyer profile account.
+    CreateBuyerProfile,
+    // Write settlement summary of sold out or finished auction. Can be
+    // called by anyone, only once.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[]` System account.
+    //  2. `[writeable,signer]` Funding account.
+    //  3. `[writeable]` Settlement summary account.
+    Settle,
+    // Write amount of tokens and lamports, including taker fee, which bid of
+    // `token_amount` would transfer right now to `state::Quote` account of
+    // wallet, so calling program can read it after CPI. Auction is not
+    // modified.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[writeable,signer]` Wallet account, pays rent of quote account.
+    //  2. `[]` System account.
+    //  3. `[writeable]` Quote account of wallet, created by first quote.
+    ViewQuote {
+        token_amount: u64,
+    },
+    // Claim remaining tokens for free after price reached zero in auction
+    // with `allow_zero_price`. Every wallet can claim once, up to
+    // `free_claim_limit` tokens. Claims end at `free_claim_end`, tokens are
+    // withdrawn by seller only after it.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[]` System account.
+    //  2. `[writeable,signer]` Wallet account, pays rent of free claim account.
+    //  3. `[]` Token account.
+    //  4. `[]` Token mint account.
+    //  5. `[writeable]` Auction associated token account.
+    //  6. `[]` Owner of auction associated token account.
+    //  7. `[writeable]` Customer token account.
+    //  8. `[writeable]` Free claim account.
+    //  9. `[]` Allowlist entry account, if auction has allowlist.
+    ClaimFree,
+    // Change auction start time and price parameters before auction started,
+    // parameters are validated as in `InitializeAuction`.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[writeable]` Admin log account.
+    UpdateAuction {
+        time_start: UnixTimestamp,
+        time_step: UnixTimestamp,
+        price_start: u64,
+        price_step: u64,
+    },
+    // Set rules which raise price floor as tokens are sold, replacing
+    // previous ones. Only before auction started.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[]` System account.
+    //  3. `[writeable,signer]` Funding account.
+    //  4. `[writeable]` Price milestones account.
+    //  5. `[writeable]` Admin log account.
+    SetPriceMilestones {
+        milestones: Vec<PriceMilestone>,
+    },
+    // End auction immediately, bids fail as for finished auction and unsold
+    // tokens and proceeds can be withdrawn.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[writeable]` Admin log account.
+    EndAuctionEarly,
+    // Buy all remaining tokens at auction `buy_now_price` per token, price
+    // curve is bypassed and auction ends. Accounts are the same as for
+    // `MakeBid` without customer wallet and tip recipient.
+    BuyNow,
+    // Withdraw payment tokens from auction paid with SPL token, same
+    // conditions as for `WithdrawSOL`.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[signer]` Auction authority key, `[]` if spl-token multisig.
+    //  2. `[]` Token account.
+    //  3. `[]` Token mint account.
+    //  4. `[]` Payment mint account.
+    //  5. `[writeable]` Auction associated payment token account.
+    //  6. `[]` Owner of auction associated token account.
+    //  7. `[writeable]` Destination payment token account.
+    //  8. `[writeable]` Admin log account.
+    //  9. `[signer]` Co-signer key, if auction has it.
+    // 10. ..10+M `[signer]` M signer keys, if authority is spl-token multisig
+    //     account.
+    WithdrawPayment,
+    // Write what withdrawals of finished auction would transfer right now to
+    // `state::SettlementPreview` account of wallet: tokens and where they go
+    // according to finalization policy, lamports, refunds owed and payment
+    // tokens. Taker fees and tips are paid on every bid, so nothing is left
+    // for them. Calling program can read it after CPI, auction is not
+    // modified.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[]` Auction associated token account.
+    //  2. `[]` Owner of auction associated token account.
+    //  3. `[writeable,signer]` Wallet account, pays rent of preview account.
+    //  4. `[]` System account.
+    //  5. `[writeable]` Settlement preview account of wallet, created by first
+    //     preview.
+    //  6. `[]` Auction associated payment token account, if auction paid with
+    //     SPL token.
+    PreviewSettlement,
+    // Fill purchase order signed off-chain by buyer, lamports are paid from
+    // buyer deposit and buyer signature is not required. Transaction should
+    // include ed25519 program instruction verifying buyer signature of
+    // `order::PurchaseOrder` message. Can be submitted by anyone, so not
+    // available in auctions with taker fee: marketplace is not part of
+    // signed message.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Deposit account of buyer.
+    //  2. `[]` Buyer wallet account.
+    //  3. `[]` Token account.
+    //  4. `[]` Token mint account.
+    //  5. `[writeable]` Auction associated token account.
+    //  6. `[writeable]` Owner of auction associated token account.
+    //  7. `[writeable]` Customer token account, owned by buyer.
+    //  8. `[]` Instructions sysvar.
+    //  9. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  10. `[]` Allowlist entry of buyer, if auction has allowlist.
+    //  11. `[writeable]` Buyer profile of buyer, if auction has buyer profiles.
+    //  12. `[writeable]` Price milestones account, if auction has milestones.
+    //  13. `[writeable]` Bid receipt of buyer, if auction has uniform price.
+    MakeOrderBid {
+        token_amount: u64,
+        max_price: u64,
+        expires_at: UnixTimestamp,
+        nonce: u64,
+    },
+    // Switch auction to piecewise price curve with given breakpoints,
+    // `price_start` and `price_step` are not used after that. Only before
+    // auction started.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[signer]` Auction authority key.
+    //  2. `[writeable]` Admin log account.
+    SetPriceSchedule {
+        breakpoints: Vec<PriceBreakpoint>,
+    },
+    // Create bid receipt of wallet, required for bids in auctions with
+    // uniform price.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[writeable,signer]` Wallet account.
+    //  2. `[]` System account.
+    //  3. `[writeable]` Bid receipt account.
+    CreateBidReceipt,
+    // Refund difference between paid lamports and cost of filled tokens at
+    // clearing price to wallet, once auction with uniform price finished.
+    // Can be called by anyone, only once per receipt.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Wallet account.
+    //  2. `[writeable]` Bid receipt account.
+    //  3. `[]` System account.
+    //  4. `[writeable]` Owner of auction associated token account.
+    ClaimRefund,
+    // Commit hash of sealed bid with `escrow` lamports during commit window,
+    // see `SealedBid::commitment`. Escrow should cover revealed bid at its
+    // price, its size is the only public information about bid.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[writeable,signer]` Wallet account.
+    //  2. `[]` System account.
+    //  3. `[writeable]` Sealed bid account.
+    //  4. `[writeable]` Sealed book account, created with rent from wallet
+    //     by the first commit.
+    CommitBid {
+        commitment: [u8; 32],
+        escrow: u64,
+    },
+    // Reveal sealed bid during reveal window, bid is added to demand at the
+    // highest price level not above `price`.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[signer]` Wallet account.
+    //  2. `[writeable]` Sealed bid account.
+    //  3. `[writeable]` Sealed book account.
+    RevealBid {
+        token_amount: u64,
+        price: u64,
+        salt: [u8; 32],
+    },
+    // Find clearing price of sealed bids after reveal window. Can be called
+    // by anyone, only once.
+    // Accounts:
+    //  0. `[]` Auction account.
+    //  1. `[writeable]` Sealed book account.
+    SettleSealedBids,
+    // Transfer filled tokens of settled sealed bid to wallet, cost at
+    // clearing price to auction SOL account and the rest of escrow with rent
+    // back to wallet. Sealed bid account is closed. Can be called by anyone.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[writeable]` Wallet account.
+    //  2. `[writeable]` Sealed bid account.
+    //  3. `[writeable]` Sealed book account.
+    //  4. `[]` Token program account.
+    //  5. `[]` Token mint account.
+    //  6. `[writeable]` Auction associated token account.
+    //  7. `[writeable]` Owner of auction associated token account.
+    //  8. `[writeable]` Wallet token account, used only if bid filled.
+    ClaimSealedBid,
+    // Same as `MakeOrderBid`, but lamports, fees and rent of relayed order
+    // account are paid by relayer, so buyer needs no SOL. Order should be
+    // signed with `relayed`, every order is filled once.
+    // Accounts:
+    //  0. `[writeable]` Auction account.
+    //  1. `[]` System account.
+    //  2. `[writeable,signer]` Relayer account.
+    //  3. `[]` Buyer wallet account.
+    //  4. `[]` Token account.
+    //  5. `[]` Token mint account.
+    //  6. `[writeable]` Auction associated token account.
+    //  7. `[writeable]` Owner of auction associated token account.
+    //  8. `[writeable]` Customer token account, owned by buyer.
+    //  9. `[]` Instructions sysvar.
+    //  10. `[writeable]` Relayed order account.
+    //  11. `[]` Fill hook program, if auction has it, followed by `[]`
+    //     buyer wallet account passed to hook.
+    //  12. `[]` Allowlist entry of buyer, if auction has allowlist.
+    //  13. `[writeable]` Marketplace account, if auction has taker fee.
+    //  14. `[writeable]` Buyer profile of buyer, if auction has buyer profiles.
+    //  15. `[writeable]` Price milestones account, if auction has milestones.
+    //  16. `[writeable]` Bid receipt of buyer, if auction has uniform price.
+    MakeRelayedBid {
+        token_amount: u64,
+        max_price: u64,
+        expires_at: UnixTimestamp,
+        nonce: u64,
+    },
+    // Set time returned to the processor instead of `Clock` sysvar when clock
+    // override account is passed to any instruction. Only in builds with
+    // `test-clock` feature, tag is out of regular range to never collide
+    // with production instructions.
+    // Accounts:
+    //  0. `[writeable,signer]` Funding account.
+    //  1. `[]` System account.
+    //  2. `[writeable]` Clock override account.
+    #[cfg(feature = "test-clock")]
+    SetClock {
+        unix_timestamp: UnixTimestamp,
+    },
 }
 
 // Would be nice to use `deku` crate for pack/unpack, but it's not available for `bpf` target.
@@ -76,15 +619,127 @@ impl AuctionInstruction {
         let (&tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
         let (this, rest) = match tag {
             0 => {
+                let (config, rest) = AuctionConfig::unpack(rest)?;
+                Ok((
+                    Self::InitializeAuction {
+                        config: Box::new(config),
+                    },
+                    rest,
+                ))
+            }
+            1 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                let (amount_basis, rest) = unpack_amount_basis(rest)?;
+                let (create_token_customer, rest) = unpack_bool(rest)?;
+                let (tip, rest) = unpack_u64(rest)?;
+                let (merkle_proof, rest) = unpack_merkle_proof(rest)?;
+                Ok((
+                    Self::MakeBid {
+                        token_amount,
+                        amount_basis,
+                        create_token_customer,
+                        tip,
+                        merkle_proof,
+                    },
+                    rest,
+                ))
+            }
+            2 => Ok((Self::WithdrawSOL, rest)),
+            3 => Ok((Self::WithdrawTokens, rest)),
+            4 => Ok((Self::SyncEscrow, rest)),
+            5 => Ok((Self::Poke, rest)),
+            6 => Ok((Self::AddToAllowlist, rest)),
+            7 => Ok((Self::RemoveFromAllowlist, rest)),
+            8 => {
+                let (lamports, rest) = unpack_u64(rest)?;
+                let (expires_at, rest) = unpack_unix_timestamp(rest)?;
+                Ok((
+                    Self::AuthorizeSession {
+                        lamports,
+                        expires_at,
+                    },
+                    rest,
+                ))
+            }
+            9 => Ok((Self::RevokeSession, rest)),
+            10 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                Ok((Self::MakeSessionBid { token_amount }, rest))
+            }
+            11 => {
+                let (lamports, rest) = unpack_u64(rest)?;
+                Ok((Self::DepositFunds { lamports }, rest))
+            }
+            12 => {
+                let (lamports, rest) = unpack_u64(rest)?;
+                Ok((Self::WithdrawFunds { lamports }, rest))
+            }
+            13 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                Ok((Self::MakeDepositBid { token_amount }, rest))
+            }
+            14 => {
                 let (token_amount, rest) = unpack_u64(rest)?;
+                let (quoted_at, rest) = unpack_unix_timestamp(rest)?;
+                Ok((
+                    Self::MakeBidAtTime {
+                        token_amount,
+                        quoted_at,
+                    },
+                    rest,
+                ))
+            }
+            15 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                Ok((Self::InitializeLaunch { token_amount }, rest))
+            }
+            16 => Ok((Self::ProgramInfo, rest)),
+            17 => {
+                let (max_lamports, rest) = unpack_u64(rest)?;
+                let (&count, mut rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                if count == 0 || count as usize > MAX_BUNDLE_SIZE {
+                    return Err(InvalidInstruction.into());
+                }
+                let mut token_amounts = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (token_amount, next) = unpack_u64(rest)?;
+                    token_amounts.push(token_amount);
+                    rest = next;
+                }
+                Ok((
+                    Self::MakeBundleBid {
+                        max_lamports,
+                        token_amounts,
+                    },
+                    rest,
+                ))
+            }
+            18 => {
+                let (max_tokens_per_step, rest) = unpack_u64(rest)?;
+                let (taker_fee_bps, rest) = unpack_u16(rest)?;
+                Ok((
+                    Self::ProposeUpdate {
+                        max_tokens_per_step,
+                        taker_fee_bps,
+                    },
+                    rest,
+                ))
+            }
+            19 => Ok((Self::ApplyUpdate, rest)),
+            20 => Ok((Self::CreateBuyerProfile, rest)),
+            21 => Ok((Self::Settle, rest)),
+            22 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                Ok((Self::ViewQuote { token_amount }, rest))
+            }
+            23 => Ok((Self::ClaimFree, rest)),
+            24 => {
                 let (time_start, rest) = unpack_unix_timestamp(rest)?;
                 let (time_step, rest) = unpack_unix_timestamp(rest)?;
                 let (price_start, rest) = unpack_u64(rest)?;
                 let (price_step, rest) = unpack_u64(rest)?;
-
                 Ok((
-                    Self::InitializeAuction {
-                        token_amount,
+                    Self::UpdateAuction {
                         time_start,
                         time_step,
                         price_start,
@@ -93,12 +748,98 @@ impl AuctionInstruction {
                     rest,
                 ))
             }
-            1 => {
+            25 => {
+                let (&count, mut rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                if count as usize > MAX_PRICE_MILESTONES {
+                    return Err(InvalidInstruction.into());
+                }
+                let mut milestones = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (sold_bps, next) = unpack_u16(rest)?;
+                    let (floor_bps, next) = unpack_u16(next)?;
+                    milestones.push(PriceMilestone {
+                        sold_bps,
+                        floor_bps,
+                    });
+                    rest = next;
+                }
+                Ok((Self::SetPriceMilestones { milestones }, rest))
+            }
+            26 => Ok((Self::EndAuctionEarly, rest)),
+            27 => Ok((Self::BuyNow, rest)),
+            28 => Ok((Self::WithdrawPayment, rest)),
+            29 => Ok((Self::PreviewSettlement, rest)),
+            30 => {
                 let (token_amount, rest) = unpack_u64(rest)?;
-                Ok((Self::MakeBid { token_amount }, rest))
+                let (max_price, rest) = unpack_u64(rest)?;
+                let (expires_at, rest) = unpack_unix_timestamp(rest)?;
+                let (nonce, rest) = unpack_u64(rest)?;
+                Ok((
+                    Self::MakeOrderBid {
+                        token_amount,
+                        max_price,
+                        expires_at,
+                        nonce,
+                    },
+                    rest,
+                ))
+            }
+            31 => {
+                let (&count, mut rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                if count as usize > MAX_PRICE_BREAKPOINTS {
+                    return Err(InvalidInstruction.into());
+                }
+                let mut breakpoints = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let (offset, next) = unpack_unix_timestamp(rest)?;
+                    let (price, next) = unpack_u64(next)?;
+                    breakpoints.push(PriceBreakpoint { offset, price });
+                    rest = next;
+                }
+                Ok((Self::SetPriceSchedule { breakpoints }, rest))
+            }
+            32 => Ok((Self::CreateBidReceipt, rest)),
+            33 => Ok((Self::ClaimRefund, rest)),
+            34 => {
+                let (commitment, rest) = unpack_hash(rest)?;
+                let (escrow, rest) = unpack_u64(rest)?;
+                Ok((Self::CommitBid { commitment, escrow }, rest))
+            }
+            35 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                let (price, rest) = unpack_u64(rest)?;
+                let (salt, rest) = unpack_hash(rest)?;
+                Ok((
+                    Self::RevealBid {
+                        token_amount,
+                        price,
+                        salt,
+                    },
+                    rest,
+                ))
+            }
+            36 => Ok((Self::SettleSealedBids, rest)),
+            37 => Ok((Self::ClaimSealedBid, rest)),
+            38 => {
+                let (token_amount, rest) = unpack_u64(rest)?;
+                let (max_price, rest) = unpack_u64(rest)?;
+                let (expires_at, rest) = unpack_unix_timestamp(rest)?;
+                let (nonce, rest) = unpack_u64(rest)?;
+                Ok((
+                    Self::MakeRelayedBid {
+                        token_amount,
+                        max_price,
+                        expires_at,
+                        nonce,
+                    },
+                    rest,
+                ))
+            }
+            #[cfg(feature = "test-clock")]
+            255 => {
+                let (unix_timestamp, rest) = unpack_unix_timestamp(rest)?;
+                Ok((Self::SetClock { unix_timestamp }, rest))
             }
-            2 => Ok((Self::WithdrawSOL, rest)),
-            3 => Ok((Self::WithdrawTokens, rest)),
             _ => Err(InvalidInstruction),
         }?;
 
@@ -112,32 +853,448 @@ impl AuctionInstruction {
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
         match self {
-            Self::InitializeAuction {
+            Self::InitializeAuction { config } => {
+                buf.push(0);
+                config.pack(&mut buf);
+            }
+            Self::MakeBid {
+                token_amount,
+                amount_basis,
+                create_token_customer,
+                tip,
+                merkle_proof,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.push(*amount_basis as u8);
+                buf.push(*create_token_customer as u8);
+                buf.extend_from_slice(&tip.to_le_bytes());
+                pack_merkle_proof(merkle_proof, &mut buf);
+            }
+            Self::WithdrawSOL => buf.push(2),
+            Self::WithdrawTokens => buf.push(3),
+            Self::SyncEscrow => buf.push(4),
+            Self::Poke => buf.push(5),
+            Self::AddToAllowlist => buf.push(6),
+            Self::RemoveFromAllowlist => buf.push(7),
+            Self::AuthorizeSession {
+                lamports,
+                expires_at,
+            } => {
+                buf.push(8);
+                buf.extend_from_slice(&lamports.to_le_bytes());
+                buf.extend_from_slice(&expires_at.to_le_bytes());
+            }
+            Self::RevokeSession => buf.push(9),
+            Self::MakeSessionBid { token_amount } => {
+                buf.push(10);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+            }
+            Self::DepositFunds { lamports } => {
+                buf.push(11);
+                buf.extend_from_slice(&lamports.to_le_bytes());
+            }
+            Self::WithdrawFunds { lamports } => {
+                buf.push(12);
+                buf.extend_from_slice(&lamports.to_le_bytes());
+            }
+            Self::MakeDepositBid { token_amount } => {
+                buf.push(13);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+            }
+            Self::MakeBidAtTime {
                 token_amount,
+                quoted_at,
+            } => {
+                buf.push(14);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.extend_from_slice(&quoted_at.to_le_bytes());
+            }
+            Self::InitializeLaunch { token_amount } => {
+                buf.push(15);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+            }
+            Self::ProgramInfo => buf.push(16),
+            Self::MakeBundleBid {
+                max_lamports,
+                token_amounts,
+            } => {
+                buf.push(17);
+                buf.extend_from_slice(&max_lamports.to_le_bytes());
+                buf.push(token_amounts.len() as u8);
+                for token_amount in token_amounts {
+                    buf.extend_from_slice(&token_amount.to_le_bytes());
+                }
+            }
+            Self::ProposeUpdate {
+                max_tokens_per_step,
+                taker_fee_bps,
+            } => {
+                buf.push(18);
+                buf.extend_from_slice(&max_tokens_per_step.to_le_bytes());
+                buf.extend_from_slice(&taker_fee_bps.to_le_bytes());
+            }
+            Self::ApplyUpdate => buf.push(19),
+            Self::CreateBuyerProfile => buf.push(20),
+            Self::Settle => buf.push(21),
+            Self::ViewQuote { token_amount } => {
+                buf.push(22);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+            }
+            Self::ClaimFree => buf.push(23),
+            Self::UpdateAuction {
                 time_start,
                 time_step,
                 price_start,
                 price_step,
             } => {
-                buf.push(0);
-                buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.push(24);
                 buf.extend_from_slice(&time_start.to_le_bytes());
                 buf.extend_from_slice(&time_step.to_le_bytes());
                 buf.extend_from_slice(&price_start.to_le_bytes());
                 buf.extend_from_slice(&price_step.to_le_bytes());
             }
-            Self::MakeBid { token_amount } => {
-                buf.push(1);
+            Self::SetPriceMilestones { milestones } => {
+                buf.push(25);
+                buf.push(milestones.len() as u8);
+                for milestone in milestones {
+                    buf.extend_from_slice(&milestone.sold_bps.to_le_bytes());
+                    buf.extend_from_slice(&milestone.floor_bps.to_le_bytes());
+                }
+            }
+            Self::EndAuctionEarly => buf.push(26),
+            Self::BuyNow => buf.push(27),
+            Self::WithdrawPayment => buf.push(28),
+            Self::PreviewSettlement => buf.push(29),
+            Self::MakeOrderBid {
+                token_amount,
+                max_price,
+                expires_at,
+                nonce,
+            } => {
+                buf.push(30);
                 buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.extend_from_slice(&max_price.to_le_bytes());
+                buf.extend_from_slice(&expires_at.to_le_bytes());
+                buf.extend_from_slice(&nonce.to_le_bytes());
+            }
+            Self::SetPriceSchedule { breakpoints } => {
+                buf.push(31);
+                buf.push(breakpoints.len() as u8);
+                for breakpoint in breakpoints {
+                    buf.extend_from_slice(&breakpoint.offset.to_le_bytes());
+                    buf.extend_from_slice(&breakpoint.price.to_le_bytes());
+                }
+            }
+            Self::CreateBidReceipt => buf.push(32),
+            Self::ClaimRefund => buf.push(33),
+            Self::CommitBid { commitment, escrow } => {
+                buf.push(34);
+                buf.extend_from_slice(commitment);
+                buf.extend_from_slice(&escrow.to_le_bytes());
+            }
+            Self::RevealBid {
+                token_amount,
+                price,
+                salt,
+            } => {
+                buf.push(35);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.extend_from_slice(&price.to_le_bytes());
+                buf.extend_from_slice(salt);
+            }
+            Self::SettleSealedBids => buf.push(36),
+            Self::ClaimSealedBid => buf.push(37),
+            Self::MakeRelayedBid {
+                token_amount,
+                max_price,
+                expires_at,
+                nonce,
+            } => {
+                buf.push(38);
+                buf.extend_from_slice(&token_amount.to_le_bytes());
+                buf.extend_from_slice(&max_price.to_le_bytes());
+                buf.extend_from_slice(&expires_at.to_le_bytes());
+                buf.extend_from_slice(&nonce.to_le_bytes());
+            }
+            #[cfg(feature = "test-clock")]
+            Self::SetClock { unix_timestamp } => {
+                buf.push(255);
+                buf.extend_from_slice(&unix_timestamp.to_le_bytes());
             }
-            Self::WithdrawSOL => buf.push(2),
-            Self::WithdrawTokens => buf.push(3),
         };
         buf
     }
 }
 
+impl AuctionConfig {
+    fn pack(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.token_amount.to_le_bytes());
+        buf.extend_from_slice(&self.time_start.to_le_bytes());
+        buf.extend_from_slice(&self.time_step.to_le_bytes());
+        buf.extend_from_slice(&self.price_start.to_le_bytes());
+        buf.extend_from_slice(&self.price_step.to_le_bytes());
+        buf.push(self.assume_prefunded as u8);
+        buf.push(self.escrow_policy as u8);
+        pack_pubkey_option(&self.hook_program, buf);
+        buf.push(self.allowlist_enabled as u8);
+        buf.extend_from_slice(&self.blackout_period.to_le_bytes());
+        buf.extend_from_slice(&self.blackout_offset.to_le_bytes());
+        buf.extend_from_slice(&self.blackout_duration.to_le_bytes());
+        buf.push(self.blackout_pauses_price as u8);
+        buf.extend_from_slice(&self.price_lock_skew.to_le_bytes());
+        pack_finalization_policy(&self.finalization_policy, buf);
+        buf.extend_from_slice(&self.max_tokens_per_step.to_le_bytes());
+        buf.push(self.amount_basis as u8);
+        buf.push(self.price_curve as u8);
+        buf.extend_from_slice(&self.taker_fee_bps.to_le_bytes());
+        buf.extend_from_slice(&self.update_delay.to_le_bytes());
+        buf.push(self.unique_listing as u8);
+        pack_pubkey_option(&self.cosigner, buf);
+        buf.push(self.buyer_profiles as u8);
+        buf.extend_from_slice(&self.min_buyer_auctions.to_le_bytes());
+        buf.push(self.allow_zero_price as u8);
+        buf.extend_from_slice(&self.free_claim_limit.to_le_bytes());
+        pack_pubkey_option(&self.tip_recipient, buf);
+        pack_denied_programs(&self.denied_programs, buf);
+        buf.extend_from_slice(&self.buy_now_price.to_le_bytes());
+        buf.extend_from_slice(&self.merkle_root);
+        buf.extend_from_slice(&self.max_bid_amount.to_le_bytes());
+        pack_pubkey_option(&self.payment_mint, buf);
+        buf.extend_from_slice(&self.price_ceiling.to_le_bytes());
+        buf.push(self.uniform_price as u8);
+        buf.extend_from_slice(&self.commit_end.to_le_bytes());
+        buf.extend_from_slice(&self.reveal_end.to_le_bytes());
+        buf.extend_from_slice(&self.time_end.to_le_bytes());
+        buf.extend_from_slice(&self.free_claim_end.to_le_bytes());
+    }
+
+    fn unpack(input: &[u8]) -> Result<(Self, &[u8]), AuctionError> {
+        let (token_amount, rest) = unpack_u64(input)?;
+        let (time_start, rest) = unpack_unix_timestamp(rest)?;
+        let (time_step, rest) = unpack_unix_timestamp(rest)?;
+        let (price_start, rest) = unpack_u64(rest)?;
+        let (price_step, rest) = unpack_u64(rest)?;
+        let (assume_prefunded, rest) = unpack_bool(rest)?;
+        let (escrow_policy, rest) = unpack_escrow_policy(rest)?;
+        let (hook_program, rest) = unpack_pubkey_option(rest)?;
+        let (allowlist_enabled, rest) = unpack_bool(rest)?;
+        let (blackout_period, rest) = unpack_unix_timestamp(rest)?;
+        let (blackout_offset, rest) = unpack_unix_timestamp(rest)?;
+        let (blackout_duration, rest) = unpack_unix_timestamp(rest)?;
+        let (blackout_pauses_price, rest) = unpack_bool(rest)?;
+        let (price_lock_skew, rest) = unpack_unix_timestamp(rest)?;
+        let (finalization_policy, rest) = unpack_finalization_policy(rest)?;
+        let (max_tokens_per_step, rest) = unpack_u64(rest)?;
+        let (amount_basis, rest) = unpack_amount_basis(rest)?;
+        let (price_curve, rest) = unpack_price_curve(rest)?;
+        let (taker_fee_bps, rest) = unpack_u16(rest)?;
+        let (update_delay, rest) = unpack_unix_timestamp(rest)?;
+        let (unique_listing, rest) = unpack_bool(rest)?;
+        let (cosigner, rest) = unpack_pubkey_option(rest)?;
+        let (buyer_profiles, rest) = unpack_bool(rest)?;
+        let (min_buyer_auctions, rest) = unpack_u64(rest)?;
+        let (allow_zero_price, rest) = unpack_bool(rest)?;
+        let (free_claim_limit, rest) = unpack_u64(rest)?;
+        let (tip_recipient, rest) = unpack_pubkey_option(rest)?;
+        let (denied_programs, rest) = unpack_denied_programs(rest)?;
+        let (buy_now_price, rest) = unpack_u64(rest)?;
+        let (merkle_root, rest) = unpack_hash(rest)?;
+        let (max_bid_amount, rest) = unpack_u64(rest)?;
+        let (payment_mint, rest) = unpack_pubkey_option(rest)?;
+        let (price_ceiling, rest) = unpack_u64(rest)?;
+        let (uniform_price, rest) = unpack_bool(rest)?;
+        let (commit_end, rest) = unpack_unix_timestamp(rest)?;
+        let (reveal_end, rest) = unpack_unix_timestamp(rest)?;
+        let (time_end, rest) = unpack_unix_timestamp(rest)?;
+        let (free_claim_end, rest) = unpack_unix_timestamp(rest)?;
+
+        Ok((
+            Self {
+                token_amount,
+                time_start,
+                time_step,
+                price_start,
+                price_step,
+                assume_prefunded,
+                escrow_policy,
+                hook_program,
+                allowlist_enabled,
+                blackout_period,
+                blackout_offset,
+                blackout_duration,
+                blackout_pauses_price,
+                price_lock_skew,
+                finalization_policy,
+                max_tokens_per_step,
+                amount_basis,
+                price_curve,
+                taker_fee_bps,
+                update_delay,
+                unique_listing,
+                cosigner,
+                buyer_profiles,
+                min_buyer_auctions,
+                allow_zero_price,
+                free_claim_limit,
+                tip_recipient,
+                denied_programs,
+                buy_now_price,
+                merkle_root,
+                max_bid_amount,
+                payment_mint,
+                price_ceiling,
+                uniform_price,
+                commit_end,
+                reveal_end,
+                time_end,
+                free_claim_end,
+            },
+            rest,
+        ))
+    }
+}
+
+fn unpack_bool(input: &[u8]) -> Result<(bool, &[u8]), AuctionError> {
+    let (&value, rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    let value = match value {
+        0 => false,
+        1 => true,
+        _ => return Err(AuctionError::InvalidInstruction),
+    };
+    Ok((value, rest))
+}
+
+fn unpack_escrow_policy(input: &[u8]) -> Result<(EscrowPolicy, &[u8]), AuctionError> {
+    let (&value, rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    let value = EscrowPolicy::from_u8(value).ok_or(AuctionError::InvalidInstruction)?;
+    Ok((value, rest))
+}
+
+fn unpack_amount_basis(input: &[u8]) -> Result<(AmountBasis, &[u8]), AuctionError> {
+    let (&value, rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    let value = AmountBasis::from_u8(value).ok_or(AuctionError::InvalidInstruction)?;
+    Ok((value, rest))
+}
+
+fn unpack_price_curve(input: &[u8]) -> Result<(PriceCurve, &[u8]), AuctionError> {
+    let (&value, rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    let value = PriceCurve::from_u8(value).ok_or(AuctionError::InvalidInstruction)?;
+    Ok((value, rest))
+}
+
+fn pack_finalization_policy(value: &FinalizationPolicy, buf: &mut Vec<u8>) {
+    match *value {
+        FinalizationPolicy::ReturnToSeller => buf.push(0),
+        FinalizationPolicy::Burn => buf.push(1),
+        FinalizationPolicy::SendTo(ref owner) => {
+            buf.push(2);
+            buf.extend_from_slice(owner.as_ref());
+        }
+    }
+}
+
+fn unpack_finalization_policy(input: &[u8]) -> Result<(FinalizationPolicy, &[u8]), AuctionError> {
+    match input.split_first() {
+        Some((&0, rest)) => Ok((FinalizationPolicy::ReturnToSeller, rest)),
+        Some((&1, rest)) => Ok((FinalizationPolicy::Burn, rest)),
+        Some((&2, rest)) if rest.len() >= 32 => {
+            let (owner, rest) = rest.split_at(32);
+            Ok((FinalizationPolicy::SendTo(Pubkey::new(owner)), rest))
+        }
+        _ => Err(AuctionError::InvalidInstruction),
+    }
+}
+
+fn pack_pubkey_option(value: &Option<Pubkey>, buf: &mut Vec<u8>) {
+    match *value {
+        Some(ref key) => {
+            buf.push(1);
+            buf.extend_from_slice(key.as_ref());
+        }
+        None => buf.push(0),
+    }
+}
+
+fn unpack_pubkey_option(input: &[u8]) -> Result<(Option<Pubkey>, &[u8]), AuctionError> {
+    match input.split_first() {
+        Some((&0, rest)) => Ok((None, rest)),
+        Some((&1, rest)) if rest.len() >= 32 => {
+            let (key, rest) = rest.split_at(32);
+            Ok((Some(Pubkey::new(key)), rest))
+        }
+        _ => Err(AuctionError::InvalidInstruction),
+    }
+}
+
+fn pack_denied_programs(value: &[Pubkey], buf: &mut Vec<u8>) {
+    buf.push(value.len() as u8);
+    for program in value {
+        buf.extend_from_slice(program.as_ref());
+    }
+}
+
+fn unpack_denied_programs(input: &[u8]) -> Result<(Vec<Pubkey>, &[u8]), AuctionError> {
+    let (&count, mut rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    if count as usize > MAX_DENIED_PROGRAMS || rest.len() < count as usize * 32 {
+        return Err(AuctionError::InvalidInstruction);
+    }
+    let mut programs = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (key, next) = rest.split_at(32);
+        programs.push(Pubkey::new(key));
+        rest = next;
+    }
+    Ok((programs, rest))
+}
+
+fn unpack_hash(input: &[u8]) -> Result<([u8; 32], &[u8]), AuctionError> {
+    if input.len() < 32 {
+        return Err(AuctionError::InvalidInstruction);
+    }
+    let (value, rest) = input.split_at(32);
+    let mut hash = [0; 32];
+    hash.copy_from_slice(value);
+    Ok((hash, rest))
+}
+
+fn pack_merkle_proof(value: &[[u8; 32]], buf: &mut Vec<u8>) {
+    buf.push(value.len() as u8);
+    for hash in value {
+        buf.extend_from_slice(hash);
+    }
+}
+
+fn unpack_merkle_proof(input: &[u8]) -> Result<(Vec<[u8; 32]>, &[u8]), AuctionError> {
+    let (&count, mut rest) = input
+        .split_first()
+        .ok_or(AuctionError::InvalidInstruction)?;
+    if count as usize > MAX_MERKLE_PROOF_LEN {
+        return Err(AuctionError::InvalidInstruction);
+    }
+    let mut proof = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let (hash, next) = unpack_hash(rest)?;
+        proof.push(hash);
+        rest = next;
+    }
+    Ok((proof, rest))
+}
+
 fn unpack_unix_timestamp(input: &[u8]) -> Result<(UnixTimestamp, &[u8]), AuctionError> {
+    if input.len() < 8 {
+        return Err(AuctionError::InvalidInstruction);
+    }
     let (value, rest) = input.split_at(8);
     Ok((
         value
@@ -149,7 +1306,18 @@ fn unpack_unix_timestamp(input: &[u8]) -> Result<(UnixTimestamp, &[u8]), Auction
     ))
 }
 
+fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), AuctionError> {
+    if input.len() < 2 {
+        return Err(AuctionError::InvalidInstruction);
+    }
+    let (value, rest) = input.split_at(2);
+    Ok((u16::from_le_bytes([value[0], value[1]]), rest))
+}
+
 fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), AuctionError> {
+    if input.len() < 8 {
+        return Err(AuctionError::InvalidInstruction);
+    }
     let (value, rest) = input.split_at(8);
     Ok((
         value
@@ -161,113 +1329,1306 @@ fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), AuctionError> {
     ))
 }
 
+/// Token account and its owner/delegate for tokens transferred to auction,
+/// not used by prefunded auction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenSource {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+}
+
 pub fn initialize_auction(
     auction_pubkey: &Pubkey,
     auction_authority_pubkey: &Pubkey,
     funding_pubkey: &Pubkey,
     token_pubkey: &Pubkey,
-    token_source_pubkey: &Pubkey,
-    token_auction_pubkey: &Pubkey,
     token_auction_owner_info: &Pubkey,
-    token_authority_pubkey: &Pubkey,
+    token_source: Option<&TokenSource>,
+    config: &AuctionConfig,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+        ("funding", funding_pubkey),
+        ("token", token_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+    ])?;
+    // Funding account takes place of source accounts of prefunded auction
+    let token_source = match token_source {
+        Some(token_source) => {
+            check_pubkeys(&[
+                ("token_source", &token_source.account),
+                ("token_authority", &token_source.authority),
+            ])?;
+            *token_source
+        }
+        None if config.assume_prefunded => TokenSource {
+            account: *funding_pubkey,
+            authority: *funding_pubkey,
+        },
+        None => return Err(BuilderError::DefaultPubkey("token_source")),
+    };
+    check_amount("token_amount", config.token_amount)?;
+    if config.time_step <= 0 {
+        return Err(BuilderError::InvalidTimeStep);
+    }
+    if config.denied_programs.len() > MAX_DENIED_PROGRAMS {
+        return Err(BuilderError::TooManyDeniedPrograms);
+    }
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new(*auction_pubkey, false),
+        AccountMeta::new_readonly(*auction_authority_pubkey, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*funding_pubkey, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*token_pubkey, false),
+        AccountMeta::new(token_source.account, false),
+        AccountMeta::new(
+            get_associated_token_address(token_auction_owner_info, token_pubkey),
+            false,
+        ),
+        AccountMeta::new(*token_auction_owner_info, false),
+        AccountMeta::new(token_source.authority, true),
+        AccountMeta::new(admin_log_pubkey, false),
+    ];
+    if config.unique_listing {
+        let (listing_marker_pubkey, _) =
+            ListingMarker::find_address(auction_authority_pubkey, token_pubkey);
+        accounts.push(AccountMeta::new(listing_marker_pubkey, false));
+    }
+    if let Some(payment_mint) = &config.payment_mint {
+        accounts.push(AccountMeta::new_readonly(*payment_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(token_auction_owner_info, payment_mint),
+            false,
+        ));
+    }
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::InitializeAuction {
+            config: Box::new(config.clone()),
+        }
+        .pack(),
+    })
+}
+
+pub fn make_bid(
+    auction_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
     token_amount: u64,
-    time_start: UnixTimestamp,
-    time_step: UnixTimestamp,
-    price_start: u64,
-    price_step: u64,
-) -> Result<Instruction, ProgramError> {
+    options: &BidOptions,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("funding", funding_pubkey)])?;
+    bid_accounts.check()?;
+    check_amount("token_amount", token_amount)?;
+    if options.merkle_proof.len() > MAX_MERKLE_PROOF_LEN {
+        return Err(BuilderError::MerkleProofTooLong);
+    }
+    let mut accounts = vec![
+        AccountMeta::new(*auction_pubkey, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*funding_pubkey, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend_from_slice(&bid_accounts.token_metas());
+    if let Some(token_customer_wallet) = options.token_customer_wallet {
+        accounts.push(AccountMeta::new_readonly(token_customer_wallet, false));
+        accounts.push(AccountMeta::new_readonly(
+            spl_associated_token_account::id(),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    }
+    bid_accounts.push_optional(funding_pubkey, &mut accounts);
+    if let Some(payment) = &options.payment {
+        accounts.push(AccountMeta::new_readonly(payment.mint, false));
+        accounts.push(AccountMeta::new(payment.source, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(&bid_accounts.token_auction_owner, &payment.mint),
+            false,
+        ));
+    }
+    if let Some(tip_recipient) = options.tip_recipient {
+        accounts.push(AccountMeta::new(tip_recipient, false));
+    }
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeBid {
+            token_amount,
+            amount_basis: options.amount_basis,
+            create_token_customer: options.token_customer_wallet.is_some(),
+            tip: options.tip,
+            merkle_proof: options.merkle_proof.clone(),
+        }
+        .pack(),
+    })
+}
+
+pub fn buy_now(
+    auction_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
+    payment: Option<&TokenPayment>,
+) -> Result<Instruction, BuilderError> {
+    let mut instruction = make_bid(
+        auction_pubkey,
+        funding_pubkey,
+        bid_accounts,
+        1,
+        &BidOptions {
+            payment: payment.copied(),
+            ..BidOptions::default()
+        },
+    )?;
+    instruction.data = AuctionInstruction::BuyNow.pack();
+    Ok(instruction)
+}
+
+/// Payment accounts of bid in auction paid with SPL token.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenPayment {
+    pub mint: Pubkey,
+    // Payment token account owned by funding account.
+    pub source: Pubkey,
+}
+
+/// Token accounts of bid together with optional accounts, required only by
+/// auction with the corresponding feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BidAccounts {
+    pub token: Pubkey,
+    pub token_auction: Pubkey,
+    pub token_auction_owner: Pubkey,
+    pub token_customer: Pubkey,
+    pub hook_program: Option<Pubkey>,
+    pub allowlist_entry: Option<Pubkey>,
+    pub marketplace: Option<Pubkey>,
+    pub buyer_profile: Option<Pubkey>,
+    pub price_milestones: Option<Pubkey>,
+    pub bid_receipt: Option<Pubkey>,
+}
+
+impl BidAccounts {
+    fn check(&self) -> Result<(), BuilderError> {
+        check_pubkeys(&[
+            ("token", &self.token),
+            ("token_auction", &self.token_auction),
+            ("token_auction_owner", &self.token_auction_owner),
+            ("token_customer", &self.token_customer),
+        ])
+    }
+
+    fn token_metas(&self) -> [AccountMeta; 4] {
+        [
+            AccountMeta::new_readonly(self.token, false),
+            AccountMeta::new(self.token_auction, false),
+            AccountMeta::new(self.token_auction_owner, false),
+            AccountMeta::new(self.token_customer, false),
+        ]
+    }
+
+    // Hook program gets buyer `wallet` as its account.
+    fn push_optional(&self, wallet: &Pubkey, accounts: &mut Vec<AccountMeta>) {
+        if let Some(hook_program) = self.hook_program {
+            accounts.push(AccountMeta::new_readonly(hook_program, false));
+            accounts.push(AccountMeta::new_readonly(*wallet, false));
+        }
+        if let Some(allowlist_entry) = self.allowlist_entry {
+            accounts.push(AccountMeta::new_readonly(allowlist_entry, false));
+        }
+        if let Some(marketplace) = self.marketplace {
+            accounts.push(AccountMeta::new(marketplace, false));
+        }
+        if let Some(buyer_profile) = self.buyer_profile {
+            accounts.push(AccountMeta::new(buyer_profile, false));
+        }
+        if let Some(price_milestones) = self.price_milestones {
+            accounts.push(AccountMeta::new(price_milestones, false));
+        }
+        if let Some(bid_receipt) = self.bid_receipt {
+            accounts.push(AccountMeta::new(bid_receipt, false));
+        }
+    }
+}
+
+/// Optional parameters of `make_bid`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BidOptions {
+    pub amount_basis: AmountBasis,
+    // Wallet of customer token account, account is created if not exists yet.
+    pub token_customer_wallet: Option<Pubkey>,
+    pub tip: u64,
+    pub tip_recipient: Option<Pubkey>,
+    pub merkle_proof: Vec<[u8; 32]>,
+    pub payment: Option<TokenPayment>,
+}
+
+/// Auction authority of withdrawals and its cosigner, if auction has it.
+/// Authority owned by SPL token multisig does not sign, `multisig_signers`
+/// sign instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuthoritySigners {
+    pub authority: Pubkey,
+    pub cosigner: Option<Pubkey>,
+    pub multisig_signers: Vec<Pubkey>,
+}
+
+impl AuthoritySigners {
+    pub fn new(authority: Pubkey) -> Self {
+        Self {
+            authority,
+            ..Self::default()
+        }
+    }
+
+    fn authority_meta(&self) -> AccountMeta {
+        AccountMeta::new_readonly(self.authority, self.multisig_signers.is_empty())
+    }
+
+    fn push_signers(&self, accounts: &mut Vec<AccountMeta>) {
+        if let Some(cosigner) = self.cosigner {
+            accounts.push(AccountMeta::new_readonly(cosigner, true));
+        }
+        for signer in &self.multisig_signers {
+            accounts.push(AccountMeta::new_readonly(*signer, true));
+        }
+    }
+}
+
+pub fn withdraw_payment(
+    auction_pubkey: &Pubkey,
+    signers: &AuthoritySigners,
+    token_pubkey: &Pubkey,
+    payment_mint_pubkey: &Pubkey,
+    token_auction_owner_info: &Pubkey,
+    payment_dest_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", &signers.authority),
+        ("token", token_pubkey),
+        ("payment_mint", payment_mint_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+        ("payment_dest", payment_dest_pubkey),
+    ])?;
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*auction_pubkey, false),
+        signers.authority_meta(),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*token_pubkey, false),
+        AccountMeta::new_readonly(*payment_mint_pubkey, false),
+        AccountMeta::new(
+            get_associated_token_address(token_auction_owner_info, payment_mint_pubkey),
+            false,
+        ),
+        AccountMeta::new_readonly(*token_auction_owner_info, false),
+        AccountMeta::new(*payment_dest_pubkey, false),
+        AccountMeta::new(admin_log_pubkey, false),
+    ];
+    signers.push_signers(&mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::WithdrawPayment.pack(),
+    })
+}
+
+pub fn preview_settlement(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    token_pubkey: &Pubkey,
+    token_auction_owner_info: &Pubkey,
+    payment_mint_pubkey: Option<&Pubkey>,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("wallet", wallet_pubkey),
+        ("token", token_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+    ])?;
+    let (settlement_preview_pubkey, _) = SettlementPreview::find_address(wallet_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*auction_pubkey, false),
+        AccountMeta::new_readonly(
+            get_associated_token_address(token_auction_owner_info, token_pubkey),
+            false,
+        ),
+        AccountMeta::new_readonly(*token_auction_owner_info, false),
+        AccountMeta::new(*wallet_pubkey, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(settlement_preview_pubkey, false),
+    ];
+    if let Some(payment_mint_pubkey) = payment_mint_pubkey {
+        accounts.push(AccountMeta::new_readonly(
+            get_associated_token_address(token_auction_owner_info, payment_mint_pubkey),
+            false,
+        ));
+    }
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::PreviewSettlement.pack(),
+    })
+}
+
+/// Append instructions sysvar to bid instruction, required when auction has
+/// denied programs.
+pub fn add_instructions_sysvar(instruction: &mut Instruction) {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+}
+
+// Instruction passed to fill hook program after each successful bid.
+// Accounts:
+//  0. `[]` Auction account.
+//  1. `[]` Buyer wallet account.
+// Data: filled token amount (u64 LE) and price per token (u64 LE).
+pub fn fill_hook(
+    hook_program_pubkey: &Pubkey,
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    token_amount: u64,
+    price: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(16);
+    data.extend_from_slice(&token_amount.to_le_bytes());
+    data.extend_from_slice(&price.to_le_bytes());
+    Instruction {
+        program_id: *hook_program_pubkey,
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*wallet_pubkey, false),
+        ],
+        data,
+    }
+}
+
+pub fn withdraw_sol(
+    auction_pubkey: &Pubkey,
+    signers: &AuthoritySigners,
+    token_pubkey: &Pubkey,
+    token_auction_owner_info: &Pubkey,
+    dest_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", &signers.authority),
+        ("token", token_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+        ("dest", dest_pubkey),
+    ])?;
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*auction_pubkey, false),
+        signers.authority_meta(),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*token_pubkey, false),
+        AccountMeta::new(*token_auction_owner_info, false),
+        AccountMeta::new(*dest_pubkey, false),
+        AccountMeta::new(admin_log_pubkey, false),
+    ];
+    signers.push_signers(&mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::WithdrawSOL.pack(),
+    })
+}
+
+pub fn withdraw_tokens(
+    auction_pubkey: &Pubkey,
+    signers: &AuthoritySigners,
+    token_pubkey: &Pubkey,
+    token_auction_owner_info: &Pubkey,
+    token_dest_pubkey: &Pubkey,
+    listing_rent_payer_pubkey: Option<&Pubkey>,
+    sealed: bool,
+) -> Result<Instruction, BuilderError> {
+    // Authority and destination are not required by every finalization policy
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("token", token_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+    ])?;
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*auction_pubkey, false),
+        signers.authority_meta(),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*token_pubkey, false),
+        AccountMeta::new(
+            get_associated_token_address(token_auction_owner_info, token_pubkey),
+            false,
+        ),
+        AccountMeta::new_readonly(*token_auction_owner_info, false),
+        AccountMeta::new(*token_dest_pubkey, false),
+        AccountMeta::new(admin_log_pubkey, false),
+    ];
+    if let Some(listing_rent_payer_pubkey) = listing_rent_payer_pubkey {
+        let (listing_marker_pubkey, _) =
+            ListingMarker::find_address(&signers.authority, token_pubkey);
+        accounts.push(AccountMeta::new(listing_marker_pubkey, false));
+        accounts.push(AccountMeta::new(*listing_rent_payer_pubkey, false));
+    }
+    if sealed {
+        let (sealed_book_pubkey, _) = SealedBook::find_address(auction_pubkey);
+        accounts.push(AccountMeta::new_readonly(sealed_book_pubkey, false));
+    }
+    signers.push_signers(&mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::WithdrawTokens.pack(),
+    })
+}
+
+pub fn sync_escrow(
+    auction_pubkey: &Pubkey,
+    token_auction_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("token_auction", token_auction_pubkey),
+    ])?;
     Ok(Instruction {
         program_id: crate::id(),
         accounts: vec![
             AccountMeta::new(*auction_pubkey, false),
-            AccountMeta::new_readonly(*auction_authority_pubkey, false),
+            AccountMeta::new_readonly(*token_auction_pubkey, false),
+        ],
+        data: AuctionInstruction::SyncEscrow.pack(),
+    })
+}
+
+pub fn poke(auction_pubkey: &Pubkey) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey)])?;
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![AccountMeta::new(*auction_pubkey, false)],
+        data: AuctionInstruction::Poke.pack(),
+    })
+}
+
+pub fn add_to_allowlist(
+    auction_pubkey: &Pubkey,
+    auction_authority_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+        ("funding", funding_pubkey),
+        ("wallet", wallet_pubkey),
+    ])?;
+    let (allowlist_entry_pubkey, _) = AllowlistEntry::find_address(auction_pubkey, wallet_pubkey);
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
             AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new(*funding_pubkey, false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(*token_pubkey, false),
-            AccountMeta::new(*token_source_pubkey, false),
-            AccountMeta::new(*token_auction_pubkey, false),
-            AccountMeta::new(*token_auction_owner_info, false),
-            AccountMeta::new(*token_authority_pubkey, true),
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new(allowlist_entry_pubkey, false),
+            AccountMeta::new_readonly(*wallet_pubkey, false),
+            AccountMeta::new(admin_log_pubkey, false),
         ],
-        data: AuctionInstruction::InitializeAuction {
-            token_amount,
-            time_start,
-            time_step,
-            price_start,
-            price_step,
+        data: AuctionInstruction::AddToAllowlist.pack(),
+    })
+}
+
+pub fn remove_from_allowlist(
+    auction_pubkey: &Pubkey,
+    auction_authority_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    rent_payer_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+        ("wallet", wallet_pubkey),
+        ("rent_payer", rent_payer_pubkey),
+    ])?;
+    let (allowlist_entry_pubkey, _) = AllowlistEntry::find_address(auction_pubkey, wallet_pubkey);
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new(allowlist_entry_pubkey, false),
+            AccountMeta::new_readonly(*wallet_pubkey, false),
+            AccountMeta::new(*rent_payer_pubkey, false),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::RemoveFromAllowlist.pack(),
+    })
+}
+
+pub fn authorize_session(
+    wallet_pubkey: &Pubkey,
+    session_key_pubkey: &Pubkey,
+    lamports: u64,
+    expires_at: UnixTimestamp,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("wallet", wallet_pubkey),
+        ("session_key", session_key_pubkey),
+    ])?;
+    let (session_pubkey, _) = Session::find_address(wallet_pubkey, session_key_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(session_pubkey, false),
+            AccountMeta::new_readonly(*session_key_pubkey, false),
+        ],
+        data: AuctionInstruction::AuthorizeSession {
+            lamports,
+            expires_at,
         }
         .pack(),
     })
 }
 
-pub fn make_bid(
+pub fn revoke_session(
+    wallet_pubkey: &Pubkey,
+    session_key_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("wallet", wallet_pubkey),
+        ("session_key", session_key_pubkey),
+    ])?;
+    let (session_pubkey, _) = Session::find_address(wallet_pubkey, session_key_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new(session_pubkey, false),
+        ],
+        data: AuctionInstruction::RevokeSession.pack(),
+    })
+}
+
+pub fn make_session_bid(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    session_key_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
+    token_amount: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("wallet", wallet_pubkey),
+        ("session_key", session_key_pubkey),
+    ])?;
+    bid_accounts.check()?;
+    check_amount("token_amount", token_amount)?;
+    let (session_pubkey, _) = Session::find_address(wallet_pubkey, session_key_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new(*auction_pubkey, false),
+        AccountMeta::new(session_pubkey, false),
+        AccountMeta::new_readonly(*session_key_pubkey, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend_from_slice(&bid_accounts.token_metas());
+    bid_accounts.push_optional(wallet_pubkey, &mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeSessionBid { token_amount }.pack(),
+    })
+}
+
+pub fn deposit_funds(wallet_pubkey: &Pubkey, lamports: u64) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("wallet", wallet_pubkey)])?;
+    check_amount("lamports", lamports)?;
+    let (deposit_pubkey, _) = Deposit::find_address(wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(deposit_pubkey, false),
+        ],
+        data: AuctionInstruction::DepositFunds { lamports }.pack(),
+    })
+}
+
+pub fn withdraw_funds(wallet_pubkey: &Pubkey, lamports: u64) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("wallet", wallet_pubkey)])?;
+    check_amount("lamports", lamports)?;
+    let (deposit_pubkey, _) = Deposit::find_address(wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new(deposit_pubkey, false),
+        ],
+        data: AuctionInstruction::WithdrawFunds { lamports }.pack(),
+    })
+}
+
+pub fn make_deposit_bid(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
+    token_amount: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("wallet", wallet_pubkey)])?;
+    bid_accounts.check()?;
+    check_amount("token_amount", token_amount)?;
+    let (deposit_pubkey, _) = Deposit::find_address(wallet_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new(*auction_pubkey, false),
+        AccountMeta::new(deposit_pubkey, false),
+        AccountMeta::new_readonly(*wallet_pubkey, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend_from_slice(&bid_accounts.token_metas());
+    bid_accounts.push_optional(wallet_pubkey, &mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeDepositBid { token_amount }.pack(),
+    })
+}
+
+/// Bid instruction for purchase order signed by `order.buyer`, should follow
+/// ed25519 program instruction with buyer signature of `order.message()`.
+/// Marketplace account is not used, auction with taker fee does not accept
+/// order bids.
+pub fn make_order_bid(
+    order: &PurchaseOrder,
+    bid_accounts: &BidAccounts,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", &order.auction), ("buyer", &order.buyer)])?;
+    bid_accounts.check()?;
+    check_amount("token_amount", order.token_amount)?;
+    let (deposit_pubkey, _) = Deposit::find_address(&order.buyer);
+    let mut accounts = vec![
+        AccountMeta::new(order.auction, false),
+        AccountMeta::new(deposit_pubkey, false),
+        AccountMeta::new_readonly(order.buyer, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend_from_slice(&bid_accounts.token_metas());
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+    BidAccounts {
+        marketplace: None,
+        ..*bid_accounts
+    }
+    .push_optional(&order.buyer, &mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeOrderBid {
+            token_amount: order.token_amount,
+            max_price: order.max_price,
+            expires_at: order.expires_at,
+            nonce: order.nonce,
+        }
+        .pack(),
+    })
+}
+
+/// Bid instruction for relayed purchase order signed by `order.buyer`, should
+/// follow ed25519 program instruction with buyer signature of
+/// `order.message()`.
+pub fn make_relayed_bid(
+    order: &PurchaseOrder,
+    relayer_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", &order.auction),
+        ("buyer", &order.buyer),
+        ("relayer", relayer_pubkey),
+    ])?;
+    bid_accounts.check()?;
+    check_amount("token_amount", order.token_amount)?;
+    let (relayed_order_pubkey, _) = RelayedOrder::find_address(&order.buyer, order.nonce);
+    let mut accounts = vec![
+        AccountMeta::new(order.auction, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*relayer_pubkey, true),
+        AccountMeta::new_readonly(order.buyer, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend_from_slice(&bid_accounts.token_metas());
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+    accounts.push(AccountMeta::new(relayed_order_pubkey, false));
+    bid_accounts.push_optional(&order.buyer, &mut accounts);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeRelayedBid {
+            token_amount: order.token_amount,
+            max_price: order.max_price,
+            expires_at: order.expires_at,
+            nonce: order.nonce,
+        }
+        .pack(),
+    })
+}
+
+pub fn make_bid_at_time(
+    auction_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    bid_accounts: &BidAccounts,
+    token_amount: u64,
+    quoted_at: UnixTimestamp,
+    payment: Option<&TokenPayment>,
+) -> Result<Instruction, BuilderError> {
+    let mut instruction = make_bid(
+        auction_pubkey,
+        funding_pubkey,
+        bid_accounts,
+        token_amount,
+        &BidOptions {
+            payment: payment.copied(),
+            ..BidOptions::default()
+        },
+    )?;
+    instruction.data = AuctionInstruction::MakeBidAtTime {
+        token_amount,
+        quoted_at,
+    }
+    .pack();
+    Ok(instruction)
+}
+
+pub fn initialize_launch(
     auction_pubkey: &Pubkey,
     funding_pubkey: &Pubkey,
     token_pubkey: &Pubkey,
     token_auction_pubkey: &Pubkey,
     token_auction_owner_info: &Pubkey,
-    token_customer_pubkey: &Pubkey,
+    mint_authority_pubkey: &Pubkey,
     token_amount: u64,
-) -> Result<Instruction, ProgramError> {
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("funding", funding_pubkey),
+        ("token", token_pubkey),
+        ("token_auction", token_auction_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+        ("mint_authority", mint_authority_pubkey),
+    ])?;
+    check_amount("token_amount", token_amount)?;
     Ok(Instruction {
         program_id: crate::id(),
         accounts: vec![
-            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_pubkey, true),
             AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new(*funding_pubkey, false),
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
             AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(*token_pubkey, false),
+            AccountMeta::new(*token_pubkey, false),
             AccountMeta::new(*token_auction_pubkey, false),
             AccountMeta::new(*token_auction_owner_info, false),
-            AccountMeta::new(*token_customer_pubkey, false),
+            AccountMeta::new_readonly(*mint_authority_pubkey, true),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
         ],
-        data: AuctionInstruction::MakeBid { token_amount }.pack(),
+        data: AuctionInstruction::InitializeLaunch { token_amount }.pack(),
     })
 }
 
-pub fn withdraw_sol(
+pub fn program_info() -> Result<Instruction, BuilderError> {
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![],
+        data: AuctionInstruction::ProgramInfo.pack(),
+    })
+}
+
+// Single fill of `make_bundle_bid`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BundleFill {
+    pub auction: Pubkey,
+    pub bid_accounts: BidAccounts,
+    pub token_amount: u64,
+}
+
+pub fn make_bundle_bid(
+    funding_pubkey: &Pubkey,
+    fills: &[BundleFill],
+    max_lamports: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("funding", funding_pubkey)])?;
+    if fills.is_empty() || fills.len() > MAX_BUNDLE_SIZE {
+        return Err(BuilderError::InvalidBundleSize);
+    }
+    let mut accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*funding_pubkey, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for fill in fills {
+        check_pubkeys(&[("auction", &fill.auction)])?;
+        fill.bid_accounts.check()?;
+        check_amount("token_amount", fill.token_amount)?;
+        accounts.push(AccountMeta::new(fill.auction, false));
+        accounts.extend_from_slice(&fill.bid_accounts.token_metas());
+        fill.bid_accounts
+            .push_optional(funding_pubkey, &mut accounts);
+    }
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::MakeBundleBid {
+            max_lamports,
+            token_amounts: fills.iter().map(|fill| fill.token_amount).collect(),
+        }
+        .pack(),
+    })
+}
+
+pub fn propose_update(
     auction_pubkey: &Pubkey,
     auction_authority_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    max_tokens_per_step: u64,
+    taker_fee_bps: u16,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+        ("funding", funding_pubkey),
+    ])?;
+    let (pending_update_pubkey, _) = PendingUpdate::find_address(auction_pubkey);
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new(pending_update_pubkey, false),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::ProposeUpdate {
+            max_tokens_per_step,
+            taker_fee_bps,
+        }
+        .pack(),
+    })
+}
+
+pub fn apply_update(
+    auction_pubkey: &Pubkey,
+    rent_payer_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("rent_payer", rent_payer_pubkey),
+    ])?;
+    let (pending_update_pubkey, _) = PendingUpdate::find_address(auction_pubkey);
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new(pending_update_pubkey, false),
+            AccountMeta::new(*rent_payer_pubkey, false),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::ApplyUpdate.pack(),
+    })
+}
+
+pub fn create_buyer_profile(wallet_pubkey: &Pubkey) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("wallet", wallet_pubkey)])?;
+    let (buyer_profile_pubkey, _) = BuyerProfile::find_address(wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(buyer_profile_pubkey, false),
+        ],
+        data: AuctionInstruction::CreateBuyerProfile.pack(),
+    })
+}
+
+pub fn settle(
+    auction_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("funding", funding_pubkey)])?;
+    let (settlement_summary_pubkey, _) = SettlementSummary::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new(settlement_summary_pubkey, false),
+        ],
+        data: AuctionInstruction::Settle.pack(),
+    })
+}
+
+pub fn view_quote(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    token_amount: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("wallet", wallet_pubkey)])?;
+    check_amount("token_amount", token_amount)?;
+    let (quote_pubkey, _) = Quote::find_address(wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(quote_pubkey, false),
+        ],
+        data: AuctionInstruction::ViewQuote { token_amount }.pack(),
+    })
+}
+
+pub fn claim_free(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
     token_pubkey: &Pubkey,
+    token_auction_pubkey: &Pubkey,
     token_auction_owner_info: &Pubkey,
-    dest_pubkey: &Pubkey,
-) -> Result<Instruction, ProgramError> {
+    token_customer_pubkey: &Pubkey,
+    allowlist_entry_pubkey: Option<&Pubkey>,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("wallet", wallet_pubkey),
+        ("token", token_pubkey),
+        ("token_auction", token_auction_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+        ("token_customer", token_customer_pubkey),
+    ])?;
+    let (free_claim_pubkey, _) = FreeClaim::find_address(auction_pubkey, wallet_pubkey);
+    let mut accounts = vec![
+        AccountMeta::new(*auction_pubkey, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*wallet_pubkey, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*token_pubkey, false),
+        AccountMeta::new(*token_auction_pubkey, false),
+        AccountMeta::new_readonly(*token_auction_owner_info, false),
+        AccountMeta::new(*token_customer_pubkey, false),
+        AccountMeta::new(free_claim_pubkey, false),
+    ];
+    if let Some(allowlist_entry_pubkey) = allowlist_entry_pubkey {
+        accounts.push(AccountMeta::new_readonly(*allowlist_entry_pubkey, false));
+    }
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AuctionInstruction::ClaimFree.pack(),
+    })
+}
+
+pub fn update_auction(
+    auction_pubkey: &Pubkey,
+    auction_authority_pubkey: &Pubkey,
+    time_start: UnixTimestamp,
+    time_step: UnixTimestamp,
+    price_start: u64,
+    price_step: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+    ])?;
+    if time_step <= 0 {
+        return Err(BuilderError::InvalidTimeStep);
+    }
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
     Ok(Instruction {
         program_id: crate::id(),
         accounts: vec![
-            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::UpdateAuction {
+            time_start,
+            time_step,
+            price_start,
+            price_step,
+        }
+        .pack(),
+    })
+}
+
+pub fn set_price_milestones(
+    auction_pubkey: &Pubkey,
+    auction_authority_pubkey: &Pubkey,
+    funding_pubkey: &Pubkey,
+    milestones: &[PriceMilestone],
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+        ("funding", funding_pubkey),
+    ])?;
+    if !PriceMilestones::is_valid(milestones) {
+        return Err(BuilderError::InvalidPriceMilestones);
+    }
+    let (price_milestones_pubkey, _) = PriceMilestones::find_address(auction_pubkey);
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*auction_pubkey, false),
             AccountMeta::new_readonly(*auction_authority_pubkey, true),
             AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new_readonly(*token_pubkey, false),
-            AccountMeta::new(*token_auction_owner_info, false),
-            AccountMeta::new(*dest_pubkey, false),
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new(price_milestones_pubkey, false),
+            AccountMeta::new(admin_log_pubkey, false),
         ],
-        data: AuctionInstruction::WithdrawSOL.pack(),
+        data: AuctionInstruction::SetPriceMilestones {
+            milestones: milestones.to_vec(),
+        }
+        .pack(),
     })
 }
 
-pub fn withdraw_tokens(
+pub fn set_price_schedule(
     auction_pubkey: &Pubkey,
     auction_authority_pubkey: &Pubkey,
+    breakpoints: &[PriceBreakpoint],
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+    ])?;
+    if !PriceSchedule::is_valid(breakpoints) {
+        return Err(BuilderError::InvalidPriceSchedule);
+    }
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::SetPriceSchedule {
+            breakpoints: breakpoints.to_vec(),
+        }
+        .pack(),
+    })
+}
+
+pub fn create_bid_receipt(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("wallet", wallet_pubkey)])?;
+    let (bid_receipt_pubkey, _) = BidReceipt::find_address(auction_pubkey, wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(bid_receipt_pubkey, false),
+        ],
+        data: AuctionInstruction::CreateBidReceipt.pack(),
+    })
+}
+
+pub fn claim_refund(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    token_auction_owner_info: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("wallet", wallet_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+    ])?;
+    let (bid_receipt_pubkey, _) = BidReceipt::find_address(auction_pubkey, wallet_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new(*wallet_pubkey, false),
+            AccountMeta::new(bid_receipt_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(*token_auction_owner_info, false),
+        ],
+        data: AuctionInstruction::ClaimRefund.pack(),
+    })
+}
+
+pub fn commit_bid(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    commitment: &[u8; 32],
+    escrow: u64,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("wallet", wallet_pubkey)])?;
+    check_amount("escrow", escrow)?;
+    let (sealed_bid_pubkey, _) = SealedBid::find_address(auction_pubkey, wallet_pubkey);
+    let (sealed_book_pubkey, _) = SealedBook::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new(*wallet_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(sealed_bid_pubkey, false),
+            AccountMeta::new(sealed_book_pubkey, false),
+        ],
+        data: AuctionInstruction::CommitBid {
+            commitment: *commitment,
+            escrow,
+        }
+        .pack(),
+    })
+}
+
+pub fn reveal_bid(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
+    token_amount: u64,
+    price: u64,
+    salt: &[u8; 32],
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey), ("wallet", wallet_pubkey)])?;
+    check_amount("token_amount", token_amount)?;
+    let (sealed_bid_pubkey, _) = SealedBid::find_address(auction_pubkey, wallet_pubkey);
+    let (sealed_book_pubkey, _) = SealedBook::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new_readonly(*wallet_pubkey, true),
+            AccountMeta::new(sealed_bid_pubkey, false),
+            AccountMeta::new(sealed_book_pubkey, false),
+        ],
+        data: AuctionInstruction::RevealBid {
+            token_amount,
+            price,
+            salt: *salt,
+        }
+        .pack(),
+    })
+}
+
+pub fn settle_sealed_bids(auction_pubkey: &Pubkey) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("auction", auction_pubkey)])?;
+    let (sealed_book_pubkey, _) = SealedBook::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*auction_pubkey, false),
+            AccountMeta::new(sealed_book_pubkey, false),
+        ],
+        data: AuctionInstruction::SettleSealedBids.pack(),
+    })
+}
+
+pub fn claim_sealed_bid(
+    auction_pubkey: &Pubkey,
+    wallet_pubkey: &Pubkey,
     token_pubkey: &Pubkey,
     token_auction_pubkey: &Pubkey,
     token_auction_owner_info: &Pubkey,
-    token_dest_pubkey: &Pubkey,
-) -> Result<Instruction, ProgramError> {
+    token_customer_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("wallet", wallet_pubkey),
+        ("token", token_pubkey),
+        ("token_auction", token_auction_pubkey),
+        ("token_auction_owner", token_auction_owner_info),
+        ("token_customer", token_customer_pubkey),
+    ])?;
+    let (sealed_bid_pubkey, _) = SealedBid::find_address(auction_pubkey, wallet_pubkey);
+    let (sealed_book_pubkey, _) = SealedBook::find_address(auction_pubkey);
     Ok(Instruction {
         program_id: crate::id(),
         accounts: vec![
-            AccountMeta::new_readonly(*auction_pubkey, false),
-            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new(*wallet_pubkey, false),
+            AccountMeta::new(sealed_bid_pubkey, false),
+            AccountMeta::new(sealed_book_pubkey, false),
             AccountMeta::new_readonly(spl_token::id(), false),
             AccountMeta::new_readonly(*token_pubkey, false),
             AccountMeta::new(*token_auction_pubkey, false),
-            AccountMeta::new_readonly(*token_auction_owner_info, false),
-            AccountMeta::new(*token_dest_pubkey, false),
+            AccountMeta::new(*token_auction_owner_info, false),
+            AccountMeta::new(*token_customer_pubkey, false),
         ],
-        data: AuctionInstruction::WithdrawTokens.pack(),
+        data: AuctionInstruction::ClaimSealedBid.pack(),
+    })
+}
+
+pub fn end_auction_early(
+    auction_pubkey: &Pubkey,
+    auction_authority_pubkey: &Pubkey,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[
+        ("auction", auction_pubkey),
+        ("auction_authority", auction_authority_pubkey),
+    ])?;
+    let (admin_log_pubkey, _) = AdminLog::find_address(auction_pubkey);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*auction_pubkey, false),
+            AccountMeta::new_readonly(*auction_authority_pubkey, true),
+            AccountMeta::new(admin_log_pubkey, false),
+        ],
+        data: AuctionInstruction::EndAuctionEarly.pack(),
+    })
+}
+
+fn check_pubkeys(pubkeys: &[(&'static str, &Pubkey)]) -> Result<(), BuilderError> {
+    match pubkeys
+        .iter()
+        .find(|(_, pubkey)| **pubkey == Pubkey::default())
+    {
+        Some((name, _)) => Err(BuilderError::DefaultPubkey(name)),
+        None => Ok(()),
+    }
+}
+
+fn check_amount(name: &'static str, amount: u64) -> Result<(), BuilderError> {
+    if amount == 0 {
+        return Err(BuilderError::ZeroAmount(name));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "test-clock")]
+pub fn set_clock(
+    funding_pubkey: &Pubkey,
+    unix_timestamp: UnixTimestamp,
+) -> Result<Instruction, BuilderError> {
+    check_pubkeys(&[("funding", funding_pubkey)])?;
+    let (clock_override_pubkey, _) = crate::state::ClockOverride::find_address();
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*funding_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(clock_override_pubkey, false),
+        ],
+        data: AuctionInstruction::SetClock { unix_timestamp }.pack(),
     })
 }
@@ -1,9 +1,22 @@
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 
+#[cfg(not(target_arch = "bpf"))]
+pub mod client;
+#[cfg(not(target_arch = "bpf"))]
+pub mod display;
 pub mod error;
 pub mod instruction;
+pub mod merkle;
+pub mod order;
+#[cfg(not(target_arch = "bpf"))]
+pub mod preview;
+pub mod price;
 pub mod processor;
+#[cfg(not(target_arch = "bpf"))]
+pub mod spec;
 pub mod state;
+#[cfg(not(target_arch = "bpf"))]
+pub mod vectors;
 
 solana_program::declare_id!("DutchAuction1111111111111111111111111111111");
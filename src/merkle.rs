@@ -0,0 +1,68 @@
+//! Merkle tree of allowlisted wallets. Leaves and nodes are hashed with
+//! different prefixes and node children are sorted, so proof is only list of
+//! sibling hashes from leaf to root.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Maximum number of hashes in proof, enough for 2^16 wallets.
+pub const MAX_MERKLE_PROOF_LEN: usize = 16;
+
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0], wallet.as_ref()]).to_bytes()
+}
+
+fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Check that `wallet` is a leaf of tree with `root`.
+pub fn verify(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let hash = proof
+        .iter()
+        .fold(leaf(wallet), |hash, sibling| node(&hash, sibling));
+    &hash == root
+}
+
+// Levels from leaves to root, node without sibling is moved up unchanged.
+#[cfg(not(target_arch = "bpf"))]
+fn levels(wallets: &[Pubkey]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![wallets.iter().map(leaf).collect::<Vec<_>>()];
+    while levels[levels.len() - 1].len() > 1 {
+        let level = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => node(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(level);
+    }
+    levels
+}
+
+/// Root of tree with `wallets` leaves, zeroes for empty list.
+#[cfg(not(target_arch = "bpf"))]
+pub fn root(wallets: &[Pubkey]) -> [u8; 32] {
+    levels(wallets)
+        .last()
+        .and_then(|level| level.first().copied())
+        .unwrap_or_default()
+}
+
+/// Proof for wallet at `index` in `wallets`, `None` if index is out of range.
+#[cfg(not(target_arch = "bpf"))]
+pub fn proof(wallets: &[Pubkey], mut index: usize) -> Option<Vec<[u8; 32]>> {
+    if index >= wallets.len() {
+        return None;
+    }
+    let mut proof = vec![];
+    for level in levels(wallets).iter() {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    Some(proof)
+}
@@ -0,0 +1,110 @@
+//! Purchase orders signed off-chain by buyer and submitted by anyone. Buyer
+//! signature is verified by ed25519 program instruction in the same
+//! transaction, auction program only looks it up with instructions sysvar.
+//! Orders are filled either from buyer deposit or with lamports fronted by
+//! relayer, so buyer does not need SOL at all.
+
+use arrayref::{array_ref, array_refs, mut_array_refs};
+use solana_program::{clock::UnixTimestamp, instruction::Instruction, pubkey::Pubkey};
+
+/// Native program verifying ed25519 signatures.
+pub mod ed25519_program {
+    solana_program::declare_id!("Ed25519SigVerify111111111111111111111111111");
+}
+
+// Offsets of the only signature in ed25519 instruction data, follow number of
+// signatures and padding byte.
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+// Instruction index of signature, key or message in the same instruction.
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Order to buy tokens from auction, filled from buyer deposit or by relayer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PurchaseOrder {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    // Maximum amount of tokens, in base units.
+    pub token_amount: u64,
+    // Order is not filled if current price is higher.
+    pub max_price: u64,
+    // Order is not filled at and after this time.
+    pub expires_at: UnixTimestamp,
+    // Should be equal to `Deposit::order_nonce` of buyer, so every order is
+    // filled at most once. Relayed orders are recorded by `RelayedOrder`
+    // account of nonce instead.
+    pub nonce: u64,
+    // Lamports are paid by relayer, not from buyer deposit. Part of message,
+    // so deposit order can not be filled by relayer and vice versa.
+    pub relayed: bool,
+}
+
+impl PurchaseOrder {
+    pub const MESSAGE_LEN: usize = 129;
+
+    /// Message signed by buyer. Starts with program id, so signed order is
+    /// not valid for other programs.
+    pub fn message(&self) -> [u8; Self::MESSAGE_LEN] {
+        let mut message = [0; Self::MESSAGE_LEN];
+        let (
+            program_id_dst,
+            auction_dst,
+            buyer_dst,
+            token_amount_dst,
+            max_price_dst,
+            expires_at_dst,
+            nonce_dst,
+            relayed_dst,
+        ) = mut_array_refs![&mut message, 32, 32, 32, 8, 8, 8, 8, 1];
+        program_id_dst.copy_from_slice(crate::id().as_ref());
+        auction_dst.copy_from_slice(self.auction.as_ref());
+        buyer_dst.copy_from_slice(self.buyer.as_ref());
+        *token_amount_dst = self.token_amount.to_le_bytes();
+        *max_price_dst = self.max_price.to_le_bytes();
+        *expires_at_dst = self.expires_at.to_le_bytes();
+        *nonce_dst = self.nonce.to_le_bytes();
+        relayed_dst[0] = self.relayed as u8;
+        message
+    }
+
+    /// Check that `instruction` is ed25519 program instruction with single
+    /// signature of order message by buyer. Signature itself is verified by
+    /// ed25519 program, transaction fails otherwise.
+    pub fn is_verified_by(&self, instruction: &Instruction) -> bool {
+        let data = &instruction.data;
+        if instruction.program_id != ed25519_program::id()
+            || data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN
+            || data[0] != 1
+        {
+            return false;
+        }
+
+        let offsets = array_ref![data, SIGNATURE_OFFSETS_START, SIGNATURE_OFFSETS_LEN];
+        let (
+            _signature_offset,
+            signature_instruction_index,
+            public_key_offset,
+            public_key_instruction_index,
+            message_data_offset,
+            message_data_size,
+            message_instruction_index,
+        ) = array_refs![offsets, 2, 2, 2, 2, 2, 2, 2];
+        if [
+            signature_instruction_index,
+            public_key_instruction_index,
+            message_instruction_index,
+        ]
+        .iter()
+        .any(|index| u16::from_le_bytes(**index) != CURRENT_INSTRUCTION)
+        {
+            return false;
+        }
+
+        let public_key_offset = u16::from_le_bytes(*public_key_offset) as usize;
+        let message_data_offset = u16::from_le_bytes(*message_data_offset) as usize;
+        let message_data_size = u16::from_le_bytes(*message_data_size) as usize;
+        data.get(public_key_offset..public_key_offset + 32) == Some(self.buyer.as_ref())
+            && data.get(message_data_offset..message_data_offset + message_data_size)
+                == Some(&self.message()[..])
+    }
+}
@@ -0,0 +1,116 @@
+//! Summary of auction parameters for seller review before signing
+//! `InitializeAuction`, not compiled for program.
+
+use std::fmt;
+
+use solana_program::{clock::UnixTimestamp, program_pack::Pack, rent::Rent};
+
+use crate::{
+    price::PriceEngine,
+    state::{format_price, AdminLog, Auction},
+};
+
+/// Maximum number of rows in `AuctionPreview::steps`.
+pub const MAX_PREVIEW_STEPS: usize = 100;
+
+/// Price of single auction step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PreviewStep {
+    pub step: u64,
+    // Step start, blackout pauses are not taken into account.
+    pub time: UnixTimestamp,
+    pub price: u64,
+}
+
+/// Lamports required for rent exemption of accounts created by
+/// `InitializeAuction`, with default rent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RentCosts {
+    pub auction: u64,
+    pub admin_log: u64,
+    pub token_auction_owner: u64,
+    pub token_auction: u64,
+}
+
+impl RentCosts {
+    pub fn total(&self) -> u64 {
+        self.auction + self.admin_log + self.token_auction_owner + self.token_auction
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuctionPreview {
+    // Proceeds if everything sold at start price.
+    pub max_proceeds: u128,
+    // Seconds until price reach zero, `None` if price never decrease.
+    pub duration: Option<UnixTimestamp>,
+    // First `MAX_PREVIEW_STEPS` steps.
+    pub steps: Vec<PreviewStep>,
+    // Number of steps with positive price, `u64::MAX` if price never decrease.
+    pub steps_total: u64,
+    pub rent: RentCosts,
+}
+
+/// Preview of auction, depends only on `auction` parameters.
+pub fn render(auction: &Auction) -> AuctionPreview {
+    let engine = auction.price_engine();
+    let steps_total = engine.steps_total();
+
+    let duration = if steps_total == u64::MAX {
+        None
+    } else {
+        let duration = steps_total as i128 * auction.time_step as i128;
+        Some(duration.min(UnixTimestamp::MAX as i128) as UnixTimestamp)
+    };
+
+    let steps = (0..steps_total.min(MAX_PREVIEW_STEPS as u64))
+        .filter_map(|step| {
+            engine.price(step).map(|price| PreviewStep {
+                step,
+                time: auction
+                    .time_start
+                    .saturating_add((step as UnixTimestamp).saturating_mul(auction.time_step)),
+                price,
+            })
+        })
+        .collect();
+
+    let rent = Rent::default();
+    AuctionPreview {
+        max_proceeds: auction.token_amount_for_sale as u128 * engine.price(0).unwrap_or(0) as u128,
+        duration,
+        steps,
+        steps_total,
+        rent: RentCosts {
+            auction: rent.minimum_balance(Auction::LEN),
+            admin_log: rent.minimum_balance(AdminLog::LEN),
+            token_auction_owner: rent.minimum_balance(0),
+            token_auction: rent.minimum_balance(spl_token::state::Account::LEN),
+        },
+    }
+}
+
+impl fmt::Display for AuctionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Max proceeds: {} lamports", self.max_proceeds)?;
+        match self.duration {
+            Some(duration) => writeln!(f, "Duration: {} seconds", duration)?,
+            None => writeln!(f, "Duration: unlimited")?,
+        }
+        writeln!(f, "Rent: {} SOL", format_price(self.rent.total()))?;
+        writeln!(f, "Steps:")?;
+        for step in &self.steps {
+            writeln!(
+                f,
+                "  {:>4} {:>12} {} SOL",
+                step.step,
+                step.time,
+                format_price(step.price)
+            )?;
+        }
+        if self.steps_total > self.steps.len() as u64 {
+            writeln!(f, "  ...")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use solana_program::clock::UnixTimestamp;
+
+use crate::state::{Auction, PriceBreakpoint, PriceCurve, MAX_PRICE_BREAKPOINTS};
+
+/// Price of auction as function of number of elapsed price steps.
+pub trait PriceEngine {
+    /// Price at `step`, `None` if auction is finished at this step.
+    fn price(&self, step: u64) -> Option<u64>;
+
+    /// Number of steps with positive price, `u64::MAX` if price never reach
+    /// zero.
+    fn steps_total(&self) -> u64;
+
+    /// Sum of prices of first `steps` steps.
+    fn price_sum(&self, steps: u64) -> u128 {
+        (0..steps.min(self.steps_total()))
+            .map(|step| self.price(step).unwrap_or(0) as u128)
+            .fold(0, u128::saturating_add)
+    }
+}
+
+/// Price decrease by `price_step` on every step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Linear {
+    pub price_start: u64,
+    pub price_step: u64,
+}
+
+impl PriceEngine for Linear {
+    fn price(&self, step: u64) -> Option<u64> {
+        self.price_step
+            .checked_mul(step)
+            .and_then(|decrease| self.price_start.checked_sub(decrease))
+            .filter(|price| *price != 0)
+    }
+
+    fn steps_total(&self) -> u64 {
+        if self.price_step == 0 {
+            return u64::MAX;
+        }
+        let steps = self.price_start / self.price_step;
+        if !self.price_start.is_multiple_of(self.price_step) {
+            steps + 1
+        } else {
+            steps
+        }
+    }
+
+    fn price_sum(&self, steps: u64) -> u128 {
+        let price_start = self.price_start as u128;
+        let price_step = self.price_step as u128;
+        let steps = steps.min(self.steps_total()) as u128;
+        steps.saturating_mul(price_start).saturating_sub(
+            price_step.saturating_mul(steps.saturating_mul(steps.saturating_sub(1)) / 2),
+        )
+    }
+}
+
+/// Price multiplied by `1 - decay_bps / 10000` on every step, rounded down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exponential {
+    pub price_start: u64,
+    pub decay_bps: u64,
+}
+
+impl Exponential {
+    // Fixed point scale of price multiplier.
+    const ONE: u128 = 1_000_000_000_000_000_000;
+
+    // Multiplier of start price after `step` steps, scaled by `ONE`.
+    fn factor(&self, mut step: u64) -> u128 {
+        let bps = Auction::BPS_DENOMINATOR as u128;
+        let mut base = Self::ONE / bps * bps.saturating_sub(self.decay_bps as u128);
+        let mut factor = Self::ONE;
+        while step > 0 && factor > 0 {
+            if step & 1 == 1 {
+                factor = factor * base / Self::ONE;
+            }
+            base = base * base / Self::ONE;
+            step >>= 1;
+        }
+        factor
+    }
+}
+
+impl PriceEngine for Exponential {
+    fn price(&self, step: u64) -> Option<u64> {
+        let price = self.price_start as u128 * self.factor(step) / Self::ONE;
+        Some(price as u64).filter(|price| *price != 0)
+    }
+
+    fn steps_total(&self) -> u64 {
+        if self.decay_bps == 0 && self.price_start > 0 {
+            return u64::MAX;
+        }
+        // Price is non-increasing, find first step with zero price. Even with
+        // 1 bps decay multiplier is below 2^-64 after 2^20 steps.
+        let (mut low, mut high) = (0, 1 << 20);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.price(middle).is_some() {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    // Geometric series of unrounded prices, so sum can be slightly above sum
+    // of rounded down prices.
+    fn price_sum(&self, steps: u64) -> u128 {
+        let steps = steps.min(self.steps_total());
+        if self.decay_bps == 0 {
+            return (self.price_start as u128).saturating_mul(steps as u128);
+        }
+        let bps = Auction::BPS_DENOMINATOR as u128;
+        let decay = Self::ONE / bps * (self.decay_bps as u128).min(bps);
+        (self.price_start as u128).saturating_mul(Self::ONE - self.factor(steps)) / decay
+    }
+}
+
+/// Price increase by `price_step` on every step, auction is finished once
+/// price is above `price_ceiling`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ascending {
+    pub price_start: u64,
+    pub price_step: u64,
+    pub price_ceiling: u64,
+}
+
+impl PriceEngine for Ascending {
+    fn price(&self, step: u64) -> Option<u64> {
+        self.price_step
+            .checked_mul(step)
+            .and_then(|increase| self.price_start.checked_add(increase))
+            .filter(|price| *price != 0 && *price <= self.price_ceiling)
+    }
+
+    fn steps_total(&self) -> u64 {
+        if self.price_start == 0 || self.price_start > self.price_ceiling {
+            return 0;
+        }
+        if self.price_step == 0 {
+            return u64::MAX;
+        }
+        ((self.price_ceiling - self.price_start) / self.price_step).saturating_add(1)
+    }
+
+    fn price_sum(&self, steps: u64) -> u128 {
+        let price_start = self.price_start as u128;
+        let price_step = self.price_step as u128;
+        let steps = steps.min(self.steps_total()) as u128;
+        steps.saturating_mul(price_start).saturating_add(
+            price_step.saturating_mul(steps.saturating_mul(steps.saturating_sub(1)) / 2),
+        )
+    }
+}
+
+/// Price is constant between breakpoints, found with binary search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Piecewise {
+    // First step and price of every breakpoint, steps strictly increasing.
+    steps: [(u64, u64); MAX_PRICE_BREAKPOINTS],
+    len: usize,
+}
+
+impl Piecewise {
+    /// Breakpoint takes effect from the first step started at or after its
+    /// offset, later of breakpoints in the same step wins. Breakpoints after
+    /// `MAX_PRICE_BREAKPOINTS` are ignored.
+    pub fn new(time_step: UnixTimestamp, breakpoints: &[PriceBreakpoint]) -> Self {
+        let mut engine = Self {
+            steps: [(0, 0); MAX_PRICE_BREAKPOINTS],
+            len: 0,
+        };
+        for breakpoint in breakpoints.iter().take(MAX_PRICE_BREAKPOINTS) {
+            let step = if time_step > 0 && breakpoint.offset > 0 {
+                ((breakpoint.offset - 1) / time_step + 1) as u64
+            } else {
+                0
+            };
+            match engine.breakpoints().last() {
+                Some(last) if last.0 == step => engine.steps[engine.len - 1].1 = breakpoint.price,
+                _ => {
+                    engine.steps[engine.len] = (step, breakpoint.price);
+                    engine.len += 1;
+                }
+            }
+        }
+        engine
+    }
+
+    /// First step and price of every breakpoint.
+    pub fn breakpoints(&self) -> &[(u64, u64)] {
+        &self.steps[..self.len]
+    }
+}
+
+impl PriceEngine for Piecewise {
+    fn price(&self, step: u64) -> Option<u64> {
+        let breakpoints = self.breakpoints();
+        let index = match breakpoints.binary_search_by(|(start, _)| start.cmp(&step)) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        Some(breakpoints[index].1).filter(|price| *price != 0)
+    }
+
+    fn steps_total(&self) -> u64 {
+        match self.breakpoints().iter().find(|(_, price)| *price == 0) {
+            Some((start, _)) => *start,
+            None if self.len == 0 => 0,
+            None => u64::MAX,
+        }
+    }
+
+    fn price_sum(&self, steps: u64) -> u128 {
+        let steps = steps.min(self.steps_total());
+        let ends = self
+            .breakpoints()
+            .iter()
+            .skip(1)
+            .map(|(start, _)| *start)
+            .chain(std::iter::once(u64::MAX));
+        self.breakpoints()
+            .iter()
+            .zip(ends)
+            .filter(|((start, _), _)| *start < steps)
+            .map(|((start, price), end)| *price as u128 * (end.min(steps) - start) as u128)
+            .fold(0, u128::saturating_add)
+    }
+}
+
+/// Engine of one of auction price curves, dispatched without allocation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CurveEngine {
+    Linear(Linear),
+    Exponential(Exponential),
+    Ascending(Ascending),
+    Piecewise(Piecewise),
+}
+
+impl PriceEngine for CurveEngine {
+    fn price(&self, step: u64) -> Option<u64> {
+        match self {
+            Self::Linear(engine) => engine.price(step),
+            Self::Exponential(engine) => engine.price(step),
+            Self::Ascending(engine) => engine.price(step),
+            Self::Piecewise(engine) => engine.price(step),
+        }
+    }
+
+    fn steps_total(&self) -> u64 {
+        match self {
+            Self::Linear(engine) => engine.steps_total(),
+            Self::Exponential(engine) => engine.steps_total(),
+            Self::Ascending(engine) => engine.steps_total(),
+            Self::Piecewise(engine) => engine.steps_total(),
+        }
+    }
+
+    fn price_sum(&self, steps: u64) -> u128 {
+        match self {
+            Self::Linear(engine) => engine.price_sum(steps),
+            Self::Exponential(engine) => engine.price_sum(steps),
+            Self::Ascending(engine) => engine.price_sum(steps),
+            Self::Piecewise(engine) => engine.price_sum(steps),
+        }
+    }
+}
+
+impl Auction {
+    /// Price engine selected by auction price curve.
+    pub fn price_engine(&self) -> CurveEngine {
+        match self.price_curve {
+            PriceCurve::Linear => CurveEngine::Linear(Linear {
+                price_start: self.price_start,
+                price_step: self.price_step,
+            }),
+            PriceCurve::Exponential => CurveEngine::Exponential(Exponential {
+                price_start: self.price_start,
+                decay_bps: self.price_step,
+            }),
+            PriceCurve::Ascending => CurveEngine::Ascending(Ascending {
+                price_start: self.price_start,
+                price_step: self.price_step,
+                price_ceiling: self.price_ceiling,
+            }),
+            PriceCurve::Piecewise => CurveEngine::Piecewise(Piecewise::new(
+                self.time_step,
+                self.price_schedule.breakpoints(),
+            )),
+        }
+    }
+}
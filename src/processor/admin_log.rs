@@ -0,0 +1,62 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_pack::Pack, rent::Rent, system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{AdminLog, AdminLogEntry},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub(super) fn create_admin_log<'a>(
+        auction_info: &AccountInfo<'a>,
+        admin_log_info: &AccountInfo<'a>,
+        funder_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (address, bump_seed) = AdminLog::find_address(auction_info.key);
+        if &address != admin_log_info.key {
+            return Err(AuctionError::InvalidAdminLog.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                admin_log_info.key,
+                Rent::get()?.minimum_balance(AdminLog::LEN),
+                AdminLog::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                funder_info.clone(),
+                admin_log_info.clone(),
+            ],
+            &[&[AdminLog::SEED, auction_info.key.as_ref(), &[bump_seed]]],
+        )?;
+
+        let admin_log = AdminLog {
+            is_initialized: true,
+            ..AdminLog::default()
+        };
+        AdminLog::pack(admin_log, &mut admin_log_info.data.borrow_mut())
+    }
+
+    pub(super) fn append_admin_log(
+        auction_info: &AccountInfo,
+        admin_log_info: &AccountInfo,
+        entry: AdminLogEntry,
+    ) -> ProgramResult {
+        let (address, _) = AdminLog::find_address(auction_info.key);
+        if &address != admin_log_info.key || admin_log_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidAdminLog.into());
+        }
+
+        let mut admin_log = AdminLog::unpack(&admin_log_info.data.borrow())?;
+        admin_log.append(entry);
+        AdminLog::pack(admin_log, &mut admin_log_info.data.borrow_mut())
+    }
+}
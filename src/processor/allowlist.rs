@@ -0,0 +1,142 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{AdminAction, AdminLogEntry, AllowlistEntry, Auction},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_add_to_allowlist(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let allowlist_entry_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        let (address, bump_seed) = AllowlistEntry::find_address(auction_info.key, wallet_info.key);
+        if &address != allowlist_entry_info.key {
+            return Err(AuctionError::InvalidAllowlistEntry.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                allowlist_entry_info.key,
+                Rent::get()?.minimum_balance(AllowlistEntry::LEN),
+                AllowlistEntry::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                funder_info.clone(),
+                allowlist_entry_info.clone(),
+            ],
+            &[&[
+                AllowlistEntry::SEED,
+                auction_info.key.as_ref(),
+                wallet_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let entry = AllowlistEntry {
+            is_initialized: true,
+            rent_payer: *funder_info.key,
+        };
+        AllowlistEntry::pack(entry, &mut allowlist_entry_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::AddToAllowlist,
+                time: Self::unix_timestamp(accounts)?,
+                key: *wallet_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub fn process_remove_from_allowlist(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let allowlist_entry_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let rent_payer_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        let (address, _) = AllowlistEntry::find_address(auction_info.key, wallet_info.key);
+        if &address != allowlist_entry_info.key || allowlist_entry_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidAllowlistEntry.into());
+        }
+        let entry = AllowlistEntry::unpack(&allowlist_entry_info.data.borrow())?;
+        if &entry.rent_payer != rent_payer_info.key {
+            return Err(AuctionError::InvalidRentPayer.into());
+        }
+
+        // Close entry account
+        let lamports = allowlist_entry_info.lamports();
+        **allowlist_entry_info.lamports.borrow_mut() = 0;
+        **rent_payer_info.lamports.borrow_mut() = rent_payer_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        AllowlistEntry::pack(
+            AllowlistEntry::default(),
+            &mut allowlist_entry_info.data.borrow_mut(),
+        )?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::RemoveFromAllowlist,
+                time: Self::unix_timestamp(accounts)?,
+                key: *wallet_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub(super) fn validate_allowlist_entry(
+        auction: &Pubkey,
+        wallet: &Pubkey,
+        allowlist_entry_info: &AccountInfo,
+    ) -> ProgramResult {
+        let (address, _) = AllowlistEntry::find_address(auction, wallet);
+        if &address != allowlist_entry_info.key {
+            return Err(AuctionError::InvalidAllowlistEntry.into());
+        }
+        if allowlist_entry_info.owner != &crate::id()
+            || !AllowlistEntry::unpack_unchecked(&allowlist_entry_info.data.borrow())?
+                .is_initialized
+        {
+            return Err(AuctionError::NotAllowlisted.into());
+        }
+        Ok(())
+    }
+}
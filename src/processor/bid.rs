@@ -0,0 +1,826 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{self, Sysvar},
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::{instruction::transfer_checked, state::Account};
+
+use crate::{
+    error::AuctionError,
+    instruction::fill_hook,
+    merkle,
+    order::PurchaseOrder,
+    state::{
+        AmountBasis, Auction, BidReceipt, BuyerProfile, Deposit, PriceCurve, PriceMilestones,
+        RelayedOrder, Session,
+    },
+};
+
+use super::{Payer, Processor};
+
+// Fill requested by bid instruction, `token_amount` is in `amount_basis`
+// units.
+#[derive(Default)]
+struct FillRequest<'c> {
+    token_amount: u64,
+    amount_basis: AmountBasis,
+    quoted_at: Option<UnixTimestamp>,
+    buy_now: bool,
+    merkle_proof: &'c [[u8; 32]],
+}
+
+impl FillRequest<'_> {
+    fn new(token_amount: u64) -> Self {
+        Self {
+            token_amount,
+            ..Self::default()
+        }
+    }
+}
+
+struct BidAccounts<'a, 'b> {
+    auction_info: &'a AccountInfo<'b>,
+    token_program_info: &'a AccountInfo<'b>,
+    token_info: &'a AccountInfo<'b>,
+    token_auction_info: &'a AccountInfo<'b>,
+    token_auction_owner_info: &'a AccountInfo<'b>,
+    token_customer_info: &'a AccountInfo<'b>,
+}
+
+impl Processor {
+    pub fn process_bid(
+        accounts: &[AccountInfo],
+        token_amount: u64,
+        amount_basis: AmountBasis,
+        quoted_at: Option<UnixTimestamp>,
+        create_token_customer: bool,
+        tip: u64,
+        merkle_proof: &[[u8; 32]],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+
+        if create_token_customer {
+            let wallet_info = next_account_info(account_info_iter)?;
+            let atoken_program_info = next_account_info(account_info_iter)?;
+            let rent_sysvar_info = next_account_info(account_info_iter)?;
+
+            let address = get_associated_token_address(wallet_info.key, token_info.key);
+            if &address != token_customer_info.key {
+                return Err(AuctionError::InvalidCustomerTokenAddress.into());
+            }
+
+            // Account can be created by earlier instruction or bid
+            if token_customer_info.owner != token_program_info.key {
+                invoke(
+                    &create_associated_token_account(
+                        funder_info.key,
+                        wallet_info.key,
+                        token_info.key,
+                    ),
+                    &[
+                        atoken_program_info.clone(),
+                        funder_info.clone(),
+                        token_customer_info.clone(),
+                        wallet_info.clone(),
+                        token_info.clone(),
+                        system_program_info.clone(),
+                        token_program_info.clone(),
+                        rent_sysvar_info.clone(),
+                    ],
+                )?;
+            }
+        }
+
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::System {
+                system_program_info,
+                funder_info,
+            },
+            funder_info.key,
+            account_info_iter,
+            Self::unix_timestamp(accounts)?,
+            FillRequest {
+                token_amount,
+                amount_basis,
+                quoted_at,
+                merkle_proof,
+                ..FillRequest::default()
+            },
+        )?;
+
+        // Tip is recorded separately from proceeds
+        if tip > 0 {
+            let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+            let tip_recipient_info = match auction.tip_recipient {
+                COption::Some(tip_recipient) => {
+                    let tip_recipient_info = next_account_info(account_info_iter)?;
+                    if tip_recipient_info.key != &tip_recipient {
+                        return Err(AuctionError::InvalidTipRecipient.into());
+                    }
+                    tip_recipient_info
+                }
+                COption::None => token_auction_owner_info,
+            };
+            Payer::System {
+                system_program_info,
+                funder_info,
+            }
+            .transfer(tip_recipient_info, tip)?;
+            auction.tips = auction.tips.saturating_add(tip);
+            Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn process_buy_now(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+
+        // Requested amount is capped by remaining tokens
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::System {
+                system_program_info,
+                funder_info,
+            },
+            funder_info.key,
+            account_info_iter,
+            Self::unix_timestamp(accounts)?,
+            FillRequest {
+                token_amount: u64::MAX,
+                buy_now: true,
+                ..FillRequest::default()
+            },
+        )?;
+        Ok(())
+    }
+
+    pub fn process_session_bid(accounts: &[AccountInfo], token_amount: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let session_info = next_account_info(account_info_iter)?;
+        let session_key_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+
+        // Check session
+        if session_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidSession.into());
+        }
+        let session = Session::unpack(&session_info.data.borrow())?;
+        let (address, _) = Session::find_address(&session.wallet, session_key_info.key);
+        if &address != session_info.key {
+            return Err(AuctionError::InvalidSession.into());
+        }
+        if !session_key_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let current_time = Self::unix_timestamp(accounts)?;
+        if current_time >= session.expires_at {
+            return Err(AuctionError::SessionExpired.into());
+        }
+
+        // Tokens can be sent only to the wallet
+        let token_customer = Account::unpack(&token_customer_info.data.borrow())?;
+        if token_customer.owner != session.wallet {
+            return Err(AuctionError::OwnerMismatch.into());
+        }
+
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::Program {
+                funder_info: session_info,
+                reserve: Rent::get()?.minimum_balance(Session::LEN),
+            },
+            &session.wallet,
+            account_info_iter,
+            current_time,
+            FillRequest::new(token_amount),
+        )?;
+        Ok(())
+    }
+
+    pub fn process_deposit_bid(accounts: &[AccountInfo], token_amount: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+
+        Self::validate_deposit(wallet_info, deposit_info)?;
+
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::Program {
+                funder_info: deposit_info,
+                reserve: Rent::get()?.minimum_balance(Deposit::LEN),
+            },
+            wallet_info.key,
+            account_info_iter,
+            Self::unix_timestamp(accounts)?,
+            FillRequest::new(token_amount),
+        )?;
+        Ok(())
+    }
+
+    pub fn process_order_bid(
+        accounts: &[AccountInfo],
+        token_amount: u64,
+        max_price: u64,
+        expires_at: UnixTimestamp,
+        nonce: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+
+        // Buyer does not sign, deposit is checked only by address
+        let (address, _) = Deposit::find_address(wallet_info.key);
+        if &address != deposit_info.key || deposit_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidDeposit.into());
+        }
+        let mut deposit = Deposit::unpack(&deposit_info.data.borrow())?;
+
+        let order = PurchaseOrder {
+            auction: *auction_info.key,
+            buyer: *wallet_info.key,
+            token_amount,
+            max_price,
+            expires_at,
+            nonce,
+            relayed: false,
+        };
+        if order.nonce != deposit.order_nonce {
+            return Err(AuctionError::InvalidOrderNonce.into());
+        }
+        let auction = Self::unpack_auction(auction_info)?;
+        if auction.taker_fee_bps > 0 {
+            return Err(AuctionError::OrderTakerFeeNotSupported.into());
+        }
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_purchase_order(
+            &order,
+            &auction,
+            instructions_info,
+            token_customer_info,
+            current_time,
+        )?;
+
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::Program {
+                funder_info: deposit_info,
+                reserve: Rent::get()?.minimum_balance(Deposit::LEN),
+            },
+            wallet_info.key,
+            account_info_iter,
+            current_time,
+            FillRequest::new(token_amount),
+        )?;
+
+        deposit.order_nonce = nonce
+            .checked_add(1)
+            .ok_or(AuctionError::InvalidOrderNonce)?;
+        Deposit::pack(deposit, &mut deposit_info.data.borrow_mut())
+    }
+
+    pub fn process_relayed_bid(
+        accounts: &[AccountInfo],
+        token_amount: u64,
+        max_price: u64,
+        expires_at: UnixTimestamp,
+        nonce: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let relayer_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+        let relayed_order_info = next_account_info(account_info_iter)?;
+
+        let order = PurchaseOrder {
+            auction: *auction_info.key,
+            buyer: *wallet_info.key,
+            token_amount,
+            max_price,
+            expires_at,
+            nonce,
+            relayed: true,
+        };
+        let auction = Self::unpack_auction(auction_info)?;
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_purchase_order(
+            &order,
+            &auction,
+            instructions_info,
+            token_customer_info,
+            current_time,
+        )?;
+
+        // Relayed order account exists once order is filled
+        let (address, bump_seed) = RelayedOrder::find_address(wallet_info.key, nonce);
+        if &address != relayed_order_info.key {
+            return Err(AuctionError::InvalidRelayedOrder.into());
+        }
+        if relayed_order_info.owner == &crate::id() {
+            return Err(AuctionError::InvalidOrderNonce.into());
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                relayer_info.key,
+                relayed_order_info.key,
+                Rent::get()?.minimum_balance(RelayedOrder::LEN),
+                RelayedOrder::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                relayer_info.clone(),
+                relayed_order_info.clone(),
+            ],
+            &[&[
+                RelayedOrder::SEED,
+                wallet_info.key.as_ref(),
+                &nonce.to_le_bytes(),
+                &[bump_seed],
+            ]],
+        )?;
+        let relayed_order = RelayedOrder {
+            is_initialized: true,
+            relayer: *relayer_info.key,
+        };
+        RelayedOrder::pack(relayed_order, &mut relayed_order_info.data.borrow_mut())?;
+
+        Self::process_fill(
+            BidAccounts {
+                auction_info,
+                token_program_info,
+                token_info,
+                token_auction_info,
+                token_auction_owner_info,
+                token_customer_info,
+            },
+            Payer::System {
+                system_program_info,
+                funder_info: relayer_info,
+            },
+            wallet_info.key,
+            account_info_iter,
+            current_time,
+            FillRequest::new(token_amount),
+        )?;
+
+        Ok(())
+    }
+
+    /// Check purchase order expiration, buyer signature by ed25519 program
+    /// instruction, price and that tokens are sent to the buyer.
+    fn check_purchase_order(
+        order: &PurchaseOrder,
+        auction: &Auction,
+        instructions_info: &AccountInfo,
+        token_customer_info: &AccountInfo,
+        current_time: UnixTimestamp,
+    ) -> ProgramResult {
+        if current_time >= order.expires_at {
+            return Err(AuctionError::OrderExpired.into());
+        }
+
+        if instructions_info.key != &sysvar::instructions::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let data = instructions_info.data.borrow();
+        let mut index = 0;
+        loop {
+            match sysvar::instructions::load_instruction_at(index, &data) {
+                Ok(instruction) if order.is_verified_by(&instruction) => break,
+                Ok(_) => index += 1,
+                Err(_) => return Err(AuctionError::InvalidOrderSignature.into()),
+            }
+        }
+
+        // Order is filled at current price, same as in `process_fill`
+        if matches!(auction.price_at(current_time), Some(price) if price > order.max_price) {
+            return Err(AuctionError::OrderPriceExceeded.into());
+        }
+
+        let token_customer = Account::unpack(&token_customer_info.data.borrow())?;
+        if token_customer.owner != order.buyer {
+            return Err(AuctionError::OwnerMismatch.into());
+        }
+        Ok(())
+    }
+
+    pub fn process_bundle_bid(
+        accounts: &[AccountInfo],
+        max_lamports: u64,
+        token_amounts: Vec<u64>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        let current_time = Self::unix_timestamp(accounts)?;
+        let lamports_before = funder_info.lamports();
+
+        for token_amount in token_amounts {
+            let auction_info = next_account_info(account_info_iter)?;
+            let token_info = next_account_info(account_info_iter)?;
+            let token_auction_info = next_account_info(account_info_iter)?;
+            let token_auction_owner_info = next_account_info(account_info_iter)?;
+            let token_customer_info = next_account_info(account_info_iter)?;
+
+            // Token payments are not counted in `max_lamports`
+            let auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+            if auction.payment_mint.is_some() {
+                return Err(AuctionError::InvalidPaymentMint.into());
+            }
+
+            let filled = Self::process_fill(
+                BidAccounts {
+                    auction_info,
+                    token_program_info,
+                    token_info,
+                    token_auction_info,
+                    token_auction_owner_info,
+                    token_customer_info,
+                },
+                Payer::System {
+                    system_program_info,
+                    funder_info,
+                },
+                funder_info.key,
+                account_info_iter,
+                current_time,
+                FillRequest::new(token_amount),
+            )?;
+            // Partial fill breaks the bundle
+            if filled != token_amount {
+                return Err(AuctionError::BundleNotFilled.into());
+            }
+        }
+
+        let spent = lamports_before.saturating_sub(funder_info.lamports());
+        if spent > max_lamports {
+            return Err(AuctionError::BundleCostExceeded.into());
+        }
+
+        Ok(())
+    }
+
+    // Returns filled token amount, can be less than requested.
+    fn process_fill<'a, 'b: 'a>(
+        accounts: BidAccounts<'a, 'b>,
+        payer: Payer<'a, 'b>,
+        wallet: &Pubkey,
+        account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+        current_time: UnixTimestamp,
+        request: FillRequest,
+    ) -> Result<u64, ProgramError> {
+        let FillRequest {
+            token_amount,
+            amount_basis,
+            quoted_at,
+            buy_now,
+            merkle_proof,
+        } = request;
+        let BidAccounts {
+            auction_info,
+            token_program_info,
+            token_info,
+            token_auction_info,
+            token_auction_owner_info,
+            token_customer_info,
+        } = accounts;
+
+        // Payee accounts are derived from auction, checked before anything
+        // is written or paid
+        let mut auction = Self::unpack_auction(auction_info)?;
+        if token_info.key != &auction.token {
+            return Err(AuctionError::InvalidAuctionToken.into());
+        }
+        let token_auction_owner =
+            Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id())?;
+        if &token_auction_owner != token_auction_owner_info.key {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+        let address = get_associated_token_address(&token_auction_owner, &auction.token);
+        if &address != token_auction_info.key {
+            return Err(AuctionError::InvalidAuctionTokenAddress.into());
+        }
+
+        if auction.is_sealed() {
+            return Err(AuctionError::SealedBidsOnly.into());
+        }
+
+        // Price can be locked at quote time from instruction, except rising
+        // price where earlier quote is cheaper
+        let price_time = match quoted_at {
+            Some(quoted_at) => {
+                if auction.price_lock_skew == 0 || auction.price_curve == PriceCurve::Ascending {
+                    return Err(AuctionError::PriceLockDisabled.into());
+                }
+                if quoted_at > current_time || current_time - quoted_at > auction.price_lock_skew {
+                    return Err(AuctionError::InvalidQuoteTime.into());
+                }
+                quoted_at
+            }
+            None => current_time,
+        };
+
+        // Check that auction started
+        let (token, current_price) = Self::get_price_at(&auction, token_info, price_time)?;
+        // Check that auction still live
+        let current_price = current_price.ok_or(AuctionError::Finished)?;
+        let current_price = if buy_now {
+            if auction.buy_now_price == 0 {
+                return Err(AuctionError::BuyNowDisabled.into());
+            }
+            auction.buy_now_price
+        } else {
+            current_price
+        };
+        let token_amount = amount_basis
+            .to_base_units(token_amount, token.decimals)
+            .ok_or(AuctionError::InvalidAmount)?;
+
+        if auction.in_blackout(current_time) {
+            return Err(AuctionError::InBlackout.into());
+        }
+
+        // Optional accounts, hook gets buyer wallet even if bid is paid by
+        // session or deposit account
+        let hook_infos = match auction.hook_program {
+            COption::Some(hook_program) => {
+                let hook_program_info = next_account_info(account_info_iter)?;
+                if hook_program_info.key != &hook_program {
+                    return Err(AuctionError::InvalidHookProgram.into());
+                }
+                let hook_wallet_info = next_account_info(account_info_iter)?;
+                if hook_wallet_info.key != wallet {
+                    return Err(AuctionError::OwnerMismatch.into());
+                }
+                Some((hook_program_info, hook_wallet_info))
+            }
+            COption::None => None,
+        };
+        if auction.allowlist_enabled {
+            let allowlist_entry_info = next_account_info(account_info_iter)?;
+            Self::validate_allowlist_entry(auction_info.key, wallet, allowlist_entry_info)?;
+        }
+        if auction.has_merkle_allowlist()
+            && !merkle::verify(&auction.merkle_root, wallet, merkle_proof)
+        {
+            return Err(AuctionError::NotAllowlisted.into());
+        }
+        let marketplace_info = if auction.taker_fee_bps > 0 {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        let buyer_profile_info = if auction.buyer_profiles {
+            let buyer_profile_info = next_account_info(account_info_iter)?;
+            let buyer_profile = Self::unpack_buyer_profile(wallet, buyer_profile_info)?;
+            if buyer_profile.auctions < auction.min_buyer_auctions {
+                return Err(AuctionError::InsufficientBuyerHistory.into());
+            }
+            Some((buyer_profile_info, buyer_profile))
+        } else {
+            None
+        };
+        let price_milestones_info = if auction.price_milestones {
+            let price_milestones_info = next_account_info(account_info_iter)?;
+            let price_milestones =
+                Self::unpack_price_milestones(auction_info.key, price_milestones_info)?;
+            Some((price_milestones_info, price_milestones))
+        } else {
+            None
+        };
+        let bid_receipt_info = if auction.uniform_price {
+            let bid_receipt_info = next_account_info(account_info_iter)?;
+            let bid_receipt = Self::unpack_bid_receipt(auction_info.key, wallet, bid_receipt_info)?;
+            Some((bid_receipt_info, bid_receipt))
+        } else {
+            None
+        };
+
+        let token_payment =
+            Self::next_token_payment(&auction, &token_auction_owner, account_info_iter)?;
+
+        Self::validate_denied_programs(&auction, account_info_iter.as_slice())?;
+
+        // Check available balance
+        if auction.remaining() == 0 {
+            return Err(AuctionError::EverythingSoldOut.into());
+        }
+        // Check amount left in current price step, buy now is not limited
+        let step_available = if buy_now {
+            auction.remaining()
+        } else {
+            auction.step_tokens_available(current_time)
+        };
+        if step_available == 0 {
+            return Err(AuctionError::StepSoldOut.into());
+        }
+        // Buy now takes everything, bid limit is not applied
+        let bid_available = if buy_now {
+            u64::MAX
+        } else {
+            auction.bid_tokens_available()
+        };
+        let token_amount = token_amount
+            .min(auction.remaining())
+            .min(step_available)
+            .min(bid_available);
+        let cost =
+            Auction::fill_cost(token_amount, current_price).ok_or(AuctionError::InvalidAmount)?;
+        if auction.uniform_price {
+            auction.record_clearing_price(current_price);
+        }
+        auction.token_amount_for_sale -= token_amount;
+        auction.record_step_sale(current_time, token_amount);
+        auction.tokens_sold = auction.tokens_sold.saturating_add(token_amount);
+        auction.proceeds = auction.proceeds.saturating_add(cost);
+        auction.last_price = current_price;
+        if let Some((price_milestones_info, mut price_milestones)) = price_milestones_info {
+            if price_milestones.apply(&mut auction) {
+                PriceMilestones::pack(
+                    price_milestones,
+                    &mut price_milestones_info.data.borrow_mut(),
+                )?;
+            }
+        }
+        auction.update_price_cumulative(current_time);
+        if buy_now {
+            auction.ended_at = current_time;
+        }
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        // Transfer SOL or payment tokens
+        let proceeds_info = match token_payment {
+            Some(ref token_payment) => token_payment.auction_info(),
+            None => token_auction_owner_info,
+        };
+        Self::pay(
+            &payer,
+            token_payment.as_ref(),
+            token_program_info,
+            proceeds_info,
+            cost,
+        )?;
+        if let Some(marketplace_info) = marketplace_info {
+            let fee = auction.taker_fee(cost);
+            if fee > 0 {
+                Self::pay(
+                    &payer,
+                    token_payment.as_ref(),
+                    token_program_info,
+                    marketplace_info,
+                    fee,
+                )?;
+            }
+        }
+
+        if let Some((buyer_profile_info, mut buyer_profile)) = buyer_profile_info {
+            buyer_profile.record_fill(auction_info.key, cost);
+            BuyerProfile::pack(buyer_profile, &mut buyer_profile_info.data.borrow_mut())?;
+        }
+        if let Some((bid_receipt_info, mut bid_receipt)) = bid_receipt_info {
+            bid_receipt.token_amount = bid_receipt.token_amount.saturating_add(token_amount);
+            bid_receipt.paid = bid_receipt.paid.saturating_add(cost);
+            BidReceipt::pack(bid_receipt, &mut bid_receipt_info.data.borrow_mut())?;
+        }
+
+        // Transfer Tokens
+        Self::check_token_transfer(token_auction_info, token_customer_info, token_amount)?;
+        invoke_signed(
+            &transfer_checked(
+                token_program_info.key,
+                token_auction_info.key,
+                token_info.key,
+                token_customer_info.key,
+                token_auction_owner_info.key,
+                &[],
+                token_amount,
+                token.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                token_auction_info.clone(),
+                token_customer_info.clone(),
+                token_auction_owner_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )?;
+
+        // Notify hook program
+        if let Some((hook_program_info, hook_wallet_info)) = hook_infos {
+            invoke(
+                &fill_hook(
+                    hook_program_info.key,
+                    auction_info.key,
+                    hook_wallet_info.key,
+                    token_amount,
+                    current_price,
+                ),
+                &[
+                    hook_program_info.clone(),
+                    auction_info.clone(),
+                    hook_wallet_info.clone(),
+                ],
+            )?;
+        }
+
+        Ok(token_amount)
+    }
+}
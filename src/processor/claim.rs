@@ -0,0 +1,126 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_token::{instruction::transfer_checked, state::Mint};
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, FreeClaim},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_claim_free(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+        let free_claim_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        if !auction.allow_zero_price {
+            return Err(AuctionError::InvalidFreeClaim.into());
+        }
+
+        // Claims open only when price reached zero, until seller can withdraw
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_started(&auction, current_time)?;
+        if auction.price_at(current_time).is_some() {
+            return Err(AuctionError::NotFinished.into());
+        }
+        if current_time >= auction.free_claim_end {
+            return Err(AuctionError::FreeClaimsEnded.into());
+        }
+        if auction.in_blackout(current_time) {
+            return Err(AuctionError::InBlackout.into());
+        }
+        if auction.allowlist_enabled {
+            let allowlist_entry_info = next_account_info(account_info_iter)?;
+            Self::validate_allowlist_entry(
+                auction_info.key,
+                wallet_info.key,
+                allowlist_entry_info,
+            )?;
+        }
+        if auction.remaining() == 0 {
+            return Err(AuctionError::EverythingSoldOut.into());
+        }
+
+        let (address, bump_seed) = FreeClaim::find_address(auction_info.key, wallet_info.key);
+        if &address != free_claim_info.key {
+            return Err(AuctionError::InvalidFreeClaim.into());
+        }
+        if free_claim_info.owner == &crate::id() {
+            return Err(AuctionError::AlreadyClaimed.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                free_claim_info.key,
+                Rent::get()?.minimum_balance(FreeClaim::LEN),
+                FreeClaim::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                free_claim_info.clone(),
+            ],
+            &[&[
+                FreeClaim::SEED,
+                auction_info.key.as_ref(),
+                wallet_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let token_amount = auction.free_claim_limit.min(auction.remaining());
+        auction.token_amount_for_sale -= token_amount;
+        auction.update_price_cumulative(current_time);
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        let free_claim = FreeClaim {
+            is_initialized: true,
+            amount: token_amount,
+        };
+        FreeClaim::pack(free_claim, &mut free_claim_info.data.borrow_mut())?;
+
+        // Transfer Tokens
+        Self::check_token_transfer(token_auction_info, token_customer_info, token_amount)?;
+        let token = Mint::unpack(&token_info.data.borrow())?;
+        invoke_signed(
+            &transfer_checked(
+                token_program_info.key,
+                token_auction_info.key,
+                token_info.key,
+                token_customer_info.key,
+                token_auction_owner_info.key,
+                &[],
+                token_amount,
+                token.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                token_auction_info.clone(),
+                token_customer_info.clone(),
+                token_auction_owner_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )
+    }
+}
@@ -0,0 +1,58 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::state::ClockOverride;
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_set_clock(
+        accounts: &[AccountInfo],
+        unix_timestamp: UnixTimestamp,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let funder_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let clock_override_info = next_account_info(account_info_iter)?;
+
+        let (address, bump_seed) = ClockOverride::find_address();
+        if &address != clock_override_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Create clock override account on first call
+        if clock_override_info.owner != &crate::id() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    funder_info.key,
+                    clock_override_info.key,
+                    Rent::get()?.minimum_balance(ClockOverride::LEN),
+                    ClockOverride::LEN as u64,
+                    &crate::id(),
+                ),
+                &[
+                    system_program_info.clone(),
+                    funder_info.clone(),
+                    clock_override_info.clone(),
+                ],
+                &[&[ClockOverride::SEED, &[bump_seed]]],
+            )?;
+        }
+
+        let clock_override = ClockOverride {
+            is_initialized: true,
+            unix_timestamp,
+        };
+        ClockOverride::pack(clock_override, &mut clock_override_info.data.borrow_mut())
+    }
+}
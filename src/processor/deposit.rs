@@ -0,0 +1,94 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{error::AuctionError, state::Deposit};
+
+use super::{Payer, Processor};
+
+impl Processor {
+    pub fn process_deposit_funds(accounts: &[AccountInfo], lamports: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+
+        let (address, bump_seed) = Deposit::find_address(wallet_info.key);
+        if &address != deposit_info.key {
+            return Err(AuctionError::InvalidDeposit.into());
+        }
+
+        // Create deposit account on first deposit
+        if deposit_info.owner != &crate::id() {
+            let rent = Rent::get()?.minimum_balance(Deposit::LEN);
+            invoke_signed(
+                &system_instruction::create_account(
+                    wallet_info.key,
+                    deposit_info.key,
+                    rent.checked_add(lamports)
+                        .ok_or(ProgramError::InvalidArgument)?,
+                    Deposit::LEN as u64,
+                    &crate::id(),
+                ),
+                &[
+                    system_program_info.clone(),
+                    wallet_info.clone(),
+                    deposit_info.clone(),
+                ],
+                &[&[Deposit::SEED, wallet_info.key.as_ref(), &[bump_seed]]],
+            )?;
+
+            let deposit = Deposit {
+                is_initialized: true,
+                wallet: *wallet_info.key,
+                order_nonce: 0,
+            };
+            Deposit::pack(deposit, &mut deposit_info.data.borrow_mut())?;
+            return Ok(());
+        }
+
+        invoke(
+            &system_instruction::transfer(wallet_info.key, deposit_info.key, lamports),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                deposit_info.clone(),
+            ],
+        )
+    }
+
+    pub fn process_withdraw_funds(accounts: &[AccountInfo], lamports: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let wallet_info = next_account_info(account_info_iter)?;
+        let deposit_info = next_account_info(account_info_iter)?;
+
+        Self::validate_deposit(wallet_info, deposit_info)?;
+
+        Payer::Program {
+            funder_info: deposit_info,
+            reserve: Rent::get()?.minimum_balance(Deposit::LEN),
+        }
+        .transfer(wallet_info, lamports)
+    }
+
+    pub(super) fn validate_deposit(
+        wallet_info: &AccountInfo,
+        deposit_info: &AccountInfo,
+    ) -> ProgramResult {
+        let (address, _) = Deposit::find_address(wallet_info.key);
+        if &address != deposit_info.key || deposit_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidDeposit.into());
+        }
+        let deposit = Deposit::unpack(&deposit_info.data.borrow())?;
+        Self::validate_owner(&deposit.wallet, wallet_info)
+    }
+}
@@ -0,0 +1,57 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account;
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, EscrowPolicy},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_sync_escrow(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+
+        let token_auction_owner =
+            Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id())?;
+        let address = get_associated_token_address(&token_auction_owner, &auction.token);
+        if &address != token_auction_info.key {
+            return Err(AuctionError::InvalidAuctionTokenAddress.into());
+        }
+
+        let token_auction = Account::unpack(&token_auction_info.data.borrow())?;
+        let token_amount_for_sale = match auction.escrow_policy {
+            EscrowPolicy::IgnoreExtras => auction.token_amount_for_sale.min(token_auction.amount),
+            EscrowPolicy::AddExtrasToSale => token_auction.amount,
+        };
+        if token_amount_for_sale != auction.token_amount_for_sale {
+            auction.token_amount_for_sale = token_amount_for_sale;
+            Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn process_poke(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        auction.update_price_cumulative(Self::unix_timestamp(accounts)?);
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+}
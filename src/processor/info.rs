@@ -0,0 +1,125 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, Quote},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_program_info() -> ProgramResult {
+        msg!("Name: {}", env!("CARGO_PKG_NAME"));
+        msg!("Version: {}", env!("CARGO_PKG_VERSION"));
+        // Set by build environment, e.g. `SOURCE_REVISION=$(git rev-parse HEAD)`
+        msg!(
+            "Source revision: {}",
+            option_env!("SOURCE_REVISION").unwrap_or("unknown")
+        );
+        msg!("Auction account size: {}", Auction::LEN);
+        Ok(())
+    }
+
+    pub fn process_view_quote(accounts: &[AccountInfo], token_amount: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let quote_info = next_account_info(account_info_iter)?;
+
+        if !wallet_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+
+        // Same checks and limits as bid
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_started(&auction, current_time)?;
+        let price = auction
+            .price_at(current_time)
+            .ok_or(AuctionError::Finished)?;
+        if auction.in_blackout(current_time) {
+            return Err(AuctionError::InBlackout.into());
+        }
+        if auction.remaining() == 0 {
+            return Err(AuctionError::EverythingSoldOut.into());
+        }
+        let step_available = auction.step_tokens_available(current_time);
+        if step_available == 0 {
+            return Err(AuctionError::StepSoldOut.into());
+        }
+
+        let token_amount = token_amount
+            .min(auction.remaining())
+            .min(step_available)
+            .min(auction.bid_tokens_available());
+        let cost = Auction::fill_cost(token_amount, price).ok_or(AuctionError::InvalidAmount)?;
+        let total = cost
+            .checked_add(auction.taker_fee(cost))
+            .ok_or(AuctionError::InvalidAmount)?;
+        msg!("Quote: {} tokens for {} lamports", token_amount, total);
+
+        // Quote account is reused by next views of wallet
+        let (address, bump_seed) = Quote::find_address(wallet_info.key);
+        if &address != quote_info.key {
+            return Err(AuctionError::InvalidQuote.into());
+        }
+        Self::create_view_account(
+            &[Quote::SEED, wallet_info.key.as_ref(), &[bump_seed]],
+            Quote::LEN,
+            wallet_info,
+            system_program_info,
+            quote_info,
+        )?;
+
+        let quote = Quote {
+            is_initialized: true,
+            auction: *auction_info.key,
+            token_amount,
+            price,
+            lamports: total,
+            quoted_at: current_time,
+        };
+        Quote::pack(quote, &mut quote_info.data.borrow_mut())
+    }
+
+    /// Create account for result of view instruction, if it was not created by
+    /// earlier view of the same wallet.
+    pub(super) fn create_view_account<'a>(
+        signer_seeds: &[&[u8]],
+        len: usize,
+        wallet_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        view_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if view_info.owner == &crate::id() {
+            return Ok(());
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                view_info.key,
+                Rent::get()?.minimum_balance(len),
+                len as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                view_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+}
@@ -0,0 +1,463 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{self, Sysvar},
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::{
+    instruction::{mint_to, set_authority, transfer_checked, AuthorityType},
+    state::{Account, Mint},
+};
+
+use crate::{
+    error::AuctionError,
+    instruction::{AuctionConfig, AuctionInstruction},
+    state::{Auction, PriceCurve, MAX_DENIED_PROGRAMS},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_initialize_auction(
+        accounts: &[AccountInfo],
+        config: &AuctionConfig,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        if config.time_start < Self::unix_timestamp(accounts)? {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        if config.time_step < 0 {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        if config.blackout_period < 0
+            || (config.blackout_period > 0
+                && (config.blackout_offset < 0
+                    || config.blackout_offset >= config.blackout_period
+                    || config.blackout_duration < 0
+                    || config.blackout_duration >= config.blackout_period))
+        {
+            return Err(AuctionError::InvalidBlackoutWindow.into());
+        }
+        if config.price_lock_skew < 0 {
+            return Err(AuctionError::InvalidQuoteTime.into());
+        }
+        if config.taker_fee_bps > Auction::BPS_DENOMINATOR {
+            return Err(AuctionError::InvalidTakerFee.into());
+        }
+        if config.price_curve == PriceCurve::Exponential
+            && config.price_step > Auction::BPS_DENOMINATOR as u64
+        {
+            return Err(AuctionError::InvalidDecayRate.into());
+        }
+        // Schedule is set by separate instruction
+        if config.price_curve == PriceCurve::Piecewise {
+            return Err(AuctionError::InvalidPriceSchedule.into());
+        }
+        if config.price_curve == PriceCurve::Ascending
+            && !Auction::is_valid_ascending(config.price_start, config.price_ceiling)
+        {
+            return Err(AuctionError::InvalidPriceCeiling.into());
+        }
+        if config.update_delay < 0 {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        if config.min_buyer_auctions > 0 && !config.buyer_profiles {
+            return Err(AuctionError::InvalidBuyerProfile.into());
+        }
+        let valid_free_claim_end = if config.allow_zero_price {
+            config.free_claim_end > config.time_start
+        } else {
+            config.free_claim_end == 0
+        };
+        if config.allow_zero_price != (config.free_claim_limit > 0) || !valid_free_claim_end {
+            return Err(AuctionError::InvalidFreeClaim.into());
+        }
+        // Refunds are paid in SOL, clearing price is the lowest fill price so
+        // it can not go below zero price of free claims and price should only
+        // decrease
+        if config.uniform_price
+            && (config.payment_mint.is_some()
+                || config.allow_zero_price
+                || config.price_curve == PriceCurve::Ascending)
+        {
+            return Err(AuctionError::InvalidUniformPrice.into());
+        }
+        if config.time_end != 0 && config.time_end <= config.time_start {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        // Sealed bids are committed and revealed in windows after start
+        if config.commit_end != 0 || config.reveal_end != 0 {
+            if config.time_start >= config.commit_end || config.commit_end >= config.reveal_end {
+                return Err(AuctionError::InvalidInitializationTime.into());
+            }
+            if config.payment_mint.is_some()
+                || config.allow_zero_price
+                || config.uniform_price
+                || config.price_curve == PriceCurve::Ascending
+            {
+                return Err(AuctionError::InvalidSealedAuction.into());
+            }
+        }
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let atoken_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_source_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_authority_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        // Rent payer may be separate subsidy wallet, not only token owner
+        if !funder_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let address = Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id());
+        if address.as_ref() != Ok(token_auction_owner_info.key) {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+
+        let address = get_associated_token_address(token_auction_owner_info.key, token_info.key);
+        if &address != token_auction_info.key {
+            return Err(AuctionError::InvalidAuctionTokenAddress.into());
+        }
+
+        let token = Mint::unpack(&token_info.data.borrow())?;
+        let token_amount = config
+            .amount_basis
+            .to_base_units(config.token_amount, token.decimals)
+            .ok_or(AuctionError::InvalidAmount)?;
+
+        // Initialize auction
+        let mut auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+        if auction.is_initialized {
+            return Err(AuctionError::AlreadyInUse.into());
+        }
+
+        auction.is_initialized = true;
+        auction.authority = *auction_authority_info.key;
+        auction.token = *token_info.key;
+        auction.time_start = config.time_start;
+        auction.time_step = config.time_step;
+        auction.price_start = config.price_start;
+        auction.price_step = config.price_step;
+        auction.token_amount_for_sale = token_amount;
+        auction.escrow_policy = config.escrow_policy;
+        auction.price_cumulative = 0;
+        auction.price_cumulative_updated_at = config.time_start;
+        auction.hook_program = config.hook_program.into();
+        auction.allowlist_enabled = config.allowlist_enabled;
+        auction.blackout_period = config.blackout_period;
+        auction.blackout_offset = config.blackout_offset;
+        auction.blackout_duration = config.blackout_duration;
+        auction.blackout_pauses_price = config.blackout_pauses_price;
+        auction.price_lock_skew = config.price_lock_skew;
+        auction.finalization_policy = config.finalization_policy;
+        auction.max_tokens_per_step = config.max_tokens_per_step;
+        auction.step_sold_index = 0;
+        auction.step_sold_amount = 0;
+        auction.price_curve = config.price_curve;
+        auction.taker_fee_bps = config.taker_fee_bps;
+        auction.update_delay = config.update_delay;
+        auction.listing_marker = config.unique_listing;
+        auction.cosigner = config.cosigner.into();
+        auction.buyer_profiles = config.buyer_profiles;
+        auction.min_buyer_auctions = config.min_buyer_auctions;
+        auction.tokens_sold = 0;
+        auction.proceeds = 0;
+        auction.last_price = 0;
+        auction.allow_zero_price = config.allow_zero_price;
+        auction.free_claim_limit = config.free_claim_limit;
+        auction.tip_recipient = config.tip_recipient.into();
+        auction.tips = 0;
+        auction.denied_programs = [Pubkey::default(); MAX_DENIED_PROGRAMS];
+        for (slot, program) in auction
+            .denied_programs
+            .iter_mut()
+            .zip(&config.denied_programs)
+        {
+            *slot = *program;
+        }
+        auction.price_milestones = false;
+        auction.price_floor = 0;
+        auction.ended_at = 0;
+        auction.buy_now_price = config.buy_now_price;
+        auction.merkle_root = config.merkle_root;
+        auction.max_bid_amount = config.max_bid_amount;
+        auction.payment_mint = config.payment_mint.into();
+        auction.price_ceiling = config.price_ceiling;
+        auction.uniform_price = config.uniform_price;
+        auction.clearing_price = 0;
+        auction.refunded = 0;
+        auction.commit_end = config.commit_end;
+        auction.reveal_end = config.reveal_end;
+        auction.time_end = config.time_end;
+        auction.free_claim_end = config.free_claim_end;
+
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        // Create derived account for SOL
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                token_auction_owner_info.key,
+                Rent::get()?.minimum_balance(0),
+                0,
+                system_program_info.key,
+            ),
+            &[
+                system_program_info.clone(),
+                funder_info.clone(),
+                token_auction_owner_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )?;
+
+        Self::create_admin_log(
+            auction_info,
+            admin_log_info,
+            funder_info,
+            system_program_info,
+        )?;
+
+        if config.unique_listing {
+            let listing_marker_info = next_account_info(account_info_iter)?;
+            Self::create_listing_marker(
+                auction_info,
+                auction_authority_info,
+                token_info,
+                listing_marker_info,
+                funder_info,
+                system_program_info,
+            )?;
+        }
+
+        // Create payment token account for proceeds
+        if let Some(payment_mint) = config.payment_mint {
+            let payment_mint_info = next_account_info(account_info_iter)?;
+            let payment_auction_info = next_account_info(account_info_iter)?;
+            if payment_mint_info.key != &payment_mint {
+                return Err(AuctionError::InvalidPaymentMint.into());
+            }
+            let address = get_associated_token_address(token_auction_owner_info.key, &payment_mint);
+            if &address != payment_auction_info.key {
+                return Err(AuctionError::InvalidAuctionPaymentAddress.into());
+            }
+            invoke(
+                &create_associated_token_account(
+                    funder_info.key,
+                    token_auction_owner_info.key,
+                    payment_mint_info.key,
+                ),
+                &[
+                    atoken_program_info.clone(),
+                    funder_info.clone(),
+                    payment_auction_info.clone(),
+                    token_auction_owner_info.clone(),
+                    payment_mint_info.clone(),
+                    system_program_info.clone(),
+                    token_program_info.clone(),
+                    rent_sysvar_info.clone(),
+                ],
+            )?;
+        }
+
+        // Tokens already transferred, only verify balance
+        if config.assume_prefunded {
+            if token_auction_info.owner != token_program_info.key {
+                return Err(AuctionError::InvalidAuctionTokenAddress.into());
+            }
+            let token_auction = Account::unpack(&token_auction_info.data.borrow())?;
+            if token_auction.owner != *token_auction_owner_info.key
+                || token_auction.mint != *token_info.key
+            {
+                return Err(AuctionError::InvalidAuctionTokenAddress.into());
+            }
+            if token_auction.amount < token_amount {
+                return Err(AuctionError::InsufficientPrefundedTokens.into());
+            }
+            return Ok(());
+        }
+
+        // Create derived account for token
+        invoke(
+            &create_associated_token_account(
+                funder_info.key,
+                token_auction_owner_info.key,
+                token_info.key,
+            ),
+            &[
+                atoken_program_info.clone(),
+                funder_info.clone(),
+                token_auction_info.clone(),
+                token_auction_owner_info.clone(),
+                token_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                rent_sysvar_info.clone(),
+            ],
+        )?;
+
+        // Move tokens
+        Self::check_token_transfer(token_source_info, token_auction_info, token_amount)?;
+        invoke(
+            &transfer_checked(
+                token_program_info.key,
+                token_source_info.key,
+                token_info.key,
+                token_auction_info.key,
+                token_authority_info.key,
+                &[],
+                token_amount,
+                token.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                token_source_info.clone(),
+                token_auction_info.clone(),
+                token_authority_info.clone(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_initialize_launch(accounts: &[AccountInfo], token_amount: u64) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let rent_sysvar_info = next_account_info(account_info_iter)?;
+        let atoken_program_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let mint_authority_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+
+        // Launch only for new auction
+        if !auction_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if auction_info.owner != &crate::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+        if auction.is_initialized {
+            return Err(AuctionError::AlreadyInUse.into());
+        }
+
+        // Minted tokens are locked only by initialized auction, so next
+        // instruction should initialize it with exactly minted amount
+        if instructions_info.key != &sysvar::instructions::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let data = instructions_info.data.borrow();
+        let index = sysvar::instructions::load_current_index(&data) as usize + 1;
+        let instruction = sysvar::instructions::load_instruction_at(index, &data)
+            .map_err(|_| AuctionError::InvalidLaunchInitialization)?;
+        let valid = instruction.program_id == crate::id()
+            && instruction.accounts.first().map(|meta| &meta.pubkey) == Some(auction_info.key)
+            && instruction.accounts.get(7).map(|meta| &meta.pubkey) == Some(token_info.key)
+            && matches!(
+                AuctionInstruction::unpack(&instruction.data),
+                Ok(AuctionInstruction::InitializeAuction { config })
+                    if config.assume_prefunded && config.token_amount == token_amount
+            );
+        if !valid {
+            return Err(AuctionError::InvalidLaunchInitialization.into());
+        }
+
+        let address = Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id());
+        if address.as_ref() != Ok(token_auction_owner_info.key) {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+
+        let address = get_associated_token_address(token_auction_owner_info.key, token_info.key);
+        if &address != token_auction_info.key {
+            return Err(AuctionError::InvalidAuctionTokenAddress.into());
+        }
+
+        // Whole supply should be minted by auction
+        let token = Mint::unpack(&token_info.data.borrow())?;
+        if token.supply != 0
+            || token.mint_authority != COption::Some(*mint_authority_info.key)
+            || token.freeze_authority.is_some()
+        {
+            return Err(AuctionError::InvalidLaunchMint.into());
+        }
+
+        // Create derived account for token
+        invoke(
+            &create_associated_token_account(
+                funder_info.key,
+                token_auction_owner_info.key,
+                token_info.key,
+            ),
+            &[
+                atoken_program_info.clone(),
+                funder_info.clone(),
+                token_auction_info.clone(),
+                token_auction_owner_info.clone(),
+                token_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+                rent_sysvar_info.clone(),
+            ],
+        )?;
+
+        // Mint tokens for sale
+        invoke(
+            &mint_to(
+                token_program_info.key,
+                token_info.key,
+                token_auction_info.key,
+                mint_authority_info.key,
+                &[],
+                token_amount,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                token_auction_info.clone(),
+                mint_authority_info.clone(),
+            ],
+        )?;
+
+        // Disable future minting
+        invoke(
+            &set_authority(
+                token_program_info.key,
+                token_info.key,
+                None,
+                AuthorityType::MintTokens,
+                mint_authority_info.key,
+                &[],
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                mint_authority_info.clone(),
+            ],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+    system_instruction, sysvar::Sysvar,
+};
+
+use crate::{error::AuctionError, state::ListingMarker};
+
+use super::Processor;
+
+impl Processor {
+    pub(super) fn create_listing_marker<'a>(
+        auction_info: &AccountInfo<'a>,
+        auction_authority_info: &AccountInfo<'a>,
+        token_info: &AccountInfo<'a>,
+        listing_marker_info: &AccountInfo<'a>,
+        funder_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (address, bump_seed) =
+            ListingMarker::find_address(auction_authority_info.key, token_info.key);
+        if &address != listing_marker_info.key {
+            return Err(AuctionError::InvalidListingMarker.into());
+        }
+        if listing_marker_info.owner == &crate::id() && listing_marker_info.lamports() > 0 {
+            return Err(AuctionError::DuplicateListing.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                listing_marker_info.key,
+                Rent::get()?.minimum_balance(ListingMarker::LEN),
+                ListingMarker::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                funder_info.clone(),
+                listing_marker_info.clone(),
+            ],
+            &[&[
+                ListingMarker::SEED,
+                auction_authority_info.key.as_ref(),
+                token_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let listing_marker = ListingMarker {
+            is_initialized: true,
+            auction: *auction_info.key,
+            rent_payer: *funder_info.key,
+        };
+        ListingMarker::pack(listing_marker, &mut listing_marker_info.data.borrow_mut())
+    }
+
+    // Marker closed on first `WithdrawTokens`, later calls do nothing.
+    pub(super) fn close_listing_marker(
+        auction_info: &AccountInfo,
+        authority: &Pubkey,
+        token_info: &AccountInfo,
+        listing_marker_info: &AccountInfo,
+        dest_info: &AccountInfo,
+    ) -> ProgramResult {
+        let (address, _) = ListingMarker::find_address(authority, token_info.key);
+        if &address != listing_marker_info.key {
+            return Err(AuctionError::InvalidListingMarker.into());
+        }
+        if listing_marker_info.owner != &crate::id() || listing_marker_info.lamports() == 0 {
+            return Ok(());
+        }
+        let listing_marker = ListingMarker::unpack(&listing_marker_info.data.borrow())?;
+        if &listing_marker.auction != auction_info.key {
+            // Marker of newer auction for the same token
+            return Ok(());
+        }
+        if dest_info.key != &listing_marker.rent_payer {
+            return Err(AuctionError::InvalidRentPayer.into());
+        }
+
+        let lamports = listing_marker_info.lamports();
+        **listing_marker_info.lamports.borrow_mut() = 0;
+        **dest_info.lamports.borrow_mut() = dest_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        ListingMarker::pack(
+            ListingMarker::default(),
+            &mut listing_marker_info.data.borrow_mut(),
+        )
+    }
+}
@@ -0,0 +1,113 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{
+        AdminAction, AdminLogEntry, Auction, PriceMilestone, PriceMilestones, MAX_PRICE_MILESTONES,
+    },
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_set_price_milestones(
+        accounts: &[AccountInfo],
+        milestones: &[PriceMilestone],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let price_milestones_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        // Bidders should know rules before first bid
+        let current_time = Self::unix_timestamp(accounts)?;
+        if auction.time_start <= current_time {
+            return Err(AuctionError::AlreadyStarted.into());
+        }
+        if !PriceMilestones::is_valid(milestones) {
+            return Err(AuctionError::InvalidPriceMilestones.into());
+        }
+
+        let (address, bump_seed) = PriceMilestones::find_address(auction_info.key);
+        if &address != price_milestones_info.key {
+            return Err(AuctionError::InvalidPriceMilestones.into());
+        }
+
+        // Create account on first call, otherwise replace previous rules
+        if price_milestones_info.owner != &crate::id() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    funder_info.key,
+                    price_milestones_info.key,
+                    Rent::get()?.minimum_balance(PriceMilestones::LEN),
+                    PriceMilestones::LEN as u64,
+                    &crate::id(),
+                ),
+                &[
+                    system_program_info.clone(),
+                    funder_info.clone(),
+                    price_milestones_info.clone(),
+                ],
+                &[&[
+                    PriceMilestones::SEED,
+                    auction_info.key.as_ref(),
+                    &[bump_seed],
+                ]],
+            )?;
+        }
+
+        let mut price_milestones = PriceMilestones {
+            is_initialized: true,
+            count: milestones.len() as u8,
+            reached: 0,
+            milestones: [PriceMilestone::default(); MAX_PRICE_MILESTONES],
+        };
+        price_milestones.milestones[..milestones.len()].copy_from_slice(milestones);
+        PriceMilestones::pack(
+            price_milestones,
+            &mut price_milestones_info.data.borrow_mut(),
+        )?;
+
+        auction.price_milestones = true;
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::SetPriceMilestones,
+                time: current_time,
+                key: *price_milestones_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub(super) fn unpack_price_milestones(
+        auction: &Pubkey,
+        price_milestones_info: &AccountInfo,
+    ) -> Result<PriceMilestones, ProgramError> {
+        let (address, _) = PriceMilestones::find_address(auction);
+        if &address != price_milestones_info.key || price_milestones_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidPriceMilestones.into());
+        }
+        PriceMilestones::unpack(&price_milestones_info.data.borrow())
+    }
+}
@@ -0,0 +1,413 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::{Clock, UnixTimestamp},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{self, Sysvar},
+};
+use spl_token::state::{Mint, Multisig};
+
+#[cfg(feature = "test-clock")]
+use crate::state::ClockOverride;
+use crate::{
+    error::AuctionError,
+    instruction::AuctionInstruction,
+    state::{AmountBasis, Auction},
+};
+
+mod admin_log;
+mod allowlist;
+mod bid;
+mod claim;
+#[cfg(feature = "test-clock")]
+mod clock;
+mod deposit;
+mod escrow;
+mod info;
+mod initialize;
+mod listing;
+mod milestones;
+mod payment;
+mod preflight;
+mod profile;
+mod refund;
+mod sealed;
+mod session;
+mod settle;
+mod update;
+mod withdraw;
+
+// Source of lamports for bid.
+enum Payer<'a, 'b> {
+    // System account which signed transaction.
+    System {
+        system_program_info: &'a AccountInfo<'b>,
+        funder_info: &'a AccountInfo<'b>,
+    },
+    // Account owned by this program, everything above `reserve` can be spent.
+    Program {
+        funder_info: &'a AccountInfo<'b>,
+        reserve: u64,
+    },
+}
+
+impl<'a, 'b> Payer<'a, 'b> {
+    fn transfer(&self, dest_info: &AccountInfo<'b>, lamports: u64) -> ProgramResult {
+        match *self {
+            Self::System {
+                system_program_info,
+                funder_info,
+            } => {
+                Processor::check_lamports(funder_info, funder_info.lamports(), lamports)?;
+                invoke(
+                    &system_instruction::transfer(funder_info.key, dest_info.key, lamports),
+                    &[
+                        system_program_info.clone(),
+                        funder_info.clone(),
+                        dest_info.clone(),
+                    ],
+                )
+            }
+            Self::Program {
+                funder_info,
+                reserve,
+            } => {
+                let available = funder_info.lamports().saturating_sub(reserve);
+                Processor::check_lamports(funder_info, available, lamports)?;
+                **funder_info.lamports.borrow_mut() -= lamports;
+                **dest_info.lamports.borrow_mut() = dest_info
+                    .lamports()
+                    .checked_add(lamports)
+                    .ok_or(ProgramError::InvalidArgument)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+pub struct Processor {}
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        if program_id != &crate::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let instruction = AuctionInstruction::unpack(input)?;
+        let (name, accounts_required) = Self::describe(&instruction);
+        msg!("Instruction: {}, accounts: {}", name, accounts.len());
+        if accounts.len() < accounts_required {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        match instruction {
+            AuctionInstruction::InitializeAuction { config } => {
+                Self::process_initialize_auction(accounts, &config)
+            }
+            AuctionInstruction::MakeBid {
+                token_amount,
+                amount_basis,
+                create_token_customer,
+                tip,
+                merkle_proof,
+            } => Self::process_bid(
+                accounts,
+                token_amount,
+                amount_basis,
+                None,
+                create_token_customer,
+                tip,
+                &merkle_proof,
+            ),
+            AuctionInstruction::InitializeLaunch { token_amount } => {
+                Self::process_initialize_launch(accounts, token_amount)
+            }
+            AuctionInstruction::MakeBidAtTime {
+                token_amount,
+                quoted_at,
+            } => Self::process_bid(
+                accounts,
+                token_amount,
+                AmountBasis::BaseUnits,
+                Some(quoted_at),
+                false,
+                0,
+                &[],
+            ),
+            AuctionInstruction::WithdrawTokens => Self::process_withdraw_tokens(accounts),
+            AuctionInstruction::WithdrawSOL => Self::process_withdraw_sol(accounts),
+            AuctionInstruction::SyncEscrow => Self::process_sync_escrow(accounts),
+            AuctionInstruction::Poke => Self::process_poke(accounts),
+            AuctionInstruction::AddToAllowlist => Self::process_add_to_allowlist(accounts),
+            AuctionInstruction::RemoveFromAllowlist => {
+                Self::process_remove_from_allowlist(accounts)
+            }
+            AuctionInstruction::AuthorizeSession {
+                lamports,
+                expires_at,
+            } => Self::process_authorize_session(accounts, lamports, expires_at),
+            AuctionInstruction::RevokeSession => Self::process_revoke_session(accounts),
+            AuctionInstruction::MakeSessionBid { token_amount } => {
+                Self::process_session_bid(accounts, token_amount)
+            }
+            AuctionInstruction::DepositFunds { lamports } => {
+                Self::process_deposit_funds(accounts, lamports)
+            }
+            AuctionInstruction::WithdrawFunds { lamports } => {
+                Self::process_withdraw_funds(accounts, lamports)
+            }
+            AuctionInstruction::MakeDepositBid { token_amount } => {
+                Self::process_deposit_bid(accounts, token_amount)
+            }
+            AuctionInstruction::ProgramInfo => Self::process_program_info(),
+            AuctionInstruction::MakeBundleBid {
+                max_lamports,
+                token_amounts,
+            } => Self::process_bundle_bid(accounts, max_lamports, token_amounts),
+            AuctionInstruction::ProposeUpdate {
+                max_tokens_per_step,
+                taker_fee_bps,
+            } => Self::process_propose_update(accounts, max_tokens_per_step, taker_fee_bps),
+            AuctionInstruction::ApplyUpdate => Self::process_apply_update(accounts),
+            AuctionInstruction::CreateBuyerProfile => Self::process_create_buyer_profile(accounts),
+            AuctionInstruction::Settle => Self::process_settle(accounts),
+            AuctionInstruction::ViewQuote { token_amount } => {
+                Self::process_view_quote(accounts, token_amount)
+            }
+            AuctionInstruction::ClaimFree => Self::process_claim_free(accounts),
+            AuctionInstruction::UpdateAuction {
+                time_start,
+                time_step,
+                price_start,
+                price_step,
+            } => Self::process_update_auction(
+                accounts,
+                time_start,
+                time_step,
+                price_start,
+                price_step,
+            ),
+            AuctionInstruction::SetPriceMilestones { milestones } => {
+                Self::process_set_price_milestones(accounts, &milestones)
+            }
+            AuctionInstruction::EndAuctionEarly => Self::process_end_auction_early(accounts),
+            AuctionInstruction::BuyNow => Self::process_buy_now(accounts),
+            AuctionInstruction::WithdrawPayment => Self::process_withdraw_payment(accounts),
+            AuctionInstruction::PreviewSettlement => Self::process_preview_settlement(accounts),
+            AuctionInstruction::MakeOrderBid {
+                token_amount,
+                max_price,
+                expires_at,
+                nonce,
+            } => Self::process_order_bid(accounts, token_amount, max_price, expires_at, nonce),
+            AuctionInstruction::SetPriceSchedule { breakpoints } => {
+                Self::process_set_price_schedule(accounts, &breakpoints)
+            }
+            AuctionInstruction::CreateBidReceipt => Self::process_create_bid_receipt(accounts),
+            AuctionInstruction::ClaimRefund => Self::process_claim_refund(accounts),
+            AuctionInstruction::CommitBid { commitment, escrow } => {
+                Self::process_commit_bid(accounts, commitment, escrow)
+            }
+            AuctionInstruction::RevealBid {
+                token_amount,
+                price,
+                salt,
+            } => Self::process_reveal_bid(accounts, token_amount, price, &salt),
+            AuctionInstruction::SettleSealedBids => Self::process_settle_sealed_bids(accounts),
+            AuctionInstruction::ClaimSealedBid => Self::process_claim_sealed_bid(accounts),
+            AuctionInstruction::MakeRelayedBid {
+                token_amount,
+                max_price,
+                expires_at,
+                nonce,
+            } => Self::process_relayed_bid(accounts, token_amount, max_price, expires_at, nonce),
+            #[cfg(feature = "test-clock")]
+            AuctionInstruction::SetClock { unix_timestamp } => {
+                Self::process_set_clock(accounts, unix_timestamp)
+            }
+        }
+    }
+
+    // Name for the entry log and the number of accounts every handler reads
+    // unconditionally, optional trailing accounts are not counted.
+    fn describe(instruction: &AuctionInstruction) -> (&'static str, usize) {
+        match instruction {
+            AuctionInstruction::InitializeAuction { .. } => ("InitializeAuction", 13),
+            AuctionInstruction::MakeBid { .. } => ("MakeBid", 8),
+            AuctionInstruction::WithdrawSOL => ("WithdrawSOL", 7),
+            AuctionInstruction::WithdrawTokens => ("WithdrawTokens", 8),
+            AuctionInstruction::SyncEscrow => ("SyncEscrow", 2),
+            AuctionInstruction::Poke => ("Poke", 1),
+            AuctionInstruction::AddToAllowlist => ("AddToAllowlist", 7),
+            AuctionInstruction::RemoveFromAllowlist => ("RemoveFromAllowlist", 6),
+            AuctionInstruction::AuthorizeSession { .. } => ("AuthorizeSession", 4),
+            AuctionInstruction::RevokeSession => ("RevokeSession", 2),
+            AuctionInstruction::MakeSessionBid { .. } => ("MakeSessionBid", 8),
+            AuctionInstruction::DepositFunds { .. } => ("DepositFunds", 3),
+            AuctionInstruction::WithdrawFunds { .. } => ("WithdrawFunds", 2),
+            AuctionInstruction::MakeDepositBid { .. } => ("MakeDepositBid", 8),
+            AuctionInstruction::MakeBidAtTime { .. } => ("MakeBidAtTime", 8),
+            AuctionInstruction::InitializeLaunch { .. } => ("InitializeLaunch", 11),
+            AuctionInstruction::ProgramInfo => ("ProgramInfo", 0),
+            AuctionInstruction::MakeBundleBid { .. } => ("MakeBundleBid", 8),
+            AuctionInstruction::ProposeUpdate { .. } => ("ProposeUpdate", 6),
+            AuctionInstruction::ApplyUpdate => ("ApplyUpdate", 4),
+            AuctionInstruction::CreateBuyerProfile => ("CreateBuyerProfile", 3),
+            AuctionInstruction::Settle => ("Settle", 4),
+            AuctionInstruction::ViewQuote { .. } => ("ViewQuote", 4),
+            AuctionInstruction::ClaimFree => ("ClaimFree", 9),
+            AuctionInstruction::UpdateAuction { .. } => ("UpdateAuction", 3),
+            AuctionInstruction::SetPriceMilestones { .. } => ("SetPriceMilestones", 6),
+            AuctionInstruction::EndAuctionEarly => ("EndAuctionEarly", 3),
+            AuctionInstruction::BuyNow => ("BuyNow", 8),
+            AuctionInstruction::WithdrawPayment => ("WithdrawPayment", 9),
+            AuctionInstruction::PreviewSettlement => ("PreviewSettlement", 6),
+            AuctionInstruction::MakeOrderBid { .. } => ("MakeOrderBid", 9),
+            AuctionInstruction::SetPriceSchedule { .. } => ("SetPriceSchedule", 3),
+            AuctionInstruction::CreateBidReceipt => ("CreateBidReceipt", 4),
+            AuctionInstruction::ClaimRefund => ("ClaimRefund", 5),
+            AuctionInstruction::CommitBid { .. } => ("CommitBid", 5),
+            AuctionInstruction::RevealBid { .. } => ("RevealBid", 4),
+            AuctionInstruction::SettleSealedBids => ("SettleSealedBids", 2),
+            AuctionInstruction::ClaimSealedBid => ("ClaimSealedBid", 9),
+            AuctionInstruction::MakeRelayedBid { .. } => ("MakeRelayedBid", 11),
+            #[cfg(feature = "test-clock")]
+            AuctionInstruction::SetClock { .. } => ("SetClock", 3),
+        }
+    }
+
+    fn validate_owner(expected_owner: &Pubkey, owner_account_info: &AccountInfo) -> ProgramResult {
+        if expected_owner != owner_account_info.key {
+            return Err(AuctionError::OwnerMismatch.into());
+        }
+        if !owner_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+
+    // Authority either signs itself or is spl-token multisig account, then at
+    // least `m` of its signers should be among instruction accounts and sign.
+    fn validate_multisig_owner(
+        expected_owner: &Pubkey,
+        owner_account_info: &AccountInfo,
+        signer_infos: &[AccountInfo],
+    ) -> ProgramResult {
+        if owner_account_info.is_signer
+            || owner_account_info.owner != &spl_token::id()
+            || owner_account_info.data_len() != Multisig::LEN
+        {
+            return Self::validate_owner(expected_owner, owner_account_info);
+        }
+        if expected_owner != owner_account_info.key {
+            return Err(AuctionError::OwnerMismatch.into());
+        }
+
+        let multisig = Multisig::unpack(&owner_account_info.data.borrow())?;
+        let signed = multisig.signers[..multisig.n as usize]
+            .iter()
+            .filter(|signer| {
+                signer_infos
+                    .iter()
+                    .any(|info| info.key == *signer && info.is_signer)
+            })
+            .count();
+        if signed < multisig.m as usize {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+
+    // Co-signer is next account if auction has it.
+    fn validate_cosigner<'a, 'b: 'a>(
+        auction: &Auction,
+        account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> ProgramResult {
+        match auction.cosigner {
+            COption::Some(ref cosigner) => {
+                Self::validate_owner(cosigner, next_account_info(account_info_iter)?)
+            }
+            COption::None => Ok(()),
+        }
+    }
+
+    // Current time, in `test-clock` builds taken from clock override account
+    // if it was passed with instruction.
+    #[cfg_attr(not(feature = "test-clock"), allow(unused_variables))]
+    fn unix_timestamp(accounts: &[AccountInfo]) -> Result<UnixTimestamp, ProgramError> {
+        #[cfg(feature = "test-clock")]
+        {
+            let (address, _) = ClockOverride::find_address();
+            let clock_override_info = accounts
+                .iter()
+                .find(|info| info.key == &address && info.owner == &crate::id());
+            if let Some(clock_override_info) = clock_override_info {
+                let clock_override = ClockOverride::unpack(&clock_override_info.data.borrow())?;
+                return Ok(clock_override.unix_timestamp);
+            }
+        }
+        Ok(Clock::get()?.unix_timestamp)
+    }
+
+    // Bid transaction should not call any program denied by auction, e.g.
+    // marketplace for instant resale of bought tokens. Instructions sysvar is
+    // looked up in all remaining accounts.
+    fn validate_denied_programs(auction: &Auction, accounts: &[AccountInfo]) -> ProgramResult {
+        if auction
+            .denied_programs
+            .iter()
+            .all(|program| program == &Pubkey::default())
+        {
+            return Ok(());
+        }
+        let instructions_info = accounts
+            .iter()
+            .find(|info| info.key == &sysvar::instructions::id())
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let data = instructions_info.data.borrow();
+        let mut index = 0;
+        while let Ok(instruction) = sysvar::instructions::load_instruction_at(index, &data) {
+            if auction.is_denied_program(&instruction.program_id) {
+                return Err(AuctionError::DeniedProgram.into());
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    // Initialized auction owned by this program, bids should not read state
+    // of account created by somebody else.
+    fn unpack_auction(auction_info: &AccountInfo) -> Result<Auction, ProgramError> {
+        if auction_info.owner != &crate::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Auction::unpack(&auction_info.data.borrow())
+    }
+
+    // Countdown is logged so wallets can show when bids open.
+    fn check_started(auction: &Auction, time: UnixTimestamp) -> ProgramResult {
+        if let Some(seconds) = auction.starts_in(time) {
+            msg!("Auction starts in {}s", seconds);
+            return Err(AuctionError::NotStarted.into());
+        }
+        Ok(())
+    }
+
+    fn get_price_at(
+        auction: &Auction,
+        token_info: &AccountInfo,
+        time: UnixTimestamp,
+    ) -> Result<(Mint, Option<u64>), ProgramError> {
+        let token = Mint::unpack(&token_info.data.borrow())?;
+
+        Self::check_started(auction, time)?;
+
+        // Calculate price and check that auction is not finished
+        Ok((token, auction.price_at(time)))
+    }
+}
@@ -0,0 +1,99 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{instruction::transfer_checked, state::Mint};
+
+use crate::{error::AuctionError, state::Auction};
+
+use super::{Payer, Processor};
+
+// Accounts of bid in auction paid with SPL token.
+pub(super) struct PaymentInfos<'a, 'b> {
+    mint_info: &'a AccountInfo<'b>,
+    source_info: &'a AccountInfo<'b>,
+    // Associated payment token account of auction SOL account.
+    auction_info: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> PaymentInfos<'a, 'b> {
+    pub(super) fn auction_info(&self) -> &'a AccountInfo<'b> {
+        self.auction_info
+    }
+}
+
+impl Processor {
+    // `token_auction_owner` is derived from auction address by caller, never
+    // taken from unchecked account.
+    pub(super) fn next_token_payment<'a, 'b: 'a>(
+        auction: &Auction,
+        token_auction_owner: &Pubkey,
+        account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> Result<Option<PaymentInfos<'a, 'b>>, ProgramError> {
+        let payment_mint = match auction.payment_mint {
+            COption::Some(payment_mint) => payment_mint,
+            COption::None => return Ok(None),
+        };
+        let mint_info = next_account_info(account_info_iter)?;
+        let source_info = next_account_info(account_info_iter)?;
+        let auction_info = next_account_info(account_info_iter)?;
+        if mint_info.key != &payment_mint {
+            return Err(AuctionError::InvalidPaymentMint.into());
+        }
+        let address = get_associated_token_address(token_auction_owner, &payment_mint);
+        if &address != auction_info.key {
+            return Err(AuctionError::InvalidAuctionPaymentAddress.into());
+        }
+        Ok(Some(PaymentInfos {
+            mint_info,
+            source_info,
+            auction_info,
+        }))
+    }
+
+    // Move `amount` of lamports or payment tokens from bid payer.
+    pub(super) fn pay<'b>(
+        payer: &Payer<'_, 'b>,
+        token_payment: Option<&PaymentInfos<'_, 'b>>,
+        token_program_info: &AccountInfo<'b>,
+        dest_info: &AccountInfo<'b>,
+        amount: u64,
+    ) -> ProgramResult {
+        let token_payment = match token_payment {
+            Some(token_payment) => token_payment,
+            None => return payer.transfer(dest_info, amount),
+        };
+        // Tokens can be moved only with signature of source owner
+        let funder_info = match *payer {
+            Payer::System { funder_info, .. } => funder_info,
+            Payer::Program { .. } => return Err(AuctionError::InvalidPaymentMint.into()),
+        };
+        Self::check_token_transfer(token_payment.source_info, dest_info, amount)?;
+        let mint = Mint::unpack(&token_payment.mint_info.data.borrow())?;
+        invoke(
+            &transfer_checked(
+                token_program_info.key,
+                token_payment.source_info.key,
+                token_payment.mint_info.key,
+                dest_info.key,
+                funder_info.key,
+                &[],
+                amount,
+                mint.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_payment.mint_info.clone(),
+                token_payment.source_info.clone(),
+                dest_info.clone(),
+                funder_info.clone(),
+            ],
+        )
+    }
+}
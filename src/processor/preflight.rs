@@ -0,0 +1,61 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_pack::Pack,
+};
+use spl_token::state::{Account, AccountState};
+
+use crate::error::AuctionError;
+
+use super::Processor;
+
+// Failed CPI aborts transaction with error code of called program, which
+// wallets can not explain to user. Common failures are checked before CPI and
+// reported as `AuctionError`, anything else is left to called program.
+impl Processor {
+    pub(super) fn check_lamports(
+        payer_info: &AccountInfo,
+        available: u64,
+        lamports: u64,
+    ) -> ProgramResult {
+        if available < lamports {
+            msg!(
+                "Account {} has {} lamports available, {} required",
+                payer_info.key,
+                available,
+                lamports
+            );
+            return Err(AuctionError::InsufficientLamports.into());
+        }
+        Ok(())
+    }
+
+    pub(super) fn check_token_transfer(
+        source_info: &AccountInfo,
+        dest_info: &AccountInfo,
+        amount: u64,
+    ) -> ProgramResult {
+        if let Ok(source) = Account::unpack(&source_info.data.borrow()) {
+            Self::check_not_frozen(source_info, &source)?;
+            if source.amount < amount {
+                msg!(
+                    "Token account {} has {} tokens, {} required",
+                    source_info.key,
+                    source.amount,
+                    amount
+                );
+                return Err(AuctionError::InsufficientTokenBalance.into());
+            }
+        }
+        if let Ok(dest) = Account::unpack(&dest_info.data.borrow()) {
+            Self::check_not_frozen(dest_info, &dest)?;
+        }
+        Ok(())
+    }
+
+    fn check_not_frozen(account_info: &AccountInfo, account: &Account) -> ProgramResult {
+        if account.state == AccountState::Frozen {
+            msg!("Token account {} is frozen", account_info.key);
+            return Err(AuctionError::FrozenTokenAccount.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{error::AuctionError, state::BuyerProfile};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_create_buyer_profile(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let buyer_profile_info = next_account_info(account_info_iter)?;
+
+        let (address, bump_seed) = BuyerProfile::find_address(wallet_info.key);
+        if &address != buyer_profile_info.key {
+            return Err(AuctionError::InvalidBuyerProfile.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                buyer_profile_info.key,
+                Rent::get()?.minimum_balance(BuyerProfile::LEN),
+                BuyerProfile::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                buyer_profile_info.clone(),
+            ],
+            &[&[BuyerProfile::SEED, wallet_info.key.as_ref(), &[bump_seed]]],
+        )?;
+
+        let buyer_profile = BuyerProfile {
+            is_initialized: true,
+            wallet: *wallet_info.key,
+            ..BuyerProfile::default()
+        };
+        BuyerProfile::pack(buyer_profile, &mut buyer_profile_info.data.borrow_mut())
+    }
+
+    pub(super) fn unpack_buyer_profile(
+        wallet: &Pubkey,
+        buyer_profile_info: &AccountInfo,
+    ) -> Result<BuyerProfile, ProgramError> {
+        let (address, _) = BuyerProfile::find_address(wallet);
+        if &address != buyer_profile_info.key || buyer_profile_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidBuyerProfile.into());
+        }
+        BuyerProfile::unpack(&buyer_profile_info.data.borrow())
+    }
+}
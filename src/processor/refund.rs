@@ -0,0 +1,131 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, BidReceipt},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_create_bid_receipt(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let bid_receipt_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        if !auction.uniform_price {
+            return Err(AuctionError::InvalidBidReceipt.into());
+        }
+
+        let (address, bump_seed) = BidReceipt::find_address(auction_info.key, wallet_info.key);
+        if &address != bid_receipt_info.key {
+            return Err(AuctionError::InvalidBidReceipt.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                bid_receipt_info.key,
+                Rent::get()?.minimum_balance(BidReceipt::LEN),
+                BidReceipt::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                bid_receipt_info.clone(),
+            ],
+            &[&[
+                BidReceipt::SEED,
+                auction_info.key.as_ref(),
+                wallet_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let bid_receipt = BidReceipt {
+            is_initialized: true,
+            ..BidReceipt::default()
+        };
+        BidReceipt::pack(bid_receipt, &mut bid_receipt_info.data.borrow_mut())
+    }
+
+    pub fn process_claim_refund(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let bid_receipt_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        let mut bid_receipt =
+            Self::unpack_bid_receipt(auction_info.key, wallet_info.key, bid_receipt_info)?;
+        if bid_receipt.refunded {
+            return Err(AuctionError::RefundClaimed.into());
+        }
+
+        // Clearing price is final only when bids are not accepted anymore,
+        // same check as withdrawals
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_started(&auction, current_time)?;
+        if auction.price_at(current_time).is_some() {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        let token_auction_owner =
+            Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id())?;
+        if &token_auction_owner != token_auction_owner_info.key {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+
+        let refund = bid_receipt.refund(auction.clearing_price);
+        msg!("Refund: {} lamports", refund);
+        bid_receipt.refunded = true;
+        BidReceipt::pack(bid_receipt, &mut bid_receipt_info.data.borrow_mut())?;
+        if refund == 0 {
+            return Ok(());
+        }
+
+        auction.refunded = auction.refunded.saturating_add(refund);
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        invoke_signed(
+            &system_instruction::transfer(token_auction_owner_info.key, wallet_info.key, refund),
+            &[
+                system_program_info.clone(),
+                token_auction_owner_info.clone(),
+                wallet_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )
+    }
+
+    pub(super) fn unpack_bid_receipt(
+        auction: &Pubkey,
+        wallet: &Pubkey,
+        bid_receipt_info: &AccountInfo,
+    ) -> Result<BidReceipt, ProgramError> {
+        let (address, _) = BidReceipt::find_address(auction, wallet);
+        if &address != bid_receipt_info.key || bid_receipt_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidBidReceipt.into());
+        }
+        BidReceipt::unpack(&bid_receipt_info.data.borrow())
+    }
+}
@@ -0,0 +1,316 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    instruction::transfer_checked,
+    state::{Account, Mint},
+};
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, SealedBid, SealedBook},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_commit_bid(
+        accounts: &[AccountInfo],
+        commitment: [u8; 32],
+        escrow: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let sealed_bid_info = next_account_info(account_info_iter)?;
+        let sealed_book_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        if !auction.is_sealed() {
+            return Err(AuctionError::InvalidSealedAuction.into());
+        }
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_started(&auction, current_time)?;
+        if current_time >= auction.commit_end || auction.is_ended(current_time) {
+            return Err(AuctionError::SealedBidWindowClosed.into());
+        }
+        if escrow == 0 {
+            return Err(AuctionError::InvalidAmount.into());
+        }
+
+        // Book can be created by earlier commit
+        let (address, bump_seed) = SealedBook::find_address(auction_info.key);
+        if &address != sealed_book_info.key {
+            return Err(AuctionError::InvalidSealedBook.into());
+        }
+        if sealed_book_info.owner != &crate::id() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    wallet_info.key,
+                    sealed_book_info.key,
+                    Rent::get()?.minimum_balance(SealedBook::LEN),
+                    SealedBook::LEN as u64,
+                    &crate::id(),
+                ),
+                &[
+                    system_program_info.clone(),
+                    wallet_info.clone(),
+                    sealed_book_info.clone(),
+                ],
+                &[&[SealedBook::SEED, auction_info.key.as_ref(), &[bump_seed]]],
+            )?;
+            let sealed_book = SealedBook {
+                is_initialized: true,
+                ..SealedBook::default()
+            };
+            SealedBook::pack(sealed_book, &mut sealed_book_info.data.borrow_mut())?;
+        }
+
+        // Escrow is kept in bid account together with rent, single bid per
+        // wallet
+        let (address, bump_seed) = SealedBid::find_address(auction_info.key, wallet_info.key);
+        if &address != sealed_bid_info.key {
+            return Err(AuctionError::InvalidSealedBid.into());
+        }
+        let lamports = Rent::get()?
+            .minimum_balance(SealedBid::LEN)
+            .checked_add(escrow)
+            .ok_or(AuctionError::InvalidAmount)?;
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                sealed_bid_info.key,
+                lamports,
+                SealedBid::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                sealed_bid_info.clone(),
+            ],
+            &[&[
+                SealedBid::SEED,
+                auction_info.key.as_ref(),
+                wallet_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let sealed_bid = SealedBid {
+            is_initialized: true,
+            commitment,
+            escrow,
+            level: SealedBid::NO_LEVEL,
+            ..SealedBid::default()
+        };
+        SealedBid::pack(sealed_bid, &mut sealed_bid_info.data.borrow_mut())
+    }
+
+    pub fn process_reveal_bid(
+        accounts: &[AccountInfo],
+        token_amount: u64,
+        price: u64,
+        salt: &[u8; 32],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let sealed_bid_info = next_account_info(account_info_iter)?;
+        let sealed_book_info = next_account_info(account_info_iter)?;
+
+        if !wallet_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        let current_time = Self::unix_timestamp(accounts)?;
+        if current_time < auction.commit_end || auction.is_ended(current_time) {
+            return Err(AuctionError::SealedBidWindowClosed.into());
+        }
+
+        let mut sealed_bid =
+            Self::unpack_sealed_bid(auction_info.key, wallet_info.key, sealed_bid_info)?;
+        if sealed_bid.revealed {
+            return Err(AuctionError::InvalidSealedBid.into());
+        }
+        let commitment =
+            SealedBid::commitment(auction_info.key, wallet_info.key, token_amount, price, salt);
+        if commitment != sealed_bid.commitment {
+            return Err(AuctionError::InvalidCommitment.into());
+        }
+        if token_amount == 0 {
+            return Err(AuctionError::InvalidAmount.into());
+        }
+        let cost = Auction::fill_cost(token_amount, price).ok_or(AuctionError::InvalidAmount)?;
+        if cost > sealed_bid.escrow {
+            return Err(AuctionError::InsufficientEscrow.into());
+        }
+
+        let level = SealedBook::level_of(&auction, price);
+        if level != SealedBid::NO_LEVEL {
+            let mut sealed_book = Self::unpack_sealed_book(auction_info.key, sealed_book_info)?;
+            let demand = &mut sealed_book.demand[level as usize];
+            *demand = demand.saturating_add(token_amount);
+            SealedBook::pack(sealed_book, &mut sealed_book_info.data.borrow_mut())?;
+        }
+
+        sealed_bid.revealed = true;
+        sealed_bid.token_amount = token_amount;
+        sealed_bid.price = price;
+        sealed_bid.level = level;
+        SealedBid::pack(sealed_bid, &mut sealed_bid_info.data.borrow_mut())
+    }
+
+    pub fn process_settle_sealed_bids(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let sealed_book_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        let current_time = Self::unix_timestamp(accounts)?;
+        if !auction.is_ended(current_time) {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        let mut sealed_book = Self::unpack_sealed_book(auction_info.key, sealed_book_info)?;
+        if sealed_book.settled {
+            return Err(AuctionError::AlreadySettled.into());
+        }
+        sealed_book.settle(&auction, auction.remaining());
+        msg!(
+            "Clearing price: {}, filled: {}",
+            sealed_book.clearing_price,
+            sealed_book.tokens_owed
+        );
+        SealedBook::pack(sealed_book, &mut sealed_book_info.data.borrow_mut())
+    }
+
+    pub fn process_claim_sealed_bid(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let sealed_bid_info = next_account_info(account_info_iter)?;
+        let sealed_book_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_customer_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        let sealed_bid =
+            Self::unpack_sealed_bid(auction_info.key, wallet_info.key, sealed_bid_info)?;
+        let mut sealed_book = Self::unpack_sealed_book(auction_info.key, sealed_book_info)?;
+        if !sealed_book.settled {
+            return Err(AuctionError::NotSettled.into());
+        }
+
+        let token_auction_owner =
+            Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id())?;
+        if &token_auction_owner != token_auction_owner_info.key {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+
+        let fill = sealed_book.fill(&sealed_bid);
+        let cost = Auction::fill_cost(fill, sealed_book.clearing_price)
+            .ok_or(AuctionError::InvalidAmount)?;
+        if cost > sealed_bid.escrow {
+            return Err(AuctionError::InsufficientEscrow.into());
+        }
+        msg!("Filled: {}, cost: {}", fill, cost);
+
+        if fill > 0 {
+            let address = get_associated_token_address(&token_auction_owner, &auction.token);
+            if &address != token_auction_info.key {
+                return Err(AuctionError::InvalidAuctionTokenAddress.into());
+            }
+            let token_customer = Account::unpack(&token_customer_info.data.borrow())?;
+            if &token_customer.owner != wallet_info.key {
+                return Err(AuctionError::OwnerMismatch.into());
+            }
+
+            auction.token_amount_for_sale = auction.token_amount_for_sale.saturating_sub(fill);
+            auction.tokens_sold = auction.tokens_sold.saturating_add(fill);
+            auction.proceeds = auction.proceeds.saturating_add(cost);
+            auction.last_price = sealed_book.clearing_price;
+            Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+            let token = Mint::unpack(&token_info.data.borrow())?;
+            Self::check_token_transfer(token_auction_info, token_customer_info, fill)?;
+            invoke_signed(
+                &transfer_checked(
+                    token_program_info.key,
+                    token_auction_info.key,
+                    token_info.key,
+                    token_customer_info.key,
+                    token_auction_owner_info.key,
+                    &[],
+                    fill,
+                    token.decimals,
+                )?,
+                &[
+                    token_program_info.clone(),
+                    token_info.clone(),
+                    token_auction_info.clone(),
+                    token_customer_info.clone(),
+                    token_auction_owner_info.clone(),
+                ],
+                &[&[auction_info.key.as_ref()]],
+            )?;
+        }
+
+        sealed_book.record_claim(&sealed_bid, fill);
+        SealedBook::pack(sealed_book, &mut sealed_book_info.data.borrow_mut())?;
+
+        // Close bid account, cost goes to auction SOL account
+        let lamports = sealed_bid_info.lamports();
+        **sealed_bid_info.lamports.borrow_mut() = 0;
+        **token_auction_owner_info.lamports.borrow_mut() = token_auction_owner_info
+            .lamports()
+            .checked_add(cost)
+            .ok_or(ProgramError::InvalidArgument)?;
+        **wallet_info.lamports.borrow_mut() = wallet_info
+            .lamports()
+            .checked_add(lamports - cost)
+            .ok_or(ProgramError::InvalidArgument)?;
+        SealedBid::pack(SealedBid::default(), &mut sealed_bid_info.data.borrow_mut())
+    }
+
+    fn unpack_sealed_bid(
+        auction: &Pubkey,
+        wallet: &Pubkey,
+        sealed_bid_info: &AccountInfo,
+    ) -> Result<SealedBid, ProgramError> {
+        let (address, _) = SealedBid::find_address(auction, wallet);
+        if &address != sealed_bid_info.key || sealed_bid_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidSealedBid.into());
+        }
+        SealedBid::unpack(&sealed_bid_info.data.borrow())
+    }
+
+    pub(super) fn unpack_sealed_book(
+        auction: &Pubkey,
+        sealed_book_info: &AccountInfo,
+    ) -> Result<SealedBook, ProgramError> {
+        let (address, _) = SealedBook::find_address(auction);
+        if &address != sealed_book_info.key || sealed_book_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidSealedBook.into());
+        }
+        SealedBook::unpack(&sealed_book_info.data.borrow())
+    }
+}
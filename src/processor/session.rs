@@ -0,0 +1,97 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{error::AuctionError, state::Session};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_authorize_session(
+        accounts: &[AccountInfo],
+        lamports: u64,
+        expires_at: UnixTimestamp,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let session_info = next_account_info(account_info_iter)?;
+        let session_key_info = next_account_info(account_info_iter)?;
+
+        if expires_at <= Self::unix_timestamp(accounts)? {
+            return Err(AuctionError::SessionExpired.into());
+        }
+
+        let (address, bump_seed) = Session::find_address(wallet_info.key, session_key_info.key);
+        if &address != session_info.key {
+            return Err(AuctionError::InvalidSession.into());
+        }
+
+        // Session account hold rent and lamports which can be spent on bids
+        let rent = Rent::get()?.minimum_balance(Session::LEN);
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet_info.key,
+                session_info.key,
+                rent.checked_add(lamports)
+                    .ok_or(ProgramError::InvalidArgument)?,
+                Session::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                wallet_info.clone(),
+                session_info.clone(),
+            ],
+            &[&[
+                Session::SEED,
+                wallet_info.key.as_ref(),
+                session_key_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let session = Session {
+            is_initialized: true,
+            wallet: *wallet_info.key,
+            session_key: *session_key_info.key,
+            expires_at,
+        };
+        Session::pack(session, &mut session_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_revoke_session(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let wallet_info = next_account_info(account_info_iter)?;
+        let session_info = next_account_info(account_info_iter)?;
+
+        if session_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidSession.into());
+        }
+        let session = Session::unpack(&session_info.data.borrow())?;
+        Self::validate_owner(&session.wallet, wallet_info)?;
+
+        // Close session account
+        let lamports = session_info.lamports();
+        **session_info.lamports.borrow_mut() = 0;
+        **wallet_info.lamports.borrow_mut() = wallet_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Session::pack(Session::default(), &mut session_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{Auction, SettlementSummary},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_settle(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let settlement_summary_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+
+        // Check that auction sold out or finished
+        let current_time = Self::unix_timestamp(accounts)?;
+        if auction.remaining() > 0 {
+            Self::check_started(&auction, current_time)?;
+            if auction.price_at(current_time).is_some() {
+                return Err(AuctionError::NotFinished.into());
+            }
+        }
+
+        let (address, bump_seed) = SettlementSummary::find_address(auction_info.key);
+        if &address != settlement_summary_info.key {
+            return Err(AuctionError::InvalidSettlementSummary.into());
+        }
+        if settlement_summary_info.owner == &crate::id() {
+            return Err(AuctionError::AlreadyInUse.into());
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                funder_info.key,
+                settlement_summary_info.key,
+                Rent::get()?.minimum_balance(SettlementSummary::LEN),
+                SettlementSummary::LEN as u64,
+                &crate::id(),
+            ),
+            &[
+                system_program_info.clone(),
+                funder_info.clone(),
+                settlement_summary_info.clone(),
+            ],
+            &[&[
+                SettlementSummary::SEED,
+                auction_info.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let settlement_summary = SettlementSummary {
+            is_initialized: true,
+            auction: *auction_info.key,
+            clearing_price: auction.last_price,
+            tokens_sold: auction.tokens_sold,
+            proceeds: auction.proceeds,
+            settled_at: current_time,
+        };
+        SettlementSummary::pack(
+            settlement_summary,
+            &mut settlement_summary_info.data.borrow_mut(),
+        )
+    }
+}
@@ -0,0 +1,279 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::AuctionError,
+    state::{
+        AdminAction, AdminLogEntry, Auction, PendingUpdate, PriceBreakpoint, PriceCurve,
+        PriceSchedule,
+    },
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_propose_update(
+        accounts: &[AccountInfo],
+        max_tokens_per_step: u64,
+        taker_fee_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let pending_update_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+        if auction.update_delay == 0 {
+            return Err(AuctionError::UpdatesDisabled.into());
+        }
+        if taker_fee_bps > Auction::BPS_DENOMINATOR {
+            return Err(AuctionError::InvalidTakerFee.into());
+        }
+
+        let (address, bump_seed) = PendingUpdate::find_address(auction_info.key);
+        if &address != pending_update_info.key {
+            return Err(AuctionError::InvalidPendingUpdate.into());
+        }
+
+        // Create account on first proposal, otherwise replace previous one
+        let rent_payer = if pending_update_info.owner != &crate::id() {
+            invoke_signed(
+                &system_instruction::create_account(
+                    funder_info.key,
+                    pending_update_info.key,
+                    Rent::get()?.minimum_balance(PendingUpdate::LEN),
+                    PendingUpdate::LEN as u64,
+                    &crate::id(),
+                ),
+                &[
+                    system_program_info.clone(),
+                    funder_info.clone(),
+                    pending_update_info.clone(),
+                ],
+                &[&[PendingUpdate::SEED, auction_info.key.as_ref(), &[bump_seed]]],
+            )?;
+            *funder_info.key
+        } else {
+            PendingUpdate::unpack(&pending_update_info.data.borrow())?.rent_payer
+        };
+
+        let current_time = Self::unix_timestamp(accounts)?;
+        let pending_update = PendingUpdate {
+            is_initialized: true,
+            apply_after: current_time.saturating_add(auction.update_delay),
+            max_tokens_per_step,
+            taker_fee_bps,
+            rent_payer,
+        };
+        PendingUpdate::pack(pending_update, &mut pending_update_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::ProposeUpdate,
+                time: current_time,
+                key: *pending_update_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub fn process_apply_update(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let pending_update_info = next_account_info(account_info_iter)?;
+        let rent_payer_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+
+        let (address, _) = PendingUpdate::find_address(auction_info.key);
+        if &address != pending_update_info.key || pending_update_info.owner != &crate::id() {
+            return Err(AuctionError::InvalidPendingUpdate.into());
+        }
+        let pending_update = PendingUpdate::unpack(&pending_update_info.data.borrow())?;
+        if &pending_update.rent_payer != rent_payer_info.key {
+            return Err(AuctionError::InvalidRentPayer.into());
+        }
+
+        let current_time = Self::unix_timestamp(accounts)?;
+        if current_time < pending_update.apply_after {
+            return Err(AuctionError::UpdateNotReady.into());
+        }
+
+        auction.max_tokens_per_step = pending_update.max_tokens_per_step;
+        auction.taker_fee_bps = pending_update.taker_fee_bps;
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        // Close pending update account
+        let lamports = pending_update_info.lamports();
+        **pending_update_info.lamports.borrow_mut() = 0;
+        **rent_payer_info.lamports.borrow_mut() = rent_payer_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        PendingUpdate::pack(
+            PendingUpdate::default(),
+            &mut pending_update_info.data.borrow_mut(),
+        )?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::ApplyUpdate,
+                time: current_time,
+                key: *pending_update_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub fn process_update_auction(
+        accounts: &[AccountInfo],
+        time_start: UnixTimestamp,
+        time_step: UnixTimestamp,
+        price_start: u64,
+        price_step: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        let current_time = Self::unix_timestamp(accounts)?;
+        if auction.time_start <= current_time {
+            return Err(AuctionError::AlreadyStarted.into());
+        }
+        if time_start < current_time || time_step < 0 {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        // Windows set at initialization should still follow new start
+        if (auction.time_end != 0 && auction.time_end <= time_start)
+            || (auction.is_sealed() && auction.commit_end <= time_start)
+        {
+            return Err(AuctionError::InvalidInitializationTime.into());
+        }
+        if auction.free_claim_end != 0 && auction.free_claim_end <= time_start {
+            return Err(AuctionError::InvalidFreeClaim.into());
+        }
+        if auction.price_curve == PriceCurve::Exponential
+            && price_step > Auction::BPS_DENOMINATOR as u64
+        {
+            return Err(AuctionError::InvalidDecayRate.into());
+        }
+        if auction.price_curve == PriceCurve::Ascending
+            && !Auction::is_valid_ascending(price_start, auction.price_ceiling)
+        {
+            return Err(AuctionError::InvalidPriceCeiling.into());
+        }
+
+        auction.time_start = time_start;
+        auction.time_step = time_step;
+        auction.price_start = price_start;
+        auction.price_step = price_step;
+        auction.price_cumulative_updated_at = time_start;
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::UpdateAuction,
+                time: current_time,
+                key: *auction_authority_info.key,
+                amount: 0,
+            },
+        )
+    }
+
+    pub fn process_end_auction_early(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        // Nothing to end if price already reached zero
+        let current_time = Self::unix_timestamp(accounts)?;
+        if auction.price_at(current_time).is_none() {
+            return Err(AuctionError::Finished.into());
+        }
+
+        auction.update_price_cumulative(current_time);
+        auction.ended_at = current_time;
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::EndAuctionEarly,
+                time: current_time,
+                key: *auction_authority_info.key,
+                amount: auction.remaining(),
+            },
+        )
+    }
+
+    pub fn process_set_price_schedule(
+        accounts: &[AccountInfo],
+        breakpoints: &[PriceBreakpoint],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let mut auction = Auction::unpack(&auction_info.data.borrow())?;
+        Self::validate_owner(&auction.authority, auction_authority_info)?;
+
+        let current_time = Self::unix_timestamp(accounts)?;
+        if auction.time_start <= current_time {
+            return Err(AuctionError::AlreadyStarted.into());
+        }
+        // Levels of sealed bids should not rise
+        if auction.is_sealed() {
+            return Err(AuctionError::InvalidSealedAuction.into());
+        }
+        auction.price_schedule =
+            PriceSchedule::new(breakpoints).ok_or(AuctionError::InvalidPriceSchedule)?;
+        auction.price_curve = PriceCurve::Piecewise;
+        Auction::pack(auction, &mut auction_info.data.borrow_mut())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::SetPriceSchedule,
+                time: current_time,
+                key: *auction_authority_info.key,
+                amount: 0,
+            },
+        )
+    }
+}
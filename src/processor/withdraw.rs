@@ -0,0 +1,354 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{
+    instruction::{burn, transfer_checked},
+    state::{Account, Mint},
+};
+
+use crate::{
+    error::AuctionError,
+    state::{AdminAction, AdminLogEntry, Auction, FinalizationPolicy, SettlementPreview},
+};
+
+use super::Processor;
+
+impl Processor {
+    pub fn process_withdraw_tokens(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let token_dest_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+        match auction.finalization_policy {
+            FinalizationPolicy::ReturnToSeller => {
+                Self::validate_multisig_owner(
+                    &auction.authority,
+                    auction_authority_info,
+                    accounts,
+                )?;
+            }
+            FinalizationPolicy::Burn => {}
+            FinalizationPolicy::SendTo(owner) => {
+                let token_dest = Account::unpack(&token_dest_info.data.borrow())?;
+                if token_dest.owner != owner {
+                    return Err(AuctionError::OwnerMismatch.into());
+                }
+            }
+        }
+
+        // Check that auction finished
+        let current_time = Self::unix_timestamp(accounts)?;
+        let (token, current_price) = Self::get_price_at(&auction, token_info, current_time)?;
+        if current_price.is_some() || auction.in_free_claims(current_time) {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        if auction.listing_marker {
+            let listing_marker_info = next_account_info(account_info_iter)?;
+            let rent_payer_info = next_account_info(account_info_iter)?;
+            Self::close_listing_marker(
+                auction_info,
+                &auction.authority,
+                token_info,
+                listing_marker_info,
+                rent_payer_info,
+            )?;
+        }
+
+        // Filled sealed bids are claimed from the same escrow
+        let tokens_owed = if auction.is_sealed() {
+            let sealed_book_info = next_account_info(account_info_iter)?;
+            let sealed_book = Self::unpack_sealed_book(auction_info.key, sealed_book_info)?;
+            if !sealed_book.settled {
+                return Err(AuctionError::NotSettled.into());
+            }
+            sealed_book.tokens_owed
+        } else {
+            0
+        };
+
+        if auction.finalization_policy == FinalizationPolicy::ReturnToSeller {
+            Self::validate_cosigner(&auction, account_info_iter)?;
+        }
+
+        let token_auction = Account::unpack_unchecked(&token_auction_info.data.borrow())?;
+        let amount = token_auction.amount.saturating_sub(tokens_owed);
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::WithdrawTokens,
+                time: current_time,
+                key: *token_dest_info.key,
+                amount,
+            },
+        )?;
+
+        // Burn Tokens
+        if auction.finalization_policy == FinalizationPolicy::Burn {
+            return invoke_signed(
+                &burn(
+                    token_program_info.key,
+                    token_auction_info.key,
+                    token_info.key,
+                    token_auction_owner_info.key,
+                    &[],
+                    amount,
+                )?,
+                &[
+                    token_program_info.clone(),
+                    token_auction_info.clone(),
+                    token_info.clone(),
+                    token_auction_owner_info.clone(),
+                ],
+                &[&[auction_info.key.as_ref()]],
+            );
+        }
+
+        // Transfer Tokens
+        Self::check_token_transfer(token_auction_info, token_dest_info, amount)?;
+        invoke_signed(
+            &transfer_checked(
+                token_program_info.key,
+                token_auction_info.key,
+                token_info.key,
+                token_dest_info.key,
+                token_auction_owner_info.key,
+                &[],
+                amount,
+                token.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                token_info.clone(),
+                token_auction_info.clone(),
+                token_dest_info.clone(),
+                token_auction_owner_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_sol(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let dest_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+        Self::validate_multisig_owner(&auction.authority, auction_authority_info, accounts)?;
+        Self::validate_cosigner(&auction, account_info_iter)?;
+
+        // Check that auction finished
+        let current_time = Self::unix_timestamp(accounts)?;
+        let (_token, current_price) = Self::get_price_at(&auction, token_info, current_time)?;
+        if current_price.is_some() {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        // Refunds of uniform price auction stay until claimed
+        let amount = token_auction_owner_info
+            .lamports()
+            .saturating_sub(auction.refunds_outstanding());
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::WithdrawSOL,
+                time: current_time,
+                key: *dest_info.key,
+                amount,
+            },
+        )?;
+
+        invoke_signed(
+            &system_instruction::transfer(token_auction_owner_info.key, dest_info.key, amount),
+            &[
+                system_program_info.clone(),
+                token_auction_owner_info.clone(),
+                dest_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn process_withdraw_payment(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let auction_authority_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let token_info = next_account_info(account_info_iter)?;
+        let payment_mint_info = next_account_info(account_info_iter)?;
+        let payment_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let payment_dest_info = next_account_info(account_info_iter)?;
+        let admin_log_info = next_account_info(account_info_iter)?;
+
+        let auction = Auction::unpack_unchecked(&auction_info.data.borrow())?;
+        Self::validate_multisig_owner(&auction.authority, auction_authority_info, accounts)?;
+        Self::validate_cosigner(&auction, account_info_iter)?;
+        if auction.payment_mint != COption::Some(*payment_mint_info.key) {
+            return Err(AuctionError::InvalidPaymentMint.into());
+        }
+
+        // Check that auction finished
+        let current_time = Self::unix_timestamp(accounts)?;
+        let (_token, current_price) = Self::get_price_at(&auction, token_info, current_time)?;
+        if current_price.is_some() {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        let address =
+            get_associated_token_address(token_auction_owner_info.key, payment_mint_info.key);
+        if &address != payment_auction_info.key {
+            return Err(AuctionError::InvalidAuctionPaymentAddress.into());
+        }
+        let payment_auction = Account::unpack(&payment_auction_info.data.borrow())?;
+
+        Self::append_admin_log(
+            auction_info,
+            admin_log_info,
+            AdminLogEntry {
+                action: AdminAction::WithdrawPayment,
+                time: current_time,
+                key: *payment_dest_info.key,
+                amount: payment_auction.amount,
+            },
+        )?;
+
+        // Transfer payment tokens
+        let payment_mint = Mint::unpack(&payment_mint_info.data.borrow())?;
+        Self::check_token_transfer(
+            payment_auction_info,
+            payment_dest_info,
+            payment_auction.amount,
+        )?;
+        invoke_signed(
+            &transfer_checked(
+                token_program_info.key,
+                payment_auction_info.key,
+                payment_mint_info.key,
+                payment_dest_info.key,
+                token_auction_owner_info.key,
+                &[],
+                payment_auction.amount,
+                payment_mint.decimals,
+            )?,
+            &[
+                token_program_info.clone(),
+                payment_mint_info.clone(),
+                payment_auction_info.clone(),
+                payment_dest_info.clone(),
+                token_auction_owner_info.clone(),
+            ],
+            &[&[auction_info.key.as_ref()]],
+        )
+    }
+
+    pub fn process_preview_settlement(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let auction_info = next_account_info(account_info_iter)?;
+        let token_auction_info = next_account_info(account_info_iter)?;
+        let token_auction_owner_info = next_account_info(account_info_iter)?;
+        let wallet_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        let settlement_preview_info = next_account_info(account_info_iter)?;
+
+        if !wallet_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let auction = Auction::unpack(&auction_info.data.borrow())?;
+
+        // Same check as withdrawals
+        let current_time = Self::unix_timestamp(accounts)?;
+        Self::check_started(&auction, current_time)?;
+        if auction.price_at(current_time).is_some() || auction.in_free_claims(current_time) {
+            return Err(AuctionError::NotFinished.into());
+        }
+
+        let token_auction_owner =
+            Pubkey::create_program_address(&[auction_info.key.as_ref()], &crate::id())?;
+        if &token_auction_owner != token_auction_owner_info.key {
+            return Err(AuctionError::InvalidAuctionTokenOwnerAddress.into());
+        }
+        let address = get_associated_token_address(&token_auction_owner, &auction.token);
+        if &address != token_auction_info.key {
+            return Err(AuctionError::InvalidAuctionTokenAddress.into());
+        }
+
+        let token_auction = Account::unpack_unchecked(&token_auction_info.data.borrow())?;
+        let refunds_owed = auction.refunds_outstanding();
+        let mut settlement_preview = SettlementPreview {
+            is_initialized: true,
+            auction: *auction_info.key,
+            tokens: token_auction.amount,
+            finalization_policy: auction.finalization_policy,
+            proceeds: token_auction_owner_info
+                .lamports()
+                .saturating_sub(refunds_owed),
+            refunds_owed,
+            payment_proceeds: 0,
+            previewed_at: current_time,
+        };
+
+        if let COption::Some(payment_mint) = auction.payment_mint {
+            let payment_auction_info = next_account_info(account_info_iter)?;
+            let address = get_associated_token_address(&token_auction_owner, &payment_mint);
+            if &address != payment_auction_info.key {
+                return Err(AuctionError::InvalidAuctionPaymentAddress.into());
+            }
+            let payment_auction = Account::unpack(&payment_auction_info.data.borrow())?;
+            settlement_preview.payment_proceeds = payment_auction.amount;
+        }
+
+        // Preview account is reused by next previews of wallet
+        let (address, bump_seed) = SettlementPreview::find_address(wallet_info.key);
+        if &address != settlement_preview_info.key {
+            return Err(AuctionError::InvalidSettlementPreview.into());
+        }
+        Self::create_view_account(
+            &[
+                SettlementPreview::SEED,
+                wallet_info.key.as_ref(),
+                &[bump_seed],
+            ],
+            SettlementPreview::LEN,
+            wallet_info,
+            system_program_info,
+            settlement_preview_info,
+        )?;
+        SettlementPreview::pack(
+            settlement_preview,
+            &mut settlement_preview_info.data.borrow_mut(),
+        )
+    }
+}
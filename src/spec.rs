@@ -0,0 +1,351 @@
+//! Portable JSON spec of auction configuration, for re-creating auction on
+//! another cluster. Not compiled for program.
+//!
+//! Spec holds only what `InitializeAuction` and `SetPriceSchedule` accept,
+//! sale progress, milestones and cluster-specific accounts (authority, token
+//! mint, escrow) are not exported. Pubkeys and enums are stored in their
+//! `Display` form.
+
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use solana_program::{
+    clock::UnixTimestamp, instruction::Instruction, program_option::COption, pubkey::Pubkey,
+};
+
+use crate::{
+    error::BuilderError,
+    instruction::{initialize_auction, set_price_schedule, AuctionConfig, TokenSource},
+    state::{AmountBasis, Auction, EscrowPolicy, FinalizationPolicy, PriceBreakpoint, PriceCurve},
+};
+
+/// Configuration of auction, see `Auction` for meaning of fields.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuctionSpec {
+    // Tokens sold and not sold yet, in base units.
+    pub token_amount: u64,
+    pub time_start: UnixTimestamp,
+    pub time_step: UnixTimestamp,
+    pub price_start: u64,
+    pub price_step: u64,
+    #[serde(with = "text")]
+    pub escrow_policy: EscrowPolicy,
+    #[serde(with = "option_text")]
+    pub hook_program: Option<Pubkey>,
+    pub allowlist_enabled: bool,
+    pub blackout_period: UnixTimestamp,
+    pub blackout_offset: UnixTimestamp,
+    pub blackout_duration: UnixTimestamp,
+    pub blackout_pauses_price: bool,
+    pub price_lock_skew: UnixTimestamp,
+    #[serde(with = "text")]
+    pub finalization_policy: FinalizationPolicy,
+    pub max_tokens_per_step: u64,
+    #[serde(with = "text")]
+    pub price_curve: PriceCurve,
+    pub taker_fee_bps: u16,
+    pub update_delay: UnixTimestamp,
+    pub unique_listing: bool,
+    #[serde(with = "option_text")]
+    pub cosigner: Option<Pubkey>,
+    pub buyer_profiles: bool,
+    pub min_buyer_auctions: u64,
+    pub allow_zero_price: bool,
+    pub free_claim_limit: u64,
+    #[serde(with = "option_text")]
+    pub tip_recipient: Option<Pubkey>,
+    #[serde(with = "vec_text")]
+    pub denied_programs: Vec<Pubkey>,
+    pub buy_now_price: u64,
+    // Allowlist merkle root, hex encoded.
+    #[serde(with = "option_hex")]
+    pub merkle_root: Option<[u8; 32]>,
+    pub max_bid_amount: u64,
+    #[serde(with = "option_text")]
+    pub payment_mint: Option<Pubkey>,
+    pub price_ceiling: u64,
+    pub uniform_price: bool,
+    pub commit_end: UnixTimestamp,
+    pub reveal_end: UnixTimestamp,
+    pub time_end: UnixTimestamp,
+    pub free_claim_end: UnixTimestamp,
+    // Breakpoints of `PriceCurve::Piecewise`, empty for other curves.
+    pub price_schedule: Vec<BreakpointSpec>,
+}
+
+/// Single `PriceBreakpoint` of spec.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BreakpointSpec {
+    pub offset: UnixTimestamp,
+    pub price: u64,
+}
+
+impl AuctionSpec {
+    /// Spec of `auction` configuration as it is now.
+    pub fn export(auction: &Auction) -> Self {
+        Self {
+            token_amount: auction.remaining().saturating_add(auction.tokens_sold),
+            time_start: auction.time_start,
+            time_step: auction.time_step,
+            price_start: auction.price_start,
+            price_step: auction.price_step,
+            escrow_policy: auction.escrow_policy,
+            hook_program: option_key(auction.hook_program),
+            allowlist_enabled: auction.allowlist_enabled,
+            blackout_period: auction.blackout_period,
+            blackout_offset: auction.blackout_offset,
+            blackout_duration: auction.blackout_duration,
+            blackout_pauses_price: auction.blackout_pauses_price,
+            price_lock_skew: auction.price_lock_skew,
+            finalization_policy: auction.finalization_policy,
+            max_tokens_per_step: auction.max_tokens_per_step,
+            price_curve: auction.price_curve,
+            taker_fee_bps: auction.taker_fee_bps,
+            update_delay: auction.update_delay,
+            unique_listing: auction.listing_marker,
+            cosigner: option_key(auction.cosigner),
+            buyer_profiles: auction.buyer_profiles,
+            min_buyer_auctions: auction.min_buyer_auctions,
+            allow_zero_price: auction.allow_zero_price,
+            free_claim_limit: auction.free_claim_limit,
+            tip_recipient: option_key(auction.tip_recipient),
+            denied_programs: auction
+                .denied_programs
+                .iter()
+                .filter(|program| **program != Pubkey::default())
+                .copied()
+                .collect(),
+            buy_now_price: auction.buy_now_price,
+            merkle_root: Some(auction.merkle_root).filter(|root| *root != [0; 32]),
+            max_bid_amount: auction.max_bid_amount,
+            payment_mint: option_key(auction.payment_mint),
+            price_ceiling: auction.price_ceiling,
+            uniform_price: auction.uniform_price,
+            commit_end: auction.commit_end,
+            reveal_end: auction.reveal_end,
+            time_end: auction.time_end,
+            free_claim_end: auction.free_claim_end,
+            price_schedule: auction
+                .price_schedule
+                .breakpoints()
+                .iter()
+                .map(|breakpoint| BreakpointSpec {
+                    offset: breakpoint.offset,
+                    price: breakpoint.price,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("spec is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Instructions creating auction with this configuration. Piecewise
+    /// auction is initialized as linear and gets its schedule with
+    /// `SetPriceSchedule` in the same transaction, before it starts.
+    /// `token_auction_owner` should be program address derived from
+    /// `auction`, escrow is its associated token account.
+    pub fn import(
+        &self,
+        auction_pubkey: &Pubkey,
+        auction_authority_pubkey: &Pubkey,
+        funding_pubkey: &Pubkey,
+        token_pubkey: &Pubkey,
+        token_auction_owner_pubkey: &Pubkey,
+        token_source: &TokenSource,
+    ) -> Result<Vec<Instruction>, BuilderError> {
+        let price_curve = match self.price_curve {
+            PriceCurve::Piecewise => PriceCurve::Linear,
+            curve => curve,
+        };
+        let config = AuctionConfig {
+            token_amount: self.token_amount,
+            time_start: self.time_start,
+            time_step: self.time_step,
+            price_start: self.price_start,
+            price_step: self.price_step,
+            assume_prefunded: false,
+            escrow_policy: self.escrow_policy,
+            hook_program: self.hook_program,
+            allowlist_enabled: self.allowlist_enabled,
+            blackout_period: self.blackout_period,
+            blackout_offset: self.blackout_offset,
+            blackout_duration: self.blackout_duration,
+            blackout_pauses_price: self.blackout_pauses_price,
+            price_lock_skew: self.price_lock_skew,
+            finalization_policy: self.finalization_policy,
+            max_tokens_per_step: self.max_tokens_per_step,
+            amount_basis: AmountBasis::BaseUnits,
+            price_curve,
+            taker_fee_bps: self.taker_fee_bps,
+            update_delay: self.update_delay,
+            unique_listing: self.unique_listing,
+            cosigner: self.cosigner,
+            buyer_profiles: self.buyer_profiles,
+            min_buyer_auctions: self.min_buyer_auctions,
+            allow_zero_price: self.allow_zero_price,
+            free_claim_limit: self.free_claim_limit,
+            tip_recipient: self.tip_recipient,
+            denied_programs: self.denied_programs.clone(),
+            buy_now_price: self.buy_now_price,
+            merkle_root: self.merkle_root.unwrap_or_default(),
+            max_bid_amount: self.max_bid_amount,
+            payment_mint: self.payment_mint,
+            price_ceiling: self.price_ceiling,
+            uniform_price: self.uniform_price,
+            commit_end: self.commit_end,
+            reveal_end: self.reveal_end,
+            time_end: self.time_end,
+            free_claim_end: self.free_claim_end,
+        };
+        let mut instructions = vec![initialize_auction(
+            auction_pubkey,
+            auction_authority_pubkey,
+            funding_pubkey,
+            token_pubkey,
+            token_auction_owner_pubkey,
+            Some(token_source),
+            &config,
+        )?];
+        if self.price_curve == PriceCurve::Piecewise {
+            let breakpoints = self
+                .price_schedule
+                .iter()
+                .map(|breakpoint| PriceBreakpoint {
+                    offset: breakpoint.offset,
+                    price: breakpoint.price,
+                })
+                .collect::<Vec<_>>();
+            instructions.push(set_price_schedule(
+                auction_pubkey,
+                auction_authority_pubkey,
+                &breakpoints,
+            )?);
+        }
+        Ok(instructions)
+    }
+}
+
+fn option_key(key: COption<Pubkey>) -> Option<Pubkey> {
+    match key {
+        COption::Some(key) => Some(key),
+        COption::None => None,
+    }
+}
+
+// Value in its `Display` form.
+mod text {
+    use super::*;
+
+    pub fn serialize<T: fmt::Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// Optional value in its `Display` form, `null` for `None`.
+mod option_text {
+    use super::*;
+
+    pub fn serialize<T: fmt::Display, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| value.parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+// List of values in their `Display` form.
+mod vec_text {
+    use super::*;
+
+    pub fn serialize<T: fmt::Display, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|value| value.parse().map_err(de::Error::custom))
+            .collect()
+    }
+}
+
+// Optional 32 bytes as lowercase hex, `null` for `None`.
+mod option_hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<[u8; 32]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(bytes) => serializer.serialize_str(
+                &bytes
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<String>(),
+            ),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<[u8; 32]>, D::Error> {
+        let value = match Option::<String>::deserialize(deserializer)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let invalid = || de::Error::custom(format!("invalid hex `{}`", value));
+        if value.len() != 64 || !value.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0; 32];
+        for (byte, pair) in bytes.iter_mut().zip(value.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(Some(bytes))
+    }
+}
@@ -1,13 +1,36 @@
+use std::convert::TryFrom;
+
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use solana_program::{
     clock::UnixTimestamp,
+    hash::hashv,
     program_error::ProgramError,
+    program_option::COption,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
 
+use crate::price::PriceEngine;
+
+/// Maximum number of programs in `Auction::denied_programs`.
+pub const MAX_DENIED_PROGRAMS: usize = 2;
+// Packed size of `Auction::denied_programs`.
+const DENIED_LEN: usize = 32 * MAX_DENIED_PROGRAMS;
+
+/// Maximum number of rules in `PriceMilestones`.
+pub const MAX_PRICE_MILESTONES: usize = 4;
+
+/// Maximum number of breakpoints in `PriceSchedule`.
+pub const MAX_PRICE_BREAKPOINTS: usize = 8;
+
+/// Number of price levels of sealed bids, prices of first price steps.
+pub const MAX_SEALED_LEVELS: usize = 16;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
 pub struct Auction {
     /// Is `true` if this structure has been initialized
     pub is_initialized: bool,
@@ -25,6 +48,373 @@ pub struct Auction {
     pub price_start: u64,
     // Price change on each time step.
     pub price_step: u64,
+    // Tokens which still can be bought.
+    pub token_amount_for_sale: u64,
+    // What to do with tokens deposited to escrow outside of auction.
+    pub escrow_policy: EscrowPolicy,
+    // Accumulated price multiplied by seconds since auction start, for TWAP.
+    pub price_cumulative: u128,
+    // Last time when `price_cumulative` was updated.
+    pub price_cumulative_updated_at: UnixTimestamp,
+    // Program invoked after each successful bid.
+    #[cfg_attr(
+        not(target_arch = "bpf"),
+        serde(serialize_with = "crate::vectors::serialize_coption_key")
+    )]
+    pub hook_program: COption<Pubkey>,
+    // Only wallets with allowlist entry can make a bid.
+    pub allowlist_enabled: bool,
+    // Period of repeating blackout window, zero if disabled.
+    pub blackout_period: UnixTimestamp,
+    // Blackout window start relative to period start.
+    pub blackout_offset: UnixTimestamp,
+    // Blackout window duration.
+    pub blackout_duration: UnixTimestamp,
+    // Price does not change during blackout windows.
+    pub blackout_pauses_price: bool,
+    // Max age of bid quote time, zero if bids at quote time are disabled.
+    pub price_lock_skew: UnixTimestamp,
+    // What to do with unsold tokens when auction finished.
+    pub finalization_policy: FinalizationPolicy,
+    // Maximum amount of tokens sold in one price step, `0` for no limit.
+    pub max_tokens_per_step: u64,
+    // Price step of `step_sold_amount` counter.
+    pub step_sold_index: UnixTimestamp,
+    // Tokens sold in price step `step_sold_index`.
+    pub step_sold_amount: u64,
+    // Price curve, parameters of curve are in auction fields.
+    pub price_curve: PriceCurve,
+    // Fee paid by buyer on top of price to marketplace account of bid, in basis points.
+    pub taker_fee_bps: u16,
+    // Delay between proposed and applied parameter update, updates disabled if zero.
+    pub update_delay: UnixTimestamp,
+    // Listing marker for authority and token is held, closed by `WithdrawTokens`.
+    pub listing_marker: bool,
+    // Key which should sign authority withdrawals together with authority.
+    #[cfg_attr(
+        not(target_arch = "bpf"),
+        serde(serialize_with = "crate::vectors::serialize_coption_key")
+    )]
+    pub cosigner: COption<Pubkey>,
+    // Bids update buyer profile of wallet.
+    pub buyer_profiles: bool,
+    // Minimum `BuyerProfile::auctions` of wallet to make a bid, `0` for no limit.
+    pub min_buyer_auctions: u64,
+    // Tokens sold by bids, in base units.
+    pub tokens_sold: u64,
+    // Lamports paid for sold tokens, without fees.
+    pub proceeds: u64,
+    // Price per token of the latest fill.
+    pub last_price: u64,
+    // Remaining tokens can be claimed for free after price reach zero.
+    pub allow_zero_price: bool,
+    // Maximum amount of tokens in single free claim of wallet.
+    pub free_claim_limit: u64,
+    // Recipient of bid tips instead of auction SOL account.
+    #[cfg_attr(
+        not(target_arch = "bpf"),
+        serde(serialize_with = "crate::vectors::serialize_coption_key")
+    )]
+    pub tip_recipient: COption<Pubkey>,
+    // Lamports tipped by bidders, not included in proceeds.
+    pub tips: u64,
+    // Bids are rejected in transactions which call any of these programs, unused
+    // slots are default pubkeys.
+    pub denied_programs: [Pubkey; MAX_DENIED_PROGRAMS],
+    // Bids update price floor by rules in price milestones account.
+    pub price_milestones: bool,
+    // Price never decrease below floor while auction is live.
+    pub price_floor: u64,
+    // Time when authority ended auction early, zero if not ended.
+    pub ended_at: UnixTimestamp,
+    // Price per token to buy everything remaining at once, zero if disabled.
+    pub buy_now_price: u64,
+    // Root of `merkle` tree of wallets allowed to bid, zeroes if disabled.
+    pub merkle_root: [u8; 32],
+    // Maximum tokens filled by single bid, zero if not limited.
+    pub max_bid_amount: u64,
+    // Mint of SPL token used for payments instead of SOL.
+    #[cfg_attr(
+        not(target_arch = "bpf"),
+        serde(serialize_with = "crate::vectors::serialize_coption_key")
+    )]
+    pub payment_mint: COption<Pubkey>,
+    // Breakpoints of piecewise price curve, set by `SetPriceSchedule`.
+    pub price_schedule: PriceSchedule,
+    // Highest price of ascending price curve, auction finishes above it.
+    pub price_ceiling: u64,
+    // All buyers pay `clearing_price`, difference with bid price is refunded
+    // with `ClaimRefund` after auction finished.
+    pub uniform_price: bool,
+    // Lowest fill price of `uniform_price` auction.
+    pub clearing_price: u64,
+    // Total lamports refunded with `ClaimRefund`.
+    pub refunded: u64,
+    // End of sealed bid commit window, zero if auction takes open bids.
+    pub commit_end: UnixTimestamp,
+    // End of sealed bid reveal window, auction finishes at it.
+    pub reveal_end: UnixTimestamp,
+    // Bids are rejected and withdraws unlock at this time, zero if auction
+    // ends only when price reaches zero.
+    pub time_end: UnixTimestamp,
+    // End of free claims window of `allow_zero_price` auction, tokens can be
+    // withdrawn only after it.
+    pub free_claim_end: UnixTimestamp,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, FromPrimitive)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub enum EscrowPolicy {
+    // Extra tokens are not sold and returned with `WithdrawTokens`.
+    #[default]
+    IgnoreExtras,
+    // Extra tokens are added to sale on `SyncEscrow`.
+    AddExtrasToSale,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, FromPrimitive)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub enum PriceCurve {
+    // Price decrease by `price_step` on every step, see `price::Linear`.
+    #[default]
+    Linear,
+    // Price decrease by `price_step` basis points of previous price on every
+    // step, see `price::Exponential`.
+    Exponential,
+    // Price set by `price_schedule`, see `price::Piecewise`.
+    Piecewise,
+    // Price increase by `price_step` on every step until `price_ceiling`,
+    // see `price::Ascending`.
+    Ascending,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, FromPrimitive)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub enum AmountBasis {
+    // Amount in token base units, as in token account balance.
+    #[default]
+    BaseUnits,
+    // Amount in whole tokens, multiplied by 10^decimals of token mint.
+    WholeTokens,
+}
+
+impl AmountBasis {
+    /// Convert `amount` to token base units, `None` on overflow.
+    pub fn to_base_units(self, amount: u64, decimals: u8) -> Option<u64> {
+        match self {
+            Self::BaseUnits => Some(amount),
+            Self::WholeTokens => 10u64
+                .checked_pow(decimals as u32)
+                .and_then(|unit| amount.checked_mul(unit)),
+        }
+    }
+}
+
+impl Auction {
+    pub const BPS_DENOMINATOR: u16 = 10_000;
+
+    /// Ascending price should start above zero, otherwise auction is finished
+    /// from start, and should not exceed ceiling.
+    pub fn is_valid_ascending(price_start: u64, price_ceiling: u64) -> bool {
+        price_start > 0 && price_start <= price_ceiling
+    }
+
+    /// Integral of price over time from auction start to `time`, price is
+    /// zero before start and after auction finished.
+    pub fn price_time_integral(&self, time: UnixTimestamp) -> u128 {
+        if time <= self.time_start || self.time_step <= 0 {
+            return 0;
+        }
+
+        let engine = self.price_engine();
+        let elapsed = (time - self.time_start) as u128;
+        let time_step = self.time_step as u128;
+        let steps = (elapsed / time_step) as u64;
+
+        let mut integral = time_step.saturating_mul(engine.price_sum(steps));
+        if let Some(price) = engine.price(steps) {
+            integral = integral.saturating_add((elapsed % time_step) * price as u128);
+        }
+        integral
+    }
+
+    /// Number of price steps with positive price, `u64::MAX` if price never
+    /// reach zero.
+    pub fn steps_total(&self) -> u64 {
+        self.price_engine().steps_total()
+    }
+
+    /// Price per token at `time`, `None` if auction is finished. Start time
+    /// is not checked, so clients can quote bids before sending them.
+    pub fn price_at(&self, time: UnixTimestamp) -> Option<u64> {
+        if self.is_ended(time) {
+            return None;
+        }
+        self.price_engine()
+            .price(self.steps_elapsed(time))
+            .map(|price| price.max(self.price_floor))
+    }
+
+    /// Seconds left until start at `time`, `None` if auction already started
+    /// or was ended before start.
+    pub fn starts_in(&self, time: UnixTimestamp) -> Option<UnixTimestamp> {
+        if self.time_start > time && !self.is_ended(time) {
+            Some(self.time_start - time)
+        } else {
+            None
+        }
+    }
+
+    /// Are bids limited to wallets in `merkle_root` tree.
+    pub fn has_merkle_allowlist(&self) -> bool {
+        self.merkle_root != [0; 32]
+    }
+
+    /// Is auction ended early by authority, passed `time_end` or sealed bid
+    /// reveal window at `time`.
+    pub fn is_ended(&self, time: UnixTimestamp) -> bool {
+        (self.ended_at != 0 && time >= self.ended_at)
+            || (self.time_end != 0 && time >= self.time_end)
+            || (self.is_sealed() && time >= self.reveal_end)
+    }
+
+    /// Are bids committed and revealed instead of filled at current price.
+    pub fn is_sealed(&self) -> bool {
+        self.reveal_end != 0
+    }
+
+    /// Number of whole price steps passed at `time`, zero before start.
+    pub fn steps_elapsed(&self, time: UnixTimestamp) -> u64 {
+        if time <= self.time_start || self.time_step <= 0 {
+            return 0;
+        }
+        self.price_elapsed(time).div_euclid(self.time_step) as u64
+    }
+
+    /// Lamports paid for `token_amount` tokens at `price`, `None` if total
+    /// does not fit into lamports balance.
+    pub fn fill_cost(token_amount: u64, price: u64) -> Option<u64> {
+        u64::try_from(token_amount as u128 * price as u128).ok()
+    }
+
+    /// Is `program` in auction list of denied programs.
+    pub fn is_denied_program(&self, program: &Pubkey) -> bool {
+        program != &Pubkey::default() && self.denied_programs.contains(program)
+    }
+
+    /// Taker fee for bid of `cost` lamports, paid on top of cost.
+    pub fn taker_fee(&self, cost: u64) -> u64 {
+        (cost as u128 * self.taker_fee_bps as u128 / Self::BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Amount of tokens still available for sale.
+    pub fn remaining(&self) -> u64 {
+        self.token_amount_for_sale
+    }
+
+    /// Is `time` before end of free claims window, tokens can not be withdrawn.
+    pub fn in_free_claims(&self, time: UnixTimestamp) -> bool {
+        self.allow_zero_price && time < self.free_claim_end
+    }
+
+    /// Is `time` inside of repeating blackout window.
+    pub fn in_blackout(&self, time: UnixTimestamp) -> bool {
+        self.blackout_period > 0
+            && (time - self.blackout_offset).rem_euclid(self.blackout_period)
+                < self.blackout_duration
+    }
+
+    /// Seconds since auction start which are used for price calculation.
+    pub fn price_elapsed(&self, time: UnixTimestamp) -> UnixTimestamp {
+        let elapsed = time - self.time_start;
+        if self.blackout_pauses_price && self.blackout_period > 0 {
+            let blackouts =
+                self.blackout_seconds_before(time) - self.blackout_seconds_before(self.time_start);
+            elapsed - blackouts
+        } else {
+            elapsed
+        }
+    }
+
+    // Number of blackout seconds from first window start to `time`.
+    fn blackout_seconds_before(&self, time: UnixTimestamp) -> UnixTimestamp {
+        let time = time - self.blackout_offset;
+        time.div_euclid(self.blackout_period) * self.blackout_duration
+            + time
+                .rem_euclid(self.blackout_period)
+                .min(self.blackout_duration)
+    }
+
+    /// Tokens which still can be sold in price step at `time`, `u64::MAX` if
+    /// auction has no per-step limit.
+    pub fn step_tokens_available(&self, time: UnixTimestamp) -> u64 {
+        if self.max_tokens_per_step == 0 {
+            return u64::MAX;
+        }
+        if self.step_index(time) != self.step_sold_index {
+            return self.max_tokens_per_step;
+        }
+        self.max_tokens_per_step
+            .saturating_sub(self.step_sold_amount)
+    }
+
+    /// Tokens which can be filled by single bid, `u64::MAX` if not limited.
+    pub fn bid_tokens_available(&self) -> u64 {
+        if self.max_bid_amount == 0 {
+            return u64::MAX;
+        }
+        self.max_bid_amount
+    }
+
+    /// Count `amount` of tokens as sold in price step at `time`.
+    pub fn record_step_sale(&mut self, time: UnixTimestamp, amount: u64) {
+        let step = self.step_index(time);
+        if step != self.step_sold_index {
+            self.step_sold_index = step;
+            self.step_sold_amount = 0;
+        }
+        self.step_sold_amount = self.step_sold_amount.saturating_add(amount);
+    }
+
+    /// Lower clearing price of `uniform_price` auction to fill `price`,
+    /// should be called before fill is counted in `tokens_sold`.
+    pub fn record_clearing_price(&mut self, price: u64) {
+        if self.tokens_sold == 0 || price < self.clearing_price {
+            self.clearing_price = price;
+        }
+    }
+
+    /// Lamports of proceeds owed to buyers of `uniform_price` auction and
+    /// not claimed yet.
+    pub fn refunds_outstanding(&self) -> u64 {
+        if !self.uniform_price {
+            return 0;
+        }
+        let cost = Self::fill_cost(self.tokens_sold, self.clearing_price).unwrap_or(u64::MAX);
+        self.proceeds
+            .saturating_sub(cost)
+            .saturating_sub(self.refunded)
+    }
+
+    // Price step at `time`.
+    fn step_index(&self, time: UnixTimestamp) -> UnixTimestamp {
+        self.steps_elapsed(time) as UnixTimestamp
+    }
+
+    /// Move TWAP accumulator to `time`.
+    pub fn update_price_cumulative(&mut self, time: UnixTimestamp) {
+        if time <= self.price_cumulative_updated_at {
+            return;
+        }
+        let delta = self
+            .price_time_integral(time)
+            .saturating_sub(self.price_time_integral(self.price_cumulative_updated_at));
+        self.price_cumulative = self.price_cumulative.wrapping_add(delta);
+        self.price_cumulative_updated_at = time;
+    }
 }
 
 impl IsInitialized for Auction {
@@ -33,20 +423,74 @@ impl IsInitialized for Auction {
     }
 }
 
+// Bits of the first two bytes of packed `Auction`, which hold all bool
+// fields. Bits 8..16 are free for flags added later, such as `paused` or
+// auction `direction`, without moving other fields.
+const INITIALIZED_FLAG: u16 = 1;
+const ALLOWLIST_ENABLED_FLAG: u16 = 1 << 1;
+const BLACKOUT_PAUSES_PRICE_FLAG: u16 = 1 << 2;
+const LISTING_MARKER_FLAG: u16 = 1 << 3;
+const BUYER_PROFILES_FLAG: u16 = 1 << 4;
+const ALLOW_ZERO_PRICE_FLAG: u16 = 1 << 5;
+const PRICE_MILESTONES_FLAG: u16 = 1 << 6;
+const UNIFORM_PRICE_FLAG: u16 = 1 << 7;
+
 impl Pack for Auction {
-    const LEN: usize = 97;
+    const LEN: usize = 672 + DENIED_LEN;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, <Auction as Pack>::LEN];
         let (
-            is_initialized_dst,
+            flags_dst,
             authority_dst,
             token_dst,
             time_start_dst,
             time_step_dst,
             price_start_dst,
             price_step_dst,
-        ) = mut_array_refs![dst, 1, 32, 32, 8, 8, 8, 8];
+            token_amount_for_sale_dst,
+            escrow_policy_dst,
+            price_cumulative_dst,
+            price_cumulative_updated_at_dst,
+            hook_program_dst,
+            blackout_period_dst,
+            blackout_offset_dst,
+            blackout_duration_dst,
+            price_lock_skew_dst,
+            finalization_policy_dst,
+            max_tokens_per_step_dst,
+            step_sold_index_dst,
+            step_sold_amount_dst,
+            price_curve_dst,
+            taker_fee_bps_dst,
+            update_delay_dst,
+            cosigner_dst,
+            min_buyer_auctions_dst,
+            tokens_sold_dst,
+            proceeds_dst,
+            last_price_dst,
+            free_claim_limit_dst,
+            tip_recipient_dst,
+            tips_dst,
+            denied_programs_dst,
+            price_floor_dst,
+            ended_at_dst,
+            buy_now_price_dst,
+            merkle_root_dst,
+            max_bid_amount_dst,
+            payment_mint_dst,
+            price_schedule_dst,
+            price_ceiling_dst,
+            clearing_price_dst,
+            refunded_dst,
+            commit_end_dst,
+            reveal_end_dst,
+            time_end_dst,
+            free_claim_end_dst,
+        ) = mut_array_refs![
+            dst, 2, 32, 32, 8, 8, 8, 8, 8, 1, 16, 8, 36, 8, 8, 8, 8, 33, 8, 8, 8, 1, 2, 8, 36, 8,
+            8, 8, 8, 8, 36, 8, DENIED_LEN, 8, 8, 8, 32, 8, 36, 129, 8, 8, 8, 8, 8, 8, 8
+        ];
         let &Auction {
             is_initialized,
             ref authority,
@@ -55,35 +499,1769 @@ impl Pack for Auction {
             time_step,
             price_start,
             price_step,
+            token_amount_for_sale,
+            escrow_policy,
+            price_cumulative,
+            price_cumulative_updated_at,
+            ref hook_program,
+            allowlist_enabled,
+            blackout_period,
+            blackout_offset,
+            blackout_duration,
+            blackout_pauses_price,
+            price_lock_skew,
+            ref finalization_policy,
+            max_tokens_per_step,
+            step_sold_index,
+            step_sold_amount,
+            price_curve,
+            taker_fee_bps,
+            update_delay,
+            listing_marker,
+            ref cosigner,
+            buyer_profiles,
+            min_buyer_auctions,
+            tokens_sold,
+            proceeds,
+            last_price,
+            allow_zero_price,
+            free_claim_limit,
+            ref tip_recipient,
+            tips,
+            ref denied_programs,
+            price_milestones,
+            price_floor,
+            ended_at,
+            buy_now_price,
+            merkle_root,
+            max_bid_amount,
+            ref payment_mint,
+            ref price_schedule,
+            price_ceiling,
+            uniform_price,
+            clearing_price,
+            refunded,
+            commit_end,
+            reveal_end,
+            time_end,
+            free_claim_end,
         } = self;
-        is_initialized_dst[0] = is_initialized as u8;
+        *flags_dst = [
+            (is_initialized, INITIALIZED_FLAG),
+            (allowlist_enabled, ALLOWLIST_ENABLED_FLAG),
+            (blackout_pauses_price, BLACKOUT_PAUSES_PRICE_FLAG),
+            (listing_marker, LISTING_MARKER_FLAG),
+            (buyer_profiles, BUYER_PROFILES_FLAG),
+            (allow_zero_price, ALLOW_ZERO_PRICE_FLAG),
+            (price_milestones, PRICE_MILESTONES_FLAG),
+            (uniform_price, UNIFORM_PRICE_FLAG),
+        ]
+        .iter()
+        .filter(|(value, _)| *value)
+        .fold(0u16, |flags, (_, flag)| flags | flag)
+        .to_le_bytes();
         authority_dst.copy_from_slice(authority.as_ref());
         token_dst.copy_from_slice(token.as_ref());
         *time_start_dst = time_start.to_le_bytes();
         *time_step_dst = time_step.to_le_bytes();
         *price_start_dst = price_start.to_le_bytes();
         *price_step_dst = price_step.to_le_bytes();
+        *token_amount_for_sale_dst = token_amount_for_sale.to_le_bytes();
+        escrow_policy_dst[0] = escrow_policy as u8;
+        *price_cumulative_dst = price_cumulative.to_le_bytes();
+        *price_cumulative_updated_at_dst = price_cumulative_updated_at.to_le_bytes();
+        pack_coption_key(hook_program, hook_program_dst);
+        *blackout_period_dst = blackout_period.to_le_bytes();
+        *blackout_offset_dst = blackout_offset.to_le_bytes();
+        *blackout_duration_dst = blackout_duration.to_le_bytes();
+        *price_lock_skew_dst = price_lock_skew.to_le_bytes();
+        finalization_policy.pack_into_slice(finalization_policy_dst);
+        *max_tokens_per_step_dst = max_tokens_per_step.to_le_bytes();
+        *step_sold_index_dst = step_sold_index.to_le_bytes();
+        *step_sold_amount_dst = step_sold_amount.to_le_bytes();
+        price_curve_dst[0] = price_curve as u8;
+        *taker_fee_bps_dst = taker_fee_bps.to_le_bytes();
+        *update_delay_dst = update_delay.to_le_bytes();
+        pack_coption_key(cosigner, cosigner_dst);
+        *min_buyer_auctions_dst = min_buyer_auctions.to_le_bytes();
+        *tokens_sold_dst = tokens_sold.to_le_bytes();
+        *proceeds_dst = proceeds.to_le_bytes();
+        *last_price_dst = last_price.to_le_bytes();
+        *free_claim_limit_dst = free_claim_limit.to_le_bytes();
+        pack_coption_key(tip_recipient, tip_recipient_dst);
+        *tips_dst = tips.to_le_bytes();
+        for (dst, program) in denied_programs_dst
+            .chunks_exact_mut(32)
+            .zip(denied_programs)
+        {
+            dst.copy_from_slice(program.as_ref());
+        }
+        *price_floor_dst = price_floor.to_le_bytes();
+        *ended_at_dst = ended_at.to_le_bytes();
+        *buy_now_price_dst = buy_now_price.to_le_bytes();
+        *merkle_root_dst = merkle_root;
+        *max_bid_amount_dst = max_bid_amount.to_le_bytes();
+        pack_coption_key(payment_mint, payment_mint_dst);
+        price_schedule.pack_into_slice(price_schedule_dst);
+        *price_ceiling_dst = price_ceiling.to_le_bytes();
+        *clearing_price_dst = clearing_price.to_le_bytes();
+        *refunded_dst = refunded.to_le_bytes();
+        *commit_end_dst = commit_end.to_le_bytes();
+        *reveal_end_dst = reveal_end.to_le_bytes();
+        *time_end_dst = time_end.to_le_bytes();
+        *free_claim_end_dst = free_claim_end.to_le_bytes();
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, <Auction as Pack>::LEN];
-        let (is_initialized, authority, token, time_start, time_step, price_start, price_step) =
-            array_refs![src, 1, 32, 32, 8, 8, 8, 8];
-        let is_initialized = match is_initialized {
-            [0] => false,
-            [1] => true,
-            _ => return Err(ProgramError::InvalidAccountData),
-        };
+        let (
+            flags,
+            authority,
+            token,
+            time_start,
+            time_step,
+            price_start,
+            price_step,
+            token_amount_for_sale,
+            escrow_policy,
+            price_cumulative,
+            price_cumulative_updated_at,
+            hook_program,
+            blackout_period,
+            blackout_offset,
+            blackout_duration,
+            price_lock_skew,
+            finalization_policy,
+            max_tokens_per_step,
+            step_sold_index,
+            step_sold_amount,
+            price_curve,
+            taker_fee_bps,
+            update_delay,
+            cosigner,
+            min_buyer_auctions,
+            tokens_sold,
+            proceeds,
+            last_price,
+            free_claim_limit,
+            tip_recipient,
+            tips,
+            denied_programs,
+            price_floor,
+            ended_at,
+            buy_now_price,
+            merkle_root,
+            max_bid_amount,
+            payment_mint,
+            price_schedule,
+            price_ceiling,
+            clearing_price,
+            refunded,
+            commit_end,
+            reveal_end,
+            time_end,
+            free_claim_end,
+        ) = array_refs![
+            src, 2, 32, 32, 8, 8, 8, 8, 8, 1, 16, 8, 36, 8, 8, 8, 8, 33, 8, 8, 8, 1, 2, 8, 36, 8,
+            8, 8, 8, 8, 36, 8, DENIED_LEN, 8, 8, 8, 32, 8, 36, 129, 8, 8, 8, 8, 8, 8, 8
+        ];
+        let flags = u16::from_le_bytes(*flags);
+        let escrow_policy =
+            EscrowPolicy::from_u8(escrow_policy[0]).ok_or(ProgramError::InvalidAccountData)?;
         Ok(Auction {
-            is_initialized,
+            is_initialized: flags & INITIALIZED_FLAG != 0,
             authority: Pubkey::new_from_array(*authority),
             token: Pubkey::new_from_array(*token),
             time_start: UnixTimestamp::from_le_bytes(*time_start),
             time_step: UnixTimestamp::from_le_bytes(*time_step),
             price_start: u64::from_le_bytes(*price_start),
             price_step: u64::from_le_bytes(*price_step),
+            token_amount_for_sale: u64::from_le_bytes(*token_amount_for_sale),
+            escrow_policy,
+            price_cumulative: u128::from_le_bytes(*price_cumulative),
+            price_cumulative_updated_at: UnixTimestamp::from_le_bytes(*price_cumulative_updated_at),
+            hook_program: unpack_coption_key(hook_program)?,
+            allowlist_enabled: flags & ALLOWLIST_ENABLED_FLAG != 0,
+            blackout_period: UnixTimestamp::from_le_bytes(*blackout_period),
+            blackout_offset: UnixTimestamp::from_le_bytes(*blackout_offset),
+            blackout_duration: UnixTimestamp::from_le_bytes(*blackout_duration),
+            blackout_pauses_price: flags & BLACKOUT_PAUSES_PRICE_FLAG != 0,
+            price_lock_skew: UnixTimestamp::from_le_bytes(*price_lock_skew),
+            finalization_policy: FinalizationPolicy::unpack_from_slice(finalization_policy)?,
+            max_tokens_per_step: u64::from_le_bytes(*max_tokens_per_step),
+            step_sold_index: UnixTimestamp::from_le_bytes(*step_sold_index),
+            step_sold_amount: u64::from_le_bytes(*step_sold_amount),
+            price_curve: PriceCurve::from_u8(price_curve[0])
+                .ok_or(ProgramError::InvalidAccountData)?,
+            taker_fee_bps: u16::from_le_bytes(*taker_fee_bps),
+            update_delay: UnixTimestamp::from_le_bytes(*update_delay),
+            listing_marker: flags & LISTING_MARKER_FLAG != 0,
+            cosigner: unpack_coption_key(cosigner)?,
+            buyer_profiles: flags & BUYER_PROFILES_FLAG != 0,
+            min_buyer_auctions: u64::from_le_bytes(*min_buyer_auctions),
+            tokens_sold: u64::from_le_bytes(*tokens_sold),
+            proceeds: u64::from_le_bytes(*proceeds),
+            last_price: u64::from_le_bytes(*last_price),
+            allow_zero_price: flags & ALLOW_ZERO_PRICE_FLAG != 0,
+            free_claim_limit: u64::from_le_bytes(*free_claim_limit),
+            tip_recipient: unpack_coption_key(tip_recipient)?,
+            tips: u64::from_le_bytes(*tips),
+            denied_programs: unpack_denied_programs(denied_programs),
+            price_milestones: flags & PRICE_MILESTONES_FLAG != 0,
+            price_floor: u64::from_le_bytes(*price_floor),
+            ended_at: UnixTimestamp::from_le_bytes(*ended_at),
+            buy_now_price: u64::from_le_bytes(*buy_now_price),
+            merkle_root: *merkle_root,
+            max_bid_amount: u64::from_le_bytes(*max_bid_amount),
+            payment_mint: unpack_coption_key(payment_mint)?,
+            price_schedule: PriceSchedule::unpack_from_slice(price_schedule)?,
+            price_ceiling: u64::from_le_bytes(*price_ceiling),
+            uniform_price: flags & UNIFORM_PRICE_FLAG != 0,
+            clearing_price: u64::from_le_bytes(*clearing_price),
+            refunded: u64::from_le_bytes(*refunded),
+            commit_end: UnixTimestamp::from_le_bytes(*commit_end),
+            reveal_end: UnixTimestamp::from_le_bytes(*reveal_end),
+            time_end: UnixTimestamp::from_le_bytes(*time_end),
+            free_claim_end: UnixTimestamp::from_le_bytes(*free_claim_end),
         })
     }
 }
 
 impl Sealed for Auction {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub enum FinalizationPolicy {
+    // Authority withdraw unsold tokens to any account.
+    #[default]
+    ReturnToSeller,
+    // Unsold tokens are burned, anyone can finalize.
+    Burn,
+    // Unsold tokens sent to token account of given owner, anyone can finalize.
+    SendTo(Pubkey),
+}
+
+impl FinalizationPolicy {
+    pub const LEN: usize = 33;
+
+    pub fn pack_into_slice(&self, dst: &mut [u8; 33]) {
+        let (tag, body) = mut_array_refs![dst, 1, 32];
+        match self {
+            Self::ReturnToSeller => {
+                tag[0] = 0;
+                *body = [0; 32];
+            }
+            Self::Burn => {
+                tag[0] = 1;
+                *body = [0; 32];
+            }
+            Self::SendTo(owner) => {
+                tag[0] = 2;
+                body.copy_from_slice(owner.as_ref());
+            }
+        }
+    }
+
+    pub fn unpack_from_slice(src: &[u8; 33]) -> Result<Self, ProgramError> {
+        let (tag, body) = array_refs![src, 1, 32];
+        match tag[0] {
+            0 => Ok(Self::ReturnToSeller),
+            1 => Ok(Self::Burn),
+            2 => Ok(Self::SendTo(Pubkey::new_from_array(*body))),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Price from `offset` seconds after auction start until the next breakpoint.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub struct PriceBreakpoint {
+    pub offset: UnixTimestamp,
+    pub price: u64,
+}
+
+/// Breakpoints of `PriceCurve::Piecewise`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub struct PriceSchedule {
+    // Number of used breakpoints.
+    pub count: u8,
+    pub breakpoints: [PriceBreakpoint; MAX_PRICE_BREAKPOINTS],
+}
+
+impl PriceSchedule {
+    pub const LEN: usize = 129;
+
+    /// First breakpoint should be at auction start, offsets strictly
+    /// increasing and prices non-increasing. Zero price finish auction.
+    pub fn is_valid(breakpoints: &[PriceBreakpoint]) -> bool {
+        breakpoints.first().map(|breakpoint| breakpoint.offset) == Some(0)
+            && breakpoints.len() <= MAX_PRICE_BREAKPOINTS
+            && breakpoints
+                .windows(2)
+                .all(|pair| pair[0].offset < pair[1].offset && pair[0].price >= pair[1].price)
+    }
+
+    /// Schedule with given breakpoints, `None` if they are not valid.
+    pub fn new(breakpoints: &[PriceBreakpoint]) -> Option<Self> {
+        if !Self::is_valid(breakpoints) {
+            return None;
+        }
+        let mut schedule = Self {
+            count: breakpoints.len() as u8,
+            ..Self::default()
+        };
+        schedule.breakpoints[..breakpoints.len()].copy_from_slice(breakpoints);
+        Some(schedule)
+    }
+
+    pub fn breakpoints(&self) -> &[PriceBreakpoint] {
+        &self.breakpoints[..self.count as usize]
+    }
+
+    pub fn pack_into_slice(&self, dst: &mut [u8; 129]) {
+        let (count_dst, breakpoints_dst) = mut_array_refs![dst, 1, 128];
+        count_dst[0] = self.count;
+        for (dst, breakpoint) in breakpoints_dst.chunks_exact_mut(16).zip(&self.breakpoints) {
+            dst[..8].copy_from_slice(&breakpoint.offset.to_le_bytes());
+            dst[8..].copy_from_slice(&breakpoint.price.to_le_bytes());
+        }
+    }
+
+    pub fn unpack_from_slice(src: &[u8; 129]) -> Result<Self, ProgramError> {
+        let (count, breakpoints_src) = array_refs![src, 1, 128];
+        if count[0] as usize > MAX_PRICE_BREAKPOINTS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut breakpoints = [PriceBreakpoint::default(); MAX_PRICE_BREAKPOINTS];
+        for (breakpoint, src) in breakpoints.iter_mut().zip(breakpoints_src.chunks_exact(16)) {
+            breakpoint.offset = UnixTimestamp::from_le_bytes(*array_ref![src, 0, 8]);
+            breakpoint.price = u64::from_le_bytes(*array_ref![src, 8, 8]);
+        }
+        Ok(Self {
+            count: count[0],
+            breakpoints,
+        })
+    }
+}
+
+/// Marker account which allow wallet to bid in auction with allowlist.
+/// Address derived from seeds: `["allowlist", auction, wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AllowlistEntry {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Account which paid rent, receive it when entry is removed.
+    pub rent_payer: Pubkey,
+}
+
+impl AllowlistEntry {
+    pub const SEED: &'static [u8] = b"allowlist";
+
+    pub fn find_address(auction: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, auction.as_ref(), wallet.as_ref()],
+            &crate::id(),
+        )
+    }
+}
+
+impl IsInitialized for AllowlistEntry {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for AllowlistEntry {
+    const LEN: usize = 33;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <AllowlistEntry as Pack>::LEN];
+        let (is_initialized_dst, rent_payer_dst) = mut_array_refs![dst, 1, 32];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        rent_payer_dst.copy_from_slice(self.rent_payer.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <AllowlistEntry as Pack>::LEN];
+        let (is_initialized, rent_payer) = array_refs![src, 1, 32];
+        Ok(AllowlistEntry {
+            is_initialized: unpack_bool(is_initialized)?,
+            rent_payer: Pubkey::new_from_array(*rent_payer),
+        })
+    }
+}
+
+impl Sealed for AllowlistEntry {}
+
+/// Record of wallet free claim in auction with `allow_zero_price`, limits
+/// every wallet to single claim.
+/// Address derived from seeds: `["free_claim", auction, wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FreeClaim {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Amount of claimed tokens.
+    pub amount: u64,
+}
+
+impl FreeClaim {
+    pub const SEED: &'static [u8] = b"free_claim";
+
+    pub fn find_address(auction: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, auction.as_ref(), wallet.as_ref()],
+            &crate::id(),
+        )
+    }
+}
+
+impl IsInitialized for FreeClaim {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for FreeClaim {
+    const LEN: usize = 9;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <FreeClaim as Pack>::LEN];
+        let (is_initialized_dst, amount_dst) = mut_array_refs![dst, 1, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *amount_dst = self.amount.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <FreeClaim as Pack>::LEN];
+        let (is_initialized, amount) = array_refs![src, 1, 8];
+        Ok(FreeClaim {
+            is_initialized: unpack_bool(is_initialized)?,
+            amount: u64::from_le_bytes(*amount),
+        })
+    }
+}
+
+impl Sealed for FreeClaim {}
+
+/// Record of filled relayed purchase order, limits every order to single
+/// fill.
+/// Address derived from seeds: `["relayed_order", buyer, nonce]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RelayedOrder {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Account which fronted lamports and paid rent.
+    pub relayer: Pubkey,
+}
+
+impl RelayedOrder {
+    pub const SEED: &'static [u8] = b"relayed_order";
+
+    pub fn find_address(buyer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, buyer.as_ref(), &nonce.to_le_bytes()],
+            &crate::id(),
+        )
+    }
+}
+
+impl IsInitialized for RelayedOrder {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for RelayedOrder {
+    const LEN: usize = 33;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <RelayedOrder as Pack>::LEN];
+        let (is_initialized_dst, relayer_dst) = mut_array_refs![dst, 1, 32];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        relayer_dst.copy_from_slice(self.relayer.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <RelayedOrder as Pack>::LEN];
+        let (is_initialized, relayer) = array_refs![src, 1, 32];
+        Ok(RelayedOrder {
+            is_initialized: unpack_bool(is_initialized)?,
+            relayer: Pubkey::new_from_array(*relayer),
+        })
+    }
+}
+
+impl Sealed for RelayedOrder {}
+
+/// Amount and cost of wallet bids in auction with `uniform_price`, refund of
+/// difference with clearing price is claimed once auction finished.
+/// Address derived from seeds: `["bid_receipt", auction, wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BidReceipt {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Tokens filled by all wallet bids.
+    pub token_amount: u64,
+    // Lamports paid for filled tokens, without fees and tips.
+    pub paid: u64,
+    // Refund was claimed.
+    pub refunded: bool,
+}
+
+impl BidReceipt {
+    pub const SEED: &'static [u8] = b"bid_receipt";
+
+    pub fn find_address(auction: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, auction.as_ref(), wallet.as_ref()],
+            &crate::id(),
+        )
+    }
+
+    /// Lamports returned to wallet when every token costs `clearing_price`.
+    pub fn refund(&self, clearing_price: u64) -> u64 {
+        let cost = Auction::fill_cost(self.token_amount, clearing_price).unwrap_or(u64::MAX);
+        self.paid.saturating_sub(cost)
+    }
+}
+
+impl IsInitialized for BidReceipt {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for BidReceipt {
+    const LEN: usize = 18;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <BidReceipt as Pack>::LEN];
+        let (is_initialized_dst, token_amount_dst, paid_dst, refunded_dst) =
+            mut_array_refs![dst, 1, 8, 8, 1];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *token_amount_dst = self.token_amount.to_le_bytes();
+        *paid_dst = self.paid.to_le_bytes();
+        refunded_dst[0] = self.refunded as u8;
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <BidReceipt as Pack>::LEN];
+        let (is_initialized, token_amount, paid, refunded) = array_refs![src, 1, 8, 8, 1];
+        Ok(BidReceipt {
+            is_initialized: unpack_bool(is_initialized)?,
+            token_amount: u64::from_le_bytes(*token_amount),
+            paid: u64::from_le_bytes(*paid),
+            refunded: unpack_bool(refunded)?,
+        })
+    }
+}
+
+impl Sealed for BidReceipt {}
+
+/// Sealed bid of wallet, holds escrow lamports until claimed.
+/// Address derived from seeds: `["sealed_bid", auction, wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SealedBid {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Hash of bid, see `SealedBid::commitment`.
+    pub commitment: [u8; 32],
+    // Lamports above rent, paid back without cost of filled tokens.
+    pub escrow: u64,
+    // Bid was revealed in reveal window, unrevealed bids are not filled.
+    pub revealed: bool,
+    // Revealed amount of tokens, in base units.
+    pub token_amount: u64,
+    // Revealed highest price per token.
+    pub price: u64,
+    // Index of highest price level not above `price`, `NO_LEVEL` if price is
+    // below every level.
+    pub level: u8,
+}
+
+impl SealedBid {
+    pub const SEED: &'static [u8] = b"sealed_bid";
+    pub const NO_LEVEL: u8 = u8::MAX;
+
+    pub fn find_address(auction: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, auction.as_ref(), wallet.as_ref()],
+            &crate::id(),
+        )
+    }
+
+    /// Hash committed by wallet, `salt` should be random and kept secret
+    /// until reveal.
+    pub fn commitment(
+        auction: &Pubkey,
+        wallet: &Pubkey,
+        token_amount: u64,
+        price: u64,
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        hashv(&[
+            auction.as_ref(),
+            wallet.as_ref(),
+            &token_amount.to_le_bytes(),
+            &price.to_le_bytes(),
+            salt,
+        ])
+        .to_bytes()
+    }
+}
+
+impl IsInitialized for SealedBid {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for SealedBid {
+    const LEN: usize = 59;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <SealedBid as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            commitment_dst,
+            escrow_dst,
+            revealed_dst,
+            token_amount_dst,
+            price_dst,
+            level_dst,
+        ) = mut_array_refs![dst, 1, 32, 8, 1, 8, 8, 1];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *commitment_dst = self.commitment;
+        *escrow_dst = self.escrow.to_le_bytes();
+        revealed_dst[0] = self.revealed as u8;
+        *token_amount_dst = self.token_amount.to_le_bytes();
+        *price_dst = self.price.to_le_bytes();
+        level_dst[0] = self.level;
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <SealedBid as Pack>::LEN];
+        let (is_initialized, commitment, escrow, revealed, token_amount, price, level) =
+            array_refs![src, 1, 32, 8, 1, 8, 8, 1];
+        Ok(SealedBid {
+            is_initialized: unpack_bool(is_initialized)?,
+            commitment: *commitment,
+            escrow: u64::from_le_bytes(*escrow),
+            revealed: unpack_bool(revealed)?,
+            token_amount: u64::from_le_bytes(*token_amount),
+            price: u64::from_le_bytes(*price),
+            level: level[0],
+        })
+    }
+}
+
+impl Sealed for SealedBid {}
+
+/// Revealed demand of sealed bid auction by price level and clearing price
+/// after settlement. Price level `i` is auction price at step `i`, bids
+/// are filled from the first level until tokens for sale run out, bids at
+/// clearing level share the rest pro rata. Every filled token costs
+/// clearing price.
+/// Address derived from seeds: `["sealed_book", auction]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SealedBook {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    pub settled: bool,
+    // Revealed tokens by price level.
+    pub demand: [u64; MAX_SEALED_LEVELS],
+    // Last level with filled bids, `SealedBid::NO_LEVEL` if nothing filled.
+    pub clearing_level: u8,
+    pub clearing_price: u64,
+    // Tokens shared by bids at clearing level.
+    pub clearing_supply: u64,
+    // Revealed tokens of clearing level bids not claimed yet.
+    pub clearing_demand_left: u64,
+    // Tokens claimed by bids at clearing level.
+    pub clearing_filled: u64,
+    // Tokens filled and not claimed yet, kept in auction escrow.
+    pub tokens_owed: u64,
+}
+
+impl SealedBook {
+    pub const SEED: &'static [u8] = b"sealed_book";
+
+    pub fn find_address(auction: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, auction.as_ref()], &crate::id())
+    }
+
+    /// Level of bid with `price`, first level with price not above it.
+    pub fn level_of(auction: &Auction, price: u64) -> u8 {
+        let engine = auction.price_engine();
+        for level in 0..MAX_SEALED_LEVELS {
+            match engine.price(level as u64) {
+                Some(level_price) if level_price.max(auction.price_floor) <= price => {
+                    return level as u8;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        SealedBid::NO_LEVEL
+    }
+
+    /// Find clearing level for `supply` tokens, tokens of filled bids are
+    /// owed until claimed.
+    pub fn settle(&mut self, auction: &Auction, supply: u64) {
+        self.settled = true;
+        let mut filled = 0u64;
+        for (level, demand) in self.demand.iter().enumerate() {
+            if *demand == 0 {
+                continue;
+            }
+            self.clearing_level = level as u8;
+            self.clearing_supply = (*demand).min(supply - filled);
+            filled += self.clearing_supply;
+            if filled == supply {
+                break;
+            }
+        }
+        if filled == 0 {
+            self.clearing_level = SealedBid::NO_LEVEL;
+            return;
+        }
+        let engine = auction.price_engine();
+        self.clearing_price = engine
+            .price(self.clearing_level as u64)
+            .unwrap_or(0)
+            .max(auction.price_floor);
+        self.clearing_demand_left = self.demand[self.clearing_level as usize];
+        self.tokens_owed = filled;
+    }
+
+    /// Tokens filled for `bid`, rounded down at clearing level.
+    pub fn fill(&self, bid: &SealedBid) -> u64 {
+        if !bid.revealed || bid.level == SealedBid::NO_LEVEL {
+            return 0;
+        }
+        match bid.level.cmp(&self.clearing_level) {
+            std::cmp::Ordering::Less => bid.token_amount,
+            std::cmp::Ordering::Equal => {
+                let demand = self.demand[bid.level as usize] as u128;
+                (bid.token_amount as u128 * self.clearing_supply as u128 / demand) as u64
+            }
+            std::cmp::Ordering::Greater => 0,
+        }
+    }
+
+    /// Record claim of `bid` filled with `fill` tokens. Rounding remainder
+    /// of clearing level is released once every bid at it claimed.
+    pub fn record_claim(&mut self, bid: &SealedBid, fill: u64) {
+        self.tokens_owed = self.tokens_owed.saturating_sub(fill);
+        if bid.revealed && bid.level == self.clearing_level {
+            self.clearing_demand_left = self.clearing_demand_left.saturating_sub(bid.token_amount);
+            self.clearing_filled = self.clearing_filled.saturating_add(fill);
+            if self.clearing_demand_left == 0 {
+                let remainder = self.clearing_supply.saturating_sub(self.clearing_filled);
+                self.tokens_owed = self.tokens_owed.saturating_sub(remainder);
+            }
+        }
+    }
+}
+
+impl IsInitialized for SealedBook {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for SealedBook {
+    const LEN: usize = 171;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <SealedBook as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            settled_dst,
+            demand_dst,
+            clearing_level_dst,
+            clearing_price_dst,
+            clearing_supply_dst,
+            clearing_demand_left_dst,
+            clearing_filled_dst,
+            tokens_owed_dst,
+        ) = mut_array_refs![dst, 1, 1, 128, 1, 8, 8, 8, 8, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        settled_dst[0] = self.settled as u8;
+        for (dst, demand) in demand_dst.chunks_exact_mut(8).zip(self.demand.iter()) {
+            dst.copy_from_slice(&demand.to_le_bytes());
+        }
+        clearing_level_dst[0] = self.clearing_level;
+        *clearing_price_dst = self.clearing_price.to_le_bytes();
+        *clearing_supply_dst = self.clearing_supply.to_le_bytes();
+        *clearing_demand_left_dst = self.clearing_demand_left.to_le_bytes();
+        *clearing_filled_dst = self.clearing_filled.to_le_bytes();
+        *tokens_owed_dst = self.tokens_owed.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <SealedBook as Pack>::LEN];
+        let (
+            is_initialized,
+            settled,
+            demand_src,
+            clearing_level,
+            clearing_price,
+            clearing_supply,
+            clearing_demand_left,
+            clearing_filled,
+            tokens_owed,
+        ) = array_refs![src, 1, 1, 128, 1, 8, 8, 8, 8, 8];
+        let mut demand = [0; MAX_SEALED_LEVELS];
+        for (demand, src) in demand.iter_mut().zip(demand_src.chunks_exact(8)) {
+            *demand = u64::from_le_bytes(*array_ref![src, 0, 8]);
+        }
+        Ok(SealedBook {
+            is_initialized: unpack_bool(is_initialized)?,
+            settled: unpack_bool(settled)?,
+            demand,
+            clearing_level: clearing_level[0],
+            clearing_price: u64::from_le_bytes(*clearing_price),
+            clearing_supply: u64::from_le_bytes(*clearing_supply),
+            clearing_demand_left: u64::from_le_bytes(*clearing_demand_left),
+            clearing_filled: u64::from_le_bytes(*clearing_filled),
+            tokens_owed: u64::from_le_bytes(*tokens_owed),
+        })
+    }
+}
+
+impl Sealed for SealedBook {}
+
+/// Ephemeral key authorized by wallet to make bids on its behalf, spending
+/// lamports deposited to this account.
+/// Address derived from seeds: `["session", wallet, session_key]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Session {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Wallet which authorized session.
+    pub wallet: Pubkey,
+    // Key which can sign bids.
+    pub session_key: Pubkey,
+    // Session can not be used after this time.
+    pub expires_at: UnixTimestamp,
+}
+
+impl Session {
+    pub const SEED: &'static [u8] = b"session";
+
+    pub fn find_address(wallet: &Pubkey, session_key: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, wallet.as_ref(), session_key.as_ref()],
+            &crate::id(),
+        )
+    }
+}
+
+impl IsInitialized for Session {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Session {
+    const LEN: usize = 73;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <Session as Pack>::LEN];
+        let (is_initialized_dst, wallet_dst, session_key_dst, expires_at_dst) =
+            mut_array_refs![dst, 1, 32, 32, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        wallet_dst.copy_from_slice(self.wallet.as_ref());
+        session_key_dst.copy_from_slice(self.session_key.as_ref());
+        *expires_at_dst = self.expires_at.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <Session as Pack>::LEN];
+        let (is_initialized, wallet, session_key, expires_at) = array_refs![src, 1, 32, 32, 8];
+        Ok(Session {
+            is_initialized: unpack_bool(is_initialized)?,
+            wallet: Pubkey::new_from_array(*wallet),
+            session_key: Pubkey::new_from_array(*session_key),
+            expires_at: UnixTimestamp::from_le_bytes(*expires_at),
+        })
+    }
+}
+
+impl Sealed for Session {}
+
+/// Lamports deposited by wallet to pay for bids.
+/// Address derived from seeds: `["deposit", wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Deposit {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Wallet which own deposited lamports.
+    pub wallet: Pubkey,
+    // Nonce of the next purchase order of wallet, see `order::PurchaseOrder`.
+    pub order_nonce: u64,
+}
+
+impl Deposit {
+    pub const SEED: &'static [u8] = b"deposit";
+
+    pub fn find_address(wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, wallet.as_ref()], &crate::id())
+    }
+}
+
+impl IsInitialized for Deposit {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Deposit {
+    const LEN: usize = 41;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <Deposit as Pack>::LEN];
+        let (is_initialized_dst, wallet_dst, order_nonce_dst) = mut_array_refs![dst, 1, 32, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        wallet_dst.copy_from_slice(self.wallet.as_ref());
+        *order_nonce_dst = self.order_nonce.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <Deposit as Pack>::LEN];
+        let (is_initialized, wallet, order_nonce) = array_refs![src, 1, 32, 8];
+        Ok(Deposit {
+            is_initialized: unpack_bool(is_initialized)?,
+            wallet: Pubkey::new_from_array(*wallet),
+            order_nonce: u64::from_le_bytes(*order_nonce),
+        })
+    }
+}
+
+impl Sealed for Deposit {}
+
+/// Bid statistics of wallet across all auctions.
+/// Address derived from seeds: `["buyer", wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BuyerProfile {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    pub wallet: Pubkey,
+    // Number of auctions with fills, consecutive fills in the same auction
+    // are counted once.
+    pub auctions: u64,
+    // Lamports paid for tokens, without fees.
+    pub volume: u64,
+    // Auction of the latest fill.
+    pub last_auction: Pubkey,
+}
+
+impl BuyerProfile {
+    pub const SEED: &'static [u8] = b"buyer";
+
+    pub fn find_address(wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, wallet.as_ref()], &crate::id())
+    }
+
+    /// Record fill of `cost` lamports in `auction`.
+    pub fn record_fill(&mut self, auction: &Pubkey, cost: u64) {
+        if &self.last_auction != auction {
+            self.auctions = self.auctions.saturating_add(1);
+            self.last_auction = *auction;
+        }
+        self.volume = self.volume.saturating_add(cost);
+    }
+}
+
+impl IsInitialized for BuyerProfile {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for BuyerProfile {
+    const LEN: usize = 81;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <BuyerProfile as Pack>::LEN];
+        let (is_initialized_dst, wallet_dst, auctions_dst, volume_dst, last_auction_dst) =
+            mut_array_refs![dst, 1, 32, 8, 8, 32];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        wallet_dst.copy_from_slice(self.wallet.as_ref());
+        *auctions_dst = self.auctions.to_le_bytes();
+        *volume_dst = self.volume.to_le_bytes();
+        last_auction_dst.copy_from_slice(self.last_auction.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <BuyerProfile as Pack>::LEN];
+        let (is_initialized, wallet, auctions, volume, last_auction) =
+            array_refs![src, 1, 32, 8, 8, 32];
+        Ok(BuyerProfile {
+            is_initialized: unpack_bool(is_initialized)?,
+            wallet: Pubkey::new_from_array(*wallet),
+            auctions: u64::from_le_bytes(*auctions),
+            volume: u64::from_le_bytes(*volume),
+            last_auction: Pubkey::new_from_array(*last_auction),
+        })
+    }
+}
+
+impl Sealed for BuyerProfile {}
+
+/// Final result of auction, written once after sell-out or expiry so other
+/// programs can read it without replaying bids.
+/// Address derived from seeds: `["settlement", auction]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SettlementSummary {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    pub auction: Pubkey,
+    // Price per token of the latest fill, zero if nothing sold.
+    pub clearing_price: u64,
+    // Tokens sold by bids, in base units.
+    pub tokens_sold: u64,
+    // Lamports paid for sold tokens, without fees.
+    pub proceeds: u64,
+    // Time of settlement.
+    pub settled_at: UnixTimestamp,
+}
+
+impl SettlementSummary {
+    pub const SEED: &'static [u8] = b"settlement";
+
+    pub fn find_address(auction: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, auction.as_ref()], &crate::id())
+    }
+}
+
+impl IsInitialized for SettlementSummary {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for SettlementSummary {
+    const LEN: usize = 65;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <SettlementSummary as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            auction_dst,
+            clearing_price_dst,
+            tokens_sold_dst,
+            proceeds_dst,
+            settled_at_dst,
+        ) = mut_array_refs![dst, 1, 32, 8, 8, 8, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        auction_dst.copy_from_slice(self.auction.as_ref());
+        *clearing_price_dst = self.clearing_price.to_le_bytes();
+        *tokens_sold_dst = self.tokens_sold.to_le_bytes();
+        *proceeds_dst = self.proceeds.to_le_bytes();
+        *settled_at_dst = self.settled_at.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <SettlementSummary as Pack>::LEN];
+        let (is_initialized, auction, clearing_price, tokens_sold, proceeds, settled_at) =
+            array_refs![src, 1, 32, 8, 8, 8, 8];
+        Ok(SettlementSummary {
+            is_initialized: unpack_bool(is_initialized)?,
+            auction: Pubkey::new_from_array(*auction),
+            clearing_price: u64::from_le_bytes(*clearing_price),
+            tokens_sold: u64::from_le_bytes(*tokens_sold),
+            proceeds: u64::from_le_bytes(*proceeds),
+            settled_at: UnixTimestamp::from_le_bytes(*settled_at),
+        })
+    }
+}
+
+impl Sealed for SettlementSummary {}
+
+/// Result of the latest `ViewQuote` of wallet, so calling programs can read
+/// it after CPI.
+/// Address derived from seeds: `["quote", wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Quote {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    pub auction: Pubkey,
+    // Tokens which bid would fill, capped by auction limits.
+    pub token_amount: u64,
+    // Price per token at `quoted_at`.
+    pub price: u64,
+    // Lamports which bid would pay, including taker fee.
+    pub lamports: u64,
+    // Time of quote.
+    pub quoted_at: UnixTimestamp,
+}
+
+impl Quote {
+    pub const SEED: &'static [u8] = b"quote";
+
+    pub fn find_address(wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, wallet.as_ref()], &crate::id())
+    }
+}
+
+impl IsInitialized for Quote {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for Quote {
+    const LEN: usize = 65;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <Quote as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            auction_dst,
+            token_amount_dst,
+            price_dst,
+            lamports_dst,
+            quoted_at_dst,
+        ) = mut_array_refs![dst, 1, 32, 8, 8, 8, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        auction_dst.copy_from_slice(self.auction.as_ref());
+        *token_amount_dst = self.token_amount.to_le_bytes();
+        *price_dst = self.price.to_le_bytes();
+        *lamports_dst = self.lamports.to_le_bytes();
+        *quoted_at_dst = self.quoted_at.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <Quote as Pack>::LEN];
+        let (is_initialized, auction, token_amount, price, lamports, quoted_at) =
+            array_refs![src, 1, 32, 8, 8, 8, 8];
+        Ok(Quote {
+            is_initialized: unpack_bool(is_initialized)?,
+            auction: Pubkey::new_from_array(*auction),
+            token_amount: u64::from_le_bytes(*token_amount),
+            price: u64::from_le_bytes(*price),
+            lamports: u64::from_le_bytes(*lamports),
+            quoted_at: UnixTimestamp::from_le_bytes(*quoted_at),
+        })
+    }
+}
+
+impl Sealed for Quote {}
+
+/// Result of the latest `PreviewSettlement` of wallet, so calling programs
+/// can read it after CPI.
+/// Address derived from seeds: `["settlement_preview", wallet]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SettlementPreview {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    pub auction: Pubkey,
+    // Tokens left in auction associated token account.
+    pub tokens: u64,
+    // Where `tokens` go on withdrawal.
+    pub finalization_policy: FinalizationPolicy,
+    // Lamports withdrawn by `WithdrawSOL`, without refunds owed.
+    pub proceeds: u64,
+    // Lamports owed to buyers of `uniform_price` auction.
+    pub refunds_owed: u64,
+    // Payment tokens withdrawn by `WithdrawPayment`, zero if paid with SOL.
+    pub payment_proceeds: u64,
+    // Time of preview.
+    pub previewed_at: UnixTimestamp,
+}
+
+impl SettlementPreview {
+    pub const SEED: &'static [u8] = b"settlement_preview";
+
+    pub fn find_address(wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, wallet.as_ref()], &crate::id())
+    }
+}
+
+impl IsInitialized for SettlementPreview {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for SettlementPreview {
+    const LEN: usize = 106;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <SettlementPreview as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            auction_dst,
+            tokens_dst,
+            finalization_policy_dst,
+            proceeds_dst,
+            refunds_owed_dst,
+            payment_proceeds_dst,
+            previewed_at_dst,
+        ) = mut_array_refs![dst, 1, 32, 8, 33, 8, 8, 8, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        auction_dst.copy_from_slice(self.auction.as_ref());
+        *tokens_dst = self.tokens.to_le_bytes();
+        self.finalization_policy
+            .pack_into_slice(finalization_policy_dst);
+        *proceeds_dst = self.proceeds.to_le_bytes();
+        *refunds_owed_dst = self.refunds_owed.to_le_bytes();
+        *payment_proceeds_dst = self.payment_proceeds.to_le_bytes();
+        *previewed_at_dst = self.previewed_at.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <SettlementPreview as Pack>::LEN];
+        let (
+            is_initialized,
+            auction,
+            tokens,
+            finalization_policy,
+            proceeds,
+            refunds_owed,
+            payment_proceeds,
+            previewed_at,
+        ) = array_refs![src, 1, 32, 8, 33, 8, 8, 8, 8];
+        Ok(SettlementPreview {
+            is_initialized: unpack_bool(is_initialized)?,
+            auction: Pubkey::new_from_array(*auction),
+            tokens: u64::from_le_bytes(*tokens),
+            finalization_policy: FinalizationPolicy::unpack_from_slice(finalization_policy)?,
+            proceeds: u64::from_le_bytes(*proceeds),
+            refunds_owed: u64::from_le_bytes(*refunds_owed),
+            payment_proceeds: u64::from_le_bytes(*payment_proceeds),
+            previewed_at: UnixTimestamp::from_le_bytes(*previewed_at),
+        })
+    }
+}
+
+impl Sealed for SettlementPreview {}
+
+/// Marker of live auction for token by authority, prevents two overlapping
+/// auctions of the same token.
+/// Address derived from seeds: `["listing", authority, token]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ListingMarker {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Auction which holds marker.
+    pub auction: Pubkey,
+    // Account which paid rent, receive it when marker is closed.
+    pub rent_payer: Pubkey,
+}
+
+impl ListingMarker {
+    pub const SEED: &'static [u8] = b"listing";
+
+    pub fn find_address(authority: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, authority.as_ref(), token.as_ref()],
+            &crate::id(),
+        )
+    }
+}
+
+impl IsInitialized for ListingMarker {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ListingMarker {
+    const LEN: usize = 65;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <ListingMarker as Pack>::LEN];
+        let (is_initialized_dst, auction_dst, rent_payer_dst) = mut_array_refs![dst, 1, 32, 32];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        auction_dst.copy_from_slice(self.auction.as_ref());
+        rent_payer_dst.copy_from_slice(self.rent_payer.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <ListingMarker as Pack>::LEN];
+        let (is_initialized, auction, rent_payer) = array_refs![src, 1, 32, 32];
+        Ok(ListingMarker {
+            is_initialized: unpack_bool(is_initialized)?,
+            auction: Pubkey::new_from_array(*auction),
+            rent_payer: Pubkey::new_from_array(*rent_payer),
+        })
+    }
+}
+
+impl Sealed for ListingMarker {}
+
+/// Rule which raise auction price floor once part of tokens is sold.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(not(target_arch = "bpf"), derive(serde::Serialize))]
+pub struct PriceMilestone {
+    // Sold part of all tokens for sale, in basis points.
+    pub sold_bps: u16,
+    // New floor as part of the latest fill price, in basis points.
+    pub floor_bps: u16,
+}
+
+/// Price floor rules of auction, applied by bids in order.
+/// Address derived from seeds: `["milestones", auction]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PriceMilestones {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Number of used rules, ordered by `sold_bps`.
+    pub count: u8,
+    // Number of rules already applied.
+    pub reached: u8,
+    pub milestones: [PriceMilestone; MAX_PRICE_MILESTONES],
+}
+
+impl PriceMilestones {
+    pub const SEED: &'static [u8] = b"milestones";
+
+    pub fn find_address(auction: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, auction.as_ref()], &crate::id())
+    }
+
+    /// Rules should be ordered by strictly increasing `sold_bps` and fit
+    /// into basis points.
+    pub fn is_valid(milestones: &[PriceMilestone]) -> bool {
+        !milestones.is_empty()
+            && milestones.len() <= MAX_PRICE_MILESTONES
+            && milestones.iter().all(|milestone| {
+                milestone.sold_bps > 0
+                    && milestone.sold_bps <= Auction::BPS_DENOMINATOR
+                    && milestone.floor_bps <= Auction::BPS_DENOMINATOR
+            })
+            && milestones
+                .windows(2)
+                .all(|pair| pair[0].sold_bps < pair[1].sold_bps)
+    }
+
+    /// Raise `auction` price floor for every newly reached rule, returns
+    /// `true` if any rule was applied.
+    pub fn apply(&mut self, auction: &mut Auction) -> bool {
+        let total = auction.tokens_sold as u128 + auction.remaining() as u128;
+        if total == 0 {
+            return false;
+        }
+        let sold_bps = auction.tokens_sold as u128 * Auction::BPS_DENOMINATOR as u128 / total;
+
+        let reached = self.reached;
+        while self.reached < self.count {
+            let milestone = self.milestones[self.reached as usize];
+            if milestone.sold_bps as u128 > sold_bps {
+                break;
+            }
+            let floor = auction.last_price as u128 * milestone.floor_bps as u128
+                / Auction::BPS_DENOMINATOR as u128;
+            auction.price_floor = auction.price_floor.max(floor as u64);
+            self.reached += 1;
+        }
+        self.reached != reached
+    }
+}
+
+impl IsInitialized for PriceMilestones {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for PriceMilestones {
+    const LEN: usize = 19;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <PriceMilestones as Pack>::LEN];
+        let (is_initialized_dst, count_dst, reached_dst, milestones_dst) =
+            mut_array_refs![dst, 1, 1, 1, 16];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        count_dst[0] = self.count;
+        reached_dst[0] = self.reached;
+        for (dst, milestone) in milestones_dst.chunks_exact_mut(4).zip(&self.milestones) {
+            dst[..2].copy_from_slice(&milestone.sold_bps.to_le_bytes());
+            dst[2..].copy_from_slice(&milestone.floor_bps.to_le_bytes());
+        }
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <PriceMilestones as Pack>::LEN];
+        let (is_initialized, count, reached, milestones_src) = array_refs![src, 1, 1, 1, 16];
+        if count[0] as usize > MAX_PRICE_MILESTONES || reached[0] > count[0] {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut milestones = [PriceMilestone::default(); MAX_PRICE_MILESTONES];
+        for (milestone, src) in milestones.iter_mut().zip(milestones_src.chunks_exact(4)) {
+            milestone.sold_bps = u16::from_le_bytes([src[0], src[1]]);
+            milestone.floor_bps = u16::from_le_bytes([src[2], src[3]]);
+        }
+        Ok(PriceMilestones {
+            is_initialized: unpack_bool(is_initialized)?,
+            count: count[0],
+            reached: reached[0],
+            milestones,
+        })
+    }
+}
+
+impl Sealed for PriceMilestones {}
+
+/// Auction parameters proposed by authority, can be applied by anyone after
+/// `apply_after`.
+/// Address derived from seeds: `["pending_update", auction]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PendingUpdate {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Update can not be applied before this time.
+    pub apply_after: UnixTimestamp,
+    // New `Auction::max_tokens_per_step`.
+    pub max_tokens_per_step: u64,
+    // New `Auction::taker_fee_bps`.
+    pub taker_fee_bps: u16,
+    // Account which paid rent, receive it when update is applied.
+    pub rent_payer: Pubkey,
+}
+
+impl PendingUpdate {
+    pub const SEED: &'static [u8] = b"pending_update";
+
+    pub fn find_address(auction: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, auction.as_ref()], &crate::id())
+    }
+}
+
+impl IsInitialized for PendingUpdate {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for PendingUpdate {
+    const LEN: usize = 51;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <PendingUpdate as Pack>::LEN];
+        let (
+            is_initialized_dst,
+            apply_after_dst,
+            max_tokens_per_step_dst,
+            taker_fee_bps_dst,
+            rent_payer_dst,
+        ) = mut_array_refs![dst, 1, 8, 8, 2, 32];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *apply_after_dst = self.apply_after.to_le_bytes();
+        *max_tokens_per_step_dst = self.max_tokens_per_step.to_le_bytes();
+        *taker_fee_bps_dst = self.taker_fee_bps.to_le_bytes();
+        rent_payer_dst.copy_from_slice(self.rent_payer.as_ref());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <PendingUpdate as Pack>::LEN];
+        let (is_initialized, apply_after, max_tokens_per_step, taker_fee_bps, rent_payer) =
+            array_refs![src, 1, 8, 8, 2, 32];
+        Ok(PendingUpdate {
+            is_initialized: unpack_bool(is_initialized)?,
+            apply_after: UnixTimestamp::from_le_bytes(*apply_after),
+            max_tokens_per_step: u64::from_le_bytes(*max_tokens_per_step),
+            taker_fee_bps: u16::from_le_bytes(*taker_fee_bps),
+            rent_payer: Pubkey::new_from_array(*rent_payer),
+        })
+    }
+}
+
+impl Sealed for PendingUpdate {}
+
+/// Operation recorded in `AdminLog`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, FromPrimitive)]
+pub enum AdminAction {
+    // Proceeds withdrawn, key is destination.
+    #[default]
+    WithdrawSOL,
+    // Unsold tokens withdrawn or burned, key is destination.
+    WithdrawTokens,
+    // Key is added wallet.
+    AddToAllowlist,
+    // Key is removed wallet.
+    RemoveFromAllowlist,
+    // Key is pending update account.
+    ProposeUpdate,
+    // Key is pending update account.
+    ApplyUpdate,
+    // Key is auction authority.
+    UpdateAuction,
+    // Key is price milestones account.
+    SetPriceMilestones,
+    // Key is auction authority.
+    EndAuctionEarly,
+    // Payment tokens withdrawn, key is destination.
+    WithdrawPayment,
+    // Key is auction authority.
+    SetPriceSchedule,
+}
+
+/// Single record of `AdminLog`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdminLogEntry {
+    pub action: AdminAction,
+    pub time: UnixTimestamp,
+    // Account affected by operation.
+    pub key: Pubkey,
+    // Lamports or tokens moved, zero if nothing moved.
+    pub amount: u64,
+}
+
+impl AdminLogEntry {
+    pub const LEN: usize = 49;
+
+    pub fn pack_into_slice(&self, dst: &mut [u8; 49]) {
+        let (action_dst, time_dst, key_dst, amount_dst) = mut_array_refs![dst, 1, 8, 32, 8];
+        action_dst[0] = self.action as u8;
+        *time_dst = self.time.to_le_bytes();
+        key_dst.copy_from_slice(self.key.as_ref());
+        *amount_dst = self.amount.to_le_bytes();
+    }
+
+    pub fn unpack_from_slice(src: &[u8; 49]) -> Result<Self, ProgramError> {
+        let (action, time, key, amount) = array_refs![src, 1, 8, 32, 8];
+        Ok(AdminLogEntry {
+            action: AdminAction::from_u8(action[0]).ok_or(ProgramError::InvalidAccountData)?,
+            time: UnixTimestamp::from_le_bytes(*time),
+            key: Pubkey::new_from_array(*key),
+            amount: u64::from_le_bytes(*amount),
+        })
+    }
+}
+
+/// Append-only log of authority operations on auction, keeps last
+/// `AdminLog::CAPACITY` entries.
+/// Address derived from seeds: `["admin_log", auction]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdminLog {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Total number of appended entries, entry `n` stored at
+    // `n % CAPACITY`.
+    pub count: u64,
+    pub entries: [AdminLogEntry; 16],
+}
+
+impl AdminLog {
+    pub const SEED: &'static [u8] = b"admin_log";
+    pub const CAPACITY: usize = 16;
+
+    pub fn find_address(auction: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, auction.as_ref()], &crate::id())
+    }
+
+    /// Record entry, overwriting the oldest one if log is full.
+    pub fn append(&mut self, entry: AdminLogEntry) {
+        self.entries[(self.count % Self::CAPACITY as u64) as usize] = entry;
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// Stored entries from oldest to newest.
+    pub fn recent(&self) -> Vec<AdminLogEntry> {
+        let stored = self.count.min(Self::CAPACITY as u64) as usize;
+        (self.count as usize - stored..self.count as usize)
+            .map(|n| self.entries[n % Self::CAPACITY])
+            .collect()
+    }
+}
+
+impl IsInitialized for AdminLog {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for AdminLog {
+    const LEN: usize = 1 + 8 + AdminLogEntry::LEN * AdminLog::CAPACITY;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <AdminLog as Pack>::LEN];
+        let (is_initialized_dst, count_dst, entries_dst) = mut_array_refs![dst, 1, 8, 784];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *count_dst = self.count.to_le_bytes();
+        for (i, entry) in self.entries.iter().enumerate() {
+            entry.pack_into_slice(array_mut_ref![
+                entries_dst,
+                i * AdminLogEntry::LEN,
+                AdminLogEntry::LEN
+            ]);
+        }
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <AdminLog as Pack>::LEN];
+        let (is_initialized, count, entries_src) = array_refs![src, 1, 8, 784];
+        let mut entries = [AdminLogEntry::default(); AdminLog::CAPACITY];
+        for (i, entry) in entries.iter_mut().enumerate() {
+            *entry = AdminLogEntry::unpack_from_slice(array_ref![
+                entries_src,
+                i * AdminLogEntry::LEN,
+                AdminLogEntry::LEN
+            ])?;
+        }
+        Ok(AdminLog {
+            is_initialized: unpack_bool(is_initialized)?,
+            count: u64::from_le_bytes(*count),
+            entries,
+        })
+    }
+}
+
+impl Sealed for AdminLog {}
+
+/// Timestamp used by processor instead of `Clock` sysvar, only in builds with
+/// `test-clock` feature.
+/// Address derived from seeds: `["clock"]`.
+#[cfg(feature = "test-clock")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClockOverride {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+
+    // Current time reported to the processor.
+    pub unix_timestamp: UnixTimestamp,
+}
+
+#[cfg(feature = "test-clock")]
+impl ClockOverride {
+    pub const SEED: &'static [u8] = b"clock";
+
+    pub fn find_address() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED], &crate::id())
+    }
+}
+
+#[cfg(feature = "test-clock")]
+impl IsInitialized for ClockOverride {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+#[cfg(feature = "test-clock")]
+impl Pack for ClockOverride {
+    const LEN: usize = 9;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, <ClockOverride as Pack>::LEN];
+        let (is_initialized_dst, unix_timestamp_dst) = mut_array_refs![dst, 1, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        *unix_timestamp_dst = self.unix_timestamp.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, <ClockOverride as Pack>::LEN];
+        let (is_initialized, unix_timestamp) = array_refs![src, 1, 8];
+        Ok(ClockOverride {
+            is_initialized: unpack_bool(is_initialized)?,
+            unix_timestamp: UnixTimestamp::from_le_bytes(*unix_timestamp),
+        })
+    }
+}
+
+#[cfg(feature = "test-clock")]
+impl Sealed for ClockOverride {}
+
+/// Number of decimals used to render lamports as SOL.
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Render price in lamports as SOL, see `format_token_amount`.
+pub fn format_price(lamports: u64) -> String {
+    format_token_amount(lamports, SOL_DECIMALS)
+}
+
+/// Render raw token amount with mint decimals. Conversion is exact, so nothing
+/// is rounded, only trailing zeros of fractional part are trimmed.
+pub fn format_token_amount(raw: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", raw, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_owned()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+// Same layout as in `spl-token` state.
+fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
+    let (tag, body) = mut_array_refs![dst, 4, 32];
+    match src {
+        COption::Some(key) => {
+            *tag = [1, 0, 0, 0];
+            body.copy_from_slice(key.as_ref());
+        }
+        COption::None => {
+            *tag = [0; 4];
+        }
+    }
+}
+
+fn unpack_denied_programs(src: &[u8]) -> [Pubkey; MAX_DENIED_PROGRAMS] {
+    let mut programs = [Pubkey::default(); MAX_DENIED_PROGRAMS];
+    for (program, src) in programs.iter_mut().zip(src.chunks_exact(32)) {
+        *program = Pubkey::new(src);
+    }
+    programs
+}
+
+fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
+    let (tag, body) = array_refs![src, 4, 32];
+    match *tag {
+        [0, 0, 0, 0] => Ok(COption::None),
+        [1, 0, 0, 0] => Ok(COption::Some(Pubkey::new_from_array(*body))),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
@@ -0,0 +1,348 @@
+//! Canonical packed bytes of instructions and `Auction` account together with
+//! their decoded JSON, so SDKs in other languages can check that they stay
+//! byte-compatible with this crate. Not compiled for program.
+//!
+//! Values are the same as `tests/fixtures`, layout tests check both. Decoded
+//! JSON follows serde derive: pubkeys are byte arrays and enums are
+//! externally tagged.
+
+use serde::{Serialize, Serializer};
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+
+use crate::{
+    instruction::{AuctionConfig, AuctionInstruction},
+    state::{
+        AmountBasis, Auction, EscrowPolicy, FinalizationPolicy, PriceBreakpoint, PriceCurve,
+        PriceMilestone, PriceSchedule,
+    },
+};
+
+/// Packed bytes of `decoded` value.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TestVector<T> {
+    pub name: &'static str,
+    // Packed bytes, hex encoded.
+    pub hex: String,
+    pub decoded: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TestVectors {
+    pub accounts: Vec<TestVector<Auction>>,
+    pub instructions: Vec<TestVector<AuctionInstruction>>,
+}
+
+impl TestVectors {
+    pub fn generate() -> Self {
+        let auction = auction();
+        let mut packed = vec![0; Auction::LEN];
+        Auction::pack(auction, &mut packed).expect("failed to pack Auction");
+        Self {
+            accounts: vec![TestVector {
+                name: "auction",
+                hex: hex(&packed),
+                decoded: auction,
+            }],
+            instructions: instructions()
+                .into_iter()
+                .map(|(name, instruction)| TestVector {
+                    name,
+                    hex: hex(&instruction.pack()),
+                    decoded: instruction,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("test vectors are serializable")
+    }
+}
+
+/// Auction with every field set, packed to `tests/fixtures/auction.hex`.
+pub fn auction() -> Auction {
+    Auction {
+        is_initialized: true,
+        authority: Pubkey::new_from_array([1; 32]),
+        token: Pubkey::new_from_array([2; 32]),
+        time_start: 1_625_000_000,
+        time_step: 60,
+        price_start: 10 * u64::pow(10, 9),
+        price_step: u64::pow(10, 9),
+        token_amount_for_sale: 100,
+        escrow_policy: EscrowPolicy::AddExtrasToSale,
+        price_cumulative: 3 * u128::pow(10, 12),
+        price_cumulative_updated_at: 1_625_000_300,
+        hook_program: COption::Some(Pubkey::new_from_array([3; 32])),
+        allowlist_enabled: true,
+        blackout_period: 86_400,
+        blackout_offset: 0,
+        blackout_duration: 21_600,
+        blackout_pauses_price: true,
+        price_lock_skew: 5,
+        finalization_policy: FinalizationPolicy::SendTo(Pubkey::new_from_array([5; 32])),
+        max_tokens_per_step: 10,
+        step_sold_index: 3,
+        step_sold_amount: 4,
+        price_curve: PriceCurve::Linear,
+        taker_fee_bps: 250,
+        update_delay: 3_600,
+        listing_marker: true,
+        cosigner: COption::Some(Pubkey::new_from_array([7; 32])),
+        buyer_profiles: true,
+        min_buyer_auctions: 2,
+        tokens_sold: 1000,
+        proceeds: 50000,
+        last_price: 50,
+        allow_zero_price: true,
+        free_claim_limit: 10,
+        tip_recipient: COption::Some(Pubkey::new_from_array([9; 32])),
+        tips: 500,
+        denied_programs: [Pubkey::new_from_array([10; 32]), Pubkey::default()],
+        price_milestones: true,
+        price_floor: 40,
+        ended_at: 1_625_000_600,
+        buy_now_price: 2_000_000_000,
+        merkle_root: [11; 32],
+        max_bid_amount: 3,
+        payment_mint: COption::Some(Pubkey::new_from_array([13; 32])),
+        price_schedule: PriceSchedule::new(&[PriceBreakpoint {
+            offset: 0,
+            price: 7,
+        }])
+        .unwrap(),
+        price_ceiling: 90,
+        uniform_price: true,
+        clearing_price: 95,
+        refunded: 96,
+        commit_end: 1_700,
+        reveal_end: 1_800,
+        time_end: 1_900,
+        free_claim_end: 2_000,
+    }
+}
+
+/// Instruction of every kind, packed to `tests/fixtures/<name>.hex`.
+pub fn instructions() -> Vec<(&'static str, AuctionInstruction)> {
+    vec![
+        (
+            "initialize_auction",
+            AuctionInstruction::InitializeAuction {
+                config: Box::new(AuctionConfig {
+                    token_amount: 100,
+                    time_start: 1_625_000_000,
+                    time_step: 60,
+                    price_start: 10 * u64::pow(10, 9),
+                    price_step: u64::pow(10, 9),
+                    assume_prefunded: true,
+                    escrow_policy: EscrowPolicy::AddExtrasToSale,
+                    hook_program: Some(Pubkey::new_from_array([4; 32])),
+                    allowlist_enabled: true,
+                    blackout_period: 86_400,
+                    blackout_offset: 0,
+                    blackout_duration: 21_600,
+                    blackout_pauses_price: true,
+                    price_lock_skew: 5,
+                    finalization_policy: FinalizationPolicy::SendTo(Pubkey::new_from_array(
+                        [6; 32],
+                    )),
+                    max_tokens_per_step: 10,
+                    amount_basis: AmountBasis::WholeTokens,
+                    price_curve: PriceCurve::Linear,
+                    taker_fee_bps: 250,
+                    update_delay: 3_600,
+                    unique_listing: true,
+                    cosigner: Some(Pubkey::new_from_array([7; 32])),
+                    buyer_profiles: true,
+                    min_buyer_auctions: 2,
+                    allow_zero_price: true,
+                    free_claim_limit: 10,
+                    tip_recipient: Some(Pubkey::new_from_array([9; 32])),
+                    denied_programs: vec![Pubkey::new_from_array([10; 32])],
+                    buy_now_price: 2_000_000_000,
+                    merkle_root: [11; 32],
+                    max_bid_amount: 3,
+                    payment_mint: Some(Pubkey::new_from_array([13; 32])),
+                    price_ceiling: 90,
+                    uniform_price: true,
+                    commit_end: 1_700,
+                    reveal_end: 1_800,
+                    time_end: 1_900,
+                    free_claim_end: 2_000,
+                }),
+            },
+        ),
+        (
+            "make_bid",
+            AuctionInstruction::MakeBid {
+                token_amount: 5,
+                amount_basis: AmountBasis::WholeTokens,
+                create_token_customer: true,
+                tip: 500,
+                merkle_proof: vec![[12; 32]],
+            },
+        ),
+        ("withdraw_sol", AuctionInstruction::WithdrawSOL),
+        ("withdraw_tokens", AuctionInstruction::WithdrawTokens),
+        ("sync_escrow", AuctionInstruction::SyncEscrow),
+        ("poke", AuctionInstruction::Poke),
+        ("add_to_allowlist", AuctionInstruction::AddToAllowlist),
+        (
+            "remove_from_allowlist",
+            AuctionInstruction::RemoveFromAllowlist,
+        ),
+        (
+            "authorize_session",
+            AuctionInstruction::AuthorizeSession {
+                lamports: u64::pow(10, 9),
+                expires_at: 1_625_003_600,
+            },
+        ),
+        ("revoke_session", AuctionInstruction::RevokeSession),
+        (
+            "make_session_bid",
+            AuctionInstruction::MakeSessionBid { token_amount: 5 },
+        ),
+        (
+            "deposit_funds",
+            AuctionInstruction::DepositFunds {
+                lamports: u64::pow(10, 9),
+            },
+        ),
+        (
+            "withdraw_funds",
+            AuctionInstruction::WithdrawFunds {
+                lamports: u64::pow(10, 9),
+            },
+        ),
+        (
+            "make_deposit_bid",
+            AuctionInstruction::MakeDepositBid { token_amount: 5 },
+        ),
+        (
+            "make_bid_at_time",
+            AuctionInstruction::MakeBidAtTime {
+                token_amount: 5,
+                quoted_at: 1_625_000_060,
+            },
+        ),
+        (
+            "initialize_launch",
+            AuctionInstruction::InitializeLaunch { token_amount: 100 },
+        ),
+        ("program_info", AuctionInstruction::ProgramInfo),
+        (
+            "make_bundle_bid",
+            AuctionInstruction::MakeBundleBid {
+                max_lamports: 3 * u64::pow(10, 9),
+                token_amounts: vec![5, 1],
+            },
+        ),
+        (
+            "propose_update",
+            AuctionInstruction::ProposeUpdate {
+                max_tokens_per_step: 20,
+                taker_fee_bps: 250,
+            },
+        ),
+        ("apply_update", AuctionInstruction::ApplyUpdate),
+        (
+            "create_buyer_profile",
+            AuctionInstruction::CreateBuyerProfile,
+        ),
+        ("settle", AuctionInstruction::Settle),
+        (
+            "view_quote",
+            AuctionInstruction::ViewQuote { token_amount: 100 },
+        ),
+        ("claim_free", AuctionInstruction::ClaimFree),
+        (
+            "update_auction",
+            AuctionInstruction::UpdateAuction {
+                time_start: 1_000_000,
+                time_step: 60,
+                price_start: 100,
+                price_step: 1,
+            },
+        ),
+        (
+            "set_price_milestones",
+            AuctionInstruction::SetPriceMilestones {
+                milestones: vec![PriceMilestone {
+                    sold_bps: 5_000,
+                    floor_bps: 10_000,
+                }],
+            },
+        ),
+        ("end_auction_early", AuctionInstruction::EndAuctionEarly),
+        ("buy_now", AuctionInstruction::BuyNow),
+        ("withdraw_payment", AuctionInstruction::WithdrawPayment),
+        ("preview_settlement", AuctionInstruction::PreviewSettlement),
+        (
+            "set_price_schedule",
+            AuctionInstruction::SetPriceSchedule {
+                breakpoints: vec![
+                    PriceBreakpoint {
+                        offset: 0,
+                        price: 20,
+                    },
+                    PriceBreakpoint {
+                        offset: 60,
+                        price: 0,
+                    },
+                ],
+            },
+        ),
+        (
+            "make_order_bid",
+            AuctionInstruction::MakeOrderBid {
+                token_amount: 100,
+                max_price: 50,
+                expires_at: 1_000,
+                nonce: 2,
+            },
+        ),
+        ("create_bid_receipt", AuctionInstruction::CreateBidReceipt),
+        ("claim_refund", AuctionInstruction::ClaimRefund),
+        (
+            "commit_bid",
+            AuctionInstruction::CommitBid {
+                commitment: [7; 32],
+                escrow: 5_000,
+            },
+        ),
+        (
+            "reveal_bid",
+            AuctionInstruction::RevealBid {
+                token_amount: 100,
+                price: 50,
+                salt: [9; 32],
+            },
+        ),
+        ("settle_sealed_bids", AuctionInstruction::SettleSealedBids),
+        ("claim_sealed_bid", AuctionInstruction::ClaimSealedBid),
+        (
+            "make_relayed_bid",
+            AuctionInstruction::MakeRelayedBid {
+                token_amount: 100,
+                max_price: 50,
+                expires_at: 1_000,
+                nonce: 2,
+            },
+        ),
+    ]
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub(crate) fn serialize_coption_key<S: Serializer>(
+    key: &COption<Pubkey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match key {
+        COption::Some(key) => serializer.serialize_some(key),
+        COption::None => serializer.serialize_none(),
+    }
+}
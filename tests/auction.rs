@@ -1,27 +1,44 @@
 use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
 use solana_sdk::{
-    account::{from_account, Account, ReadableAccount},
-    clock::{Clock, UnixTimestamp},
+    account::ReadableAccount,
+    clock::UnixTimestamp,
     decode_error::DecodeError,
-    instruction::{Instruction, InstructionError},
+    instruction::InstructionError,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
     rent::Rent,
     signature::Signer,
-    signer::{keypair::Keypair, signers::Signers},
-    system_instruction, sysvar,
-    transaction::{Transaction, TransactionError},
-    transport::{Result as RpcResult, TransportError},
+    signer::keypair::Keypair,
+    system_instruction,
+    transaction::TransactionError,
+    transport::TransportError,
 };
 use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
 use spl_token::{
-    instruction::{initialize_mint, mint_to_checked, set_authority, AuthorityType},
-    state::{Account as TokenAccount, Mint},
+    instruction::{
+        initialize_mint, initialize_multisig, mint_to_checked, set_authority, AuthorityType,
+    },
+    state::{Account as TokenAccount, Mint, Multisig},
 };
 
 use dutch_auction::{
-    error::AuctionError, instruction as auction_instruction, processor::Processor, state::Auction,
+    error::AuctionError,
+    instruction::{
+        self as auction_instruction, AuctionConfig, AuthoritySigners, BidAccounts, BidOptions,
+        TokenSource,
+    },
+    order::PurchaseOrder,
+    processor::Processor,
+    state::{AdminLog, Auction, BidReceipt, Deposit, SealedBid, Session},
+};
+
+mod testing;
+
+use testing::{
+    ed25519_instruction, expect_auction_error, expect_instruction_error, get_account, get_balance,
+    get_unix_timestamp, move_forward, move_to_next_slot, program_test, program_test_with_ed25519,
+    send_tx, warp_to_price_step, TestAuction,
 };
 
 const TOKEN_AMOUNT: u64 = 100;
@@ -78,7 +95,6 @@ async fn dutch_auction() {
         &token_kp,
         &auction_kp,
         &auction_token_owner_pk,
-        &auction_token_pk,
         &auction_owner_kp,
         &auction_owner_token_pk,
     )
@@ -111,6 +127,8 @@ async fn dutch_auction() {
     // Print data from storage.
     print_account::<Mint>(&mut ctx, "Token", token_kp.pubkey()).await;
     print_account::<Auction>(&mut ctx, "Auction", auction_kp.pubkey()).await;
+    let (admin_log_pk, _) = AdminLog::find_address(&auction_kp.pubkey());
+    print_account::<AdminLog>(&mut ctx, "Admin log", admin_log_pk).await;
     // print_account::<EmptyData>(&mut ctx, "Auction token owner", auction_token_owner_pk).await;
     print_account::<TokenAccount>(&mut ctx, "Auction token", auction_token_pk).await;
     print_account::<EmptyData>(&mut ctx, "Auction owner", auction_owner_kp.pubkey()).await;
@@ -118,19 +136,1088 @@ async fn dutch_auction() {
     print_account::<TokenAccount>(&mut ctx, "Customer token", customer_token_pk).await;
 }
 
-async fn send_tx<T: Signers>(
-    ctx: &mut ProgramTestContext,
-    instructions: &[Instruction],
-    signing_keypairs: &T,
-) -> RpcResult<()> {
-    let recent_blockhash = ctx.banks_client.get_recent_blockhash().await;
-    let transaction = Transaction::new_signed_with_payer(
-        instructions,
-        Some(&ctx.payer.pubkey()),
-        signing_keypairs,
-        recent_blockhash.expect("get_recent_blockhash failed"),
-    );
-    ctx.banks_client.process_transaction(transaction).await
+#[tokio::test]
+async fn dutch_auction_step_boundaries() {
+    let token_kp = Keypair::new();
+    let (auction_kp, auction_token_owner_pk) = loop {
+        let kp = Keypair::new();
+        let derived = Pubkey::create_program_address(&[kp.pubkey().as_ref()], &dutch_auction::id());
+        if let Ok(pk) = derived {
+            break (kp, pk);
+        }
+    };
+    let auction_pk = auction_kp.pubkey();
+    let auction_token_pk =
+        get_associated_token_address(&auction_token_owner_pk, &token_kp.pubkey());
+    let auction_owner_kp = Keypair::new();
+    let auction_owner_token_pk =
+        get_associated_token_address(&auction_owner_kp.pubkey(), &token_kp.pubkey());
+    let customer_kp = Keypair::new();
+    let customer_token_pk = get_associated_token_address(&customer_kp.pubkey(), &token_kp.pubkey());
+
+    let program_test = ProgramTest::new(
+        "dutch_auction",
+        dutch_auction::id(),
+        processor!(Processor::process),
+    );
+    let mut ctx = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&ctx.payer.to_bytes()).expect("invalid payer");
+
+    let rent = ctx.banks_client.get_rent().await.expect("get_rent failed");
+
+    create_nft(
+        &mut ctx,
+        &payer,
+        &rent,
+        &token_kp,
+        &auction_owner_kp.pubkey(),
+        &auction_owner_token_pk,
+    )
+    .await;
+    auction_initialize(
+        &mut ctx,
+        &payer,
+        &rent,
+        &token_kp,
+        &auction_kp,
+        &auction_token_owner_pk,
+        &auction_owner_kp,
+        &auction_owner_token_pk,
+    )
+    .await;
+    send_tx(
+        &mut ctx,
+        &[create_associated_token_account(
+            &payer.pubkey(),
+            &customer_kp.pubkey(),
+            &token_kp.pubkey(),
+        )],
+        &[&payer],
+    )
+    .await
+    .expect("failed to create customer account for tokens");
+
+    let bid_accounts = BidAccounts {
+        token: token_kp.pubkey(),
+        token_auction: auction_token_pk,
+        token_auction_owner: auction_token_owner_pk,
+        token_customer: customer_token_pk,
+        ..BidAccounts::default()
+    };
+    let make_bid = |token_amount| {
+        auction_instruction::make_bid(
+            &auction_pk,
+            &payer.pubkey(),
+            &bid_accounts,
+            token_amount,
+            &BidOptions::default(),
+        )
+        .expect("failed to create MakeBid instruction")
+    };
+
+    let acc = get_account(&mut ctx, auction_pk).await;
+    let auction = Auction::unpack(acc.data()).expect("Valid packed data");
+    let steps = auction.steps_total() as i64;
+
+    // First second of the second step, price already decreased once
+    warp_to_price_step(&mut ctx, &auction_pk, 1).await;
+    let balance = get_balance(&mut ctx, &auction_token_owner_pk).await;
+    send_tx(&mut ctx, &[make_bid(1)], &[&payer])
+        .await
+        .expect("failed to make a bid");
+    assert_eq!(
+        get_balance(&mut ctx, &auction_token_owner_pk).await - balance,
+        PRICE_START - PRICE_STEP
+    );
+
+    // Last price step, price is lowest
+    warp_to_price_step(&mut ctx, &auction_pk, steps - 1).await;
+    let balance = get_balance(&mut ctx, &auction_token_owner_pk).await;
+    send_tx(&mut ctx, &[make_bid(1)], &[&payer])
+        .await
+        .expect("failed to make a bid");
+    assert_eq!(
+        get_balance(&mut ctx, &auction_token_owner_pk).await - balance,
+        PRICE_START - PRICE_STEP * (steps as u64 - 1)
+    );
+
+    // First second after finish
+    warp_to_price_step(&mut ctx, &auction_pk, steps).await;
+    let error = send_tx(&mut ctx, &[make_bid(1)], &[&payer])
+        .await
+        .expect_err("expected error");
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(x),
+        )) => {
+            assert_eq!(
+                AuctionError::decode_custom_error_to_enum(x),
+                Some(AuctionError::Finished)
+            );
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    };
+}
+
+#[tokio::test]
+async fn dutch_auction_account_validation() {
+    let token_kp = Keypair::new();
+    let (auction_kp, auction_token_owner_pk) = loop {
+        let kp = Keypair::new();
+        let derived = Pubkey::create_program_address(&[kp.pubkey().as_ref()], &dutch_auction::id());
+        if let Ok(pk) = derived {
+            break (kp, pk);
+        }
+    };
+    let auction_pk = auction_kp.pubkey();
+    let auction_token_pk =
+        get_associated_token_address(&auction_token_owner_pk, &token_kp.pubkey());
+    let auction_owner_kp = Keypair::new();
+    let auction_owner_token_pk =
+        get_associated_token_address(&auction_owner_kp.pubkey(), &token_kp.pubkey());
+    let stranger_kp = Keypair::new();
+    let stranger_token_pk = get_associated_token_address(&stranger_kp.pubkey(), &token_kp.pubkey());
+
+    let program_test = ProgramTest::new(
+        "dutch_auction",
+        dutch_auction::id(),
+        processor!(Processor::process),
+    );
+    let mut ctx = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&ctx.payer.to_bytes()).expect("invalid payer");
+
+    let rent = ctx.banks_client.get_rent().await.expect("get_rent failed");
+
+    create_nft(
+        &mut ctx,
+        &payer,
+        &rent,
+        &token_kp,
+        &auction_owner_kp.pubkey(),
+        &auction_owner_token_pk,
+    )
+    .await;
+    send_tx(
+        &mut ctx,
+        &[system_instruction::create_account(
+            &payer.pubkey(),
+            &auction_pk,
+            rent.minimum_balance(Auction::LEN),
+            Auction::LEN as u64,
+            &dutch_auction::id(),
+        )],
+        &[&payer, &auction_kp],
+    )
+    .await
+    .expect("failed to create auction account");
+
+    // InitializeAuction, each case replace one account with a wrong one
+    let time_start = get_unix_timestamp(&mut ctx).await + TIME_STEP;
+    let initialize_auction = |token_auction_pk: &Pubkey, token_auction_owner_pk: &Pubkey| {
+        let mut instruction = auction_instruction::initialize_auction(
+            &auction_pk,
+            &auction_owner_kp.pubkey(),
+            &payer.pubkey(),
+            &token_kp.pubkey(),
+            token_auction_owner_pk,
+            Some(&TokenSource {
+                account: auction_owner_token_pk,
+                authority: auction_owner_kp.pubkey(),
+            }),
+            &AuctionConfig {
+                token_amount: TOKEN_AMOUNT,
+                time_start,
+                time_step: TIME_STEP,
+                price_start: PRICE_START,
+                price_step: PRICE_STEP,
+                ..AuctionConfig::default()
+            },
+        )
+        .expect("failed to create InitializeAuction instruction");
+        // Builder always derives auction token account
+        instruction.accounts[9].pubkey = *token_auction_pk;
+        instruction
+    };
+    let cases = vec![
+        (
+            "auction token owner",
+            initialize_auction(&auction_token_pk, &stranger_kp.pubkey()),
+            AuctionError::InvalidAuctionTokenOwnerAddress,
+        ),
+        (
+            "auction token",
+            initialize_auction(&stranger_token_pk, &auction_token_owner_pk),
+            AuctionError::InvalidAuctionTokenAddress,
+        ),
+    ];
+    for (name, instruction, expected) in cases {
+        let result = send_tx(&mut ctx, &[instruction], &[&payer, &auction_owner_kp]).await;
+        expect_auction_error(name, result, expected);
+    }
+
+    send_tx(
+        &mut ctx,
+        &[initialize_auction(
+            &auction_token_pk,
+            &auction_token_owner_pk,
+        )],
+        &[&payer, &auction_owner_kp],
+    )
+    .await
+    .expect("failed to initialize auction account");
+
+    // Withdraw instructions signed by somebody else than auction authority
+    let cases = vec![
+        (
+            "withdraw tokens authority",
+            auction_instruction::withdraw_tokens(
+                &auction_pk,
+                &AuthoritySigners::new(stranger_kp.pubkey()),
+                &token_kp.pubkey(),
+                &auction_token_owner_pk,
+                &auction_owner_token_pk,
+                None,
+                false,
+            )
+            .expect("failed to create WithdrawToken"),
+            AuctionError::OwnerMismatch,
+        ),
+        (
+            "withdraw sol authority",
+            auction_instruction::withdraw_sol(
+                &auction_pk,
+                &AuthoritySigners::new(stranger_kp.pubkey()),
+                &token_kp.pubkey(),
+                &auction_token_owner_pk,
+                &stranger_kp.pubkey(),
+            )
+            .expect("failed to create WithdrawSOL"),
+            AuctionError::OwnerMismatch,
+        ),
+    ];
+    for (name, instruction, expected) in cases {
+        let result = send_tx(&mut ctx, &[instruction], &[&payer, &stranger_kp]).await;
+        expect_auction_error(name, result, expected);
+    }
+}
+
+#[tokio::test]
+async fn dutch_auction_sealed_bids() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            commit_end: time_start + TIME_STEP * 2,
+            reveal_end: time_start + TIME_STEP * 4,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START * 4).await;
+
+    let salt = [7; 32];
+    let commitment = SealedBid::commitment(&auction_pk, &buyer_kp.pubkey(), 2, PRICE_START, &salt);
+    let commit_bid = || {
+        auction_instruction::commit_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            &commitment,
+            PRICE_START * 2,
+        )
+        .expect("failed to create CommitBid instruction")
+    };
+    let result = t.send(&[commit_bid()], &[&buyer_kp]).await;
+    expect_auction_error("commit before start", result, AuctionError::NotStarted);
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    t.send(&[commit_bid()], &[&buyer_kp])
+        .await
+        .expect("failed to commit bid");
+
+    let reveal_bid = |salt| {
+        auction_instruction::reveal_bid(&auction_pk, &buyer_kp.pubkey(), 2, PRICE_START, salt)
+            .expect("failed to create RevealBid instruction")
+    };
+    let result = t.send(&[reveal_bid(&salt)], &[&buyer_kp]).await;
+    expect_auction_error(
+        "reveal before commit end",
+        result,
+        AuctionError::SealedBidWindowClosed,
+    );
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 2).await;
+    let result = t.send(&[reveal_bid(&[8; 32])], &[&buyer_kp]).await;
+    expect_auction_error("reveal other salt", result, AuctionError::InvalidCommitment);
+    t.send(&[reveal_bid(&salt)], &[&buyer_kp])
+        .await
+        .expect("failed to reveal bid");
+
+    let settle = || {
+        auction_instruction::settle_sealed_bids(&auction_pk)
+            .expect("failed to create SettleSealedBids instruction")
+    };
+    let result = t.send(&[settle()], &[]).await;
+    expect_auction_error(
+        "settle before reveal end",
+        result,
+        AuctionError::NotFinished,
+    );
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 4).await;
+    t.send(&[settle()], &[])
+        .await
+        .expect("failed to settle sealed bids");
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t.send(&[settle()], &[]).await;
+    expect_auction_error("settle twice", result, AuctionError::AlreadySettled);
+
+    let token_pk = t.token.pubkey();
+    let auction_token_pk = t.token_auction;
+    let claim = |token_auction_owner_pk: &Pubkey| {
+        auction_instruction::claim_sealed_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            &token_pk,
+            &auction_token_pk,
+            token_auction_owner_pk,
+            &buyer_token_pk,
+        )
+        .expect("failed to create ClaimSealedBid instruction")
+    };
+    let result = t.send(&[claim(&buyer_kp.pubkey())], &[]).await;
+    expect_auction_error(
+        "claim to other owner",
+        result,
+        AuctionError::InvalidAuctionTokenOwnerAddress,
+    );
+    let claim = claim(&t.token_auction_owner);
+    let balance = get_balance(&mut t.ctx, &t.token_auction_owner).await;
+    t.send(&[claim], &[])
+        .await
+        .expect("failed to claim sealed bid");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 2);
+    assert_eq!(
+        get_balance(&mut t.ctx, &t.token_auction_owner).await - balance,
+        PRICE_START * 2
+    );
+}
+
+#[tokio::test]
+async fn dutch_auction_update_windows() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            time_end: time_start + TIME_STEP * 3,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+
+    // Start can not be moved to or past end fixed at initialization
+    let seller_kp = Keypair::from_bytes(&t.seller.to_bytes()).expect("invalid seller");
+    let update_auction = |time_start| {
+        auction_instruction::update_auction(
+            &auction_pk,
+            &seller_kp.pubkey(),
+            time_start,
+            TIME_STEP,
+            PRICE_START,
+            PRICE_STEP,
+        )
+        .expect("failed to create UpdateAuction instruction")
+    };
+    let result = t
+        .send(&[update_auction(time_start + TIME_STEP * 3)], &[&seller_kp])
+        .await;
+    expect_auction_error(
+        "start at end",
+        result,
+        AuctionError::InvalidInitializationTime,
+    );
+    t.send(&[update_auction(time_start + TIME_STEP)], &[&seller_kp])
+        .await
+        .expect("failed to update auction");
+    assert_eq!(t.get_auction().await.time_start, time_start + TIME_STEP);
+}
+
+#[tokio::test]
+async fn dutch_auction_session_bids() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let now = get_unix_timestamp(&mut t.ctx).await;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start: now + TIME_STEP,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START * 4).await;
+    let session_key_kp = Keypair::new();
+    let (session_pk, _) = Session::find_address(&buyer_kp.pubkey(), &session_key_kp.pubkey());
+
+    let authorize_session = |expires_at| {
+        auction_instruction::authorize_session(
+            &buyer_kp.pubkey(),
+            &session_key_kp.pubkey(),
+            PRICE_START * 2,
+            expires_at,
+        )
+        .expect("failed to create AuthorizeSession instruction")
+    };
+    let result = t.send(&[authorize_session(now)], &[&buyer_kp]).await;
+    expect_auction_error("authorize expired", result, AuctionError::SessionExpired);
+    t.send(&[authorize_session(now + TIME_STEP * 10)], &[&buyer_kp])
+        .await
+        .expect("failed to authorize session");
+
+    // Session lamports are not spent when payee is not auction account
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let bid_accounts = t.bid_accounts(&buyer_token_pk);
+    let session_bid = |bid_accounts| {
+        auction_instruction::make_session_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            &session_key_kp.pubkey(),
+            bid_accounts,
+            1,
+        )
+        .expect("failed to create MakeSessionBid instruction")
+    };
+    let session_balance = get_balance(&mut t.ctx, &session_pk).await;
+    let other_owner = BidAccounts {
+        token_auction_owner: session_key_kp.pubkey(),
+        ..bid_accounts
+    };
+    let result = t
+        .send(&[session_bid(&other_owner)], &[&session_key_kp])
+        .await;
+    expect_auction_error(
+        "session bid to other owner",
+        result,
+        AuctionError::InvalidAuctionTokenOwnerAddress,
+    );
+    assert_eq!(get_balance(&mut t.ctx, &session_pk).await, session_balance);
+
+    t.send(&[session_bid(&bid_accounts)], &[&session_key_kp])
+        .await
+        .expect("failed to make session bid");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 1);
+    assert_eq!(
+        session_balance - get_balance(&mut t.ctx, &session_pk).await,
+        PRICE_START
+    );
+
+    let revoke_session = |wallet_pk: &Pubkey| {
+        auction_instruction::revoke_session(wallet_pk, &session_key_kp.pubkey())
+            .expect("failed to create RevokeSession instruction")
+    };
+    let result = t
+        .send(
+            &[revoke_session(&session_key_kp.pubkey())],
+            &[&session_key_kp],
+        )
+        .await;
+    expect_auction_error(
+        "revoke by session key",
+        result,
+        AuctionError::InvalidSession,
+    );
+    t.send(&[revoke_session(&buyer_kp.pubkey())], &[&buyer_kp])
+        .await
+        .expect("failed to revoke session");
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t
+        .send(&[session_bid(&bid_accounts)], &[&session_key_kp])
+        .await;
+    expect_auction_error("bid after revoke", result, AuctionError::InvalidSession);
+}
+
+#[tokio::test]
+async fn dutch_auction_deposit_bids() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START * 4).await;
+    let (deposit_pk, _) = Deposit::find_address(&buyer_kp.pubkey());
+
+    let deposit_funds = auction_instruction::deposit_funds(&buyer_kp.pubkey(), PRICE_START * 2)
+        .expect("failed to create DepositFunds instruction");
+    t.send(&[deposit_funds], &[&buyer_kp])
+        .await
+        .expect("failed to deposit funds");
+
+    // Deposit covers two tokens only
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let bid_accounts = t.bid_accounts(&buyer_token_pk);
+    let deposit_bid = |token_amount| {
+        auction_instruction::make_deposit_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            &bid_accounts,
+            token_amount,
+        )
+        .expect("failed to create MakeDepositBid instruction")
+    };
+    let result = t.send(&[deposit_bid(3)], &[&buyer_kp]).await;
+    expect_auction_error(
+        "bid above deposit",
+        result,
+        AuctionError::InsufficientLamports,
+    );
+    let deposit_balance = get_balance(&mut t.ctx, &deposit_pk).await;
+    t.send(&[deposit_bid(1)], &[&buyer_kp])
+        .await
+        .expect("failed to make deposit bid");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 1);
+    assert_eq!(
+        deposit_balance - get_balance(&mut t.ctx, &deposit_pk).await,
+        PRICE_START
+    );
+
+    let withdraw_funds = |lamports| {
+        auction_instruction::withdraw_funds(&buyer_kp.pubkey(), lamports)
+            .expect("failed to create WithdrawFunds instruction")
+    };
+    let result = t
+        .send(&[withdraw_funds(PRICE_START * 2)], &[&buyer_kp])
+        .await;
+    expect_auction_error(
+        "withdraw above deposit",
+        result,
+        AuctionError::InsufficientLamports,
+    );
+    let balance = get_balance(&mut t.ctx, &buyer_kp.pubkey()).await;
+    t.send(&[withdraw_funds(PRICE_START)], &[&buyer_kp])
+        .await
+        .expect("failed to withdraw funds");
+    assert_eq!(
+        get_balance(&mut t.ctx, &buyer_kp.pubkey()).await - balance,
+        PRICE_START
+    );
+}
+
+#[tokio::test]
+async fn dutch_auction_order_bids() {
+    let mut t = TestAuction::start(program_test_with_ed25519(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START * 4).await;
+    let (deposit_pk, _) = Deposit::find_address(&buyer_kp.pubkey());
+    let deposit_funds = auction_instruction::deposit_funds(&buyer_kp.pubkey(), PRICE_START * 2)
+        .expect("failed to create DepositFunds instruction");
+    t.send(&[deposit_funds], &[&buyer_kp])
+        .await
+        .expect("failed to deposit funds");
+
+    let order = PurchaseOrder {
+        auction: auction_pk,
+        buyer: buyer_kp.pubkey(),
+        token_amount: 1,
+        max_price: PRICE_START,
+        expires_at: time_start + TIME_STEP * 10,
+        nonce: 0,
+        relayed: false,
+    };
+    let bid_accounts = t.bid_accounts(&buyer_token_pk);
+    let order_bid = |bid_accounts| {
+        auction_instruction::make_order_bid(&order, bid_accounts)
+            .expect("failed to create MakeOrderBid instruction")
+    };
+    let signature = || ed25519_instruction(&buyer_kp, &order.message());
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let result = t.send(&[order_bid(&bid_accounts)], &[]).await;
+    expect_auction_error(
+        "order not signed",
+        result,
+        AuctionError::InvalidOrderSignature,
+    );
+
+    // Deposit is not debited when payee is not auction account
+    let deposit_balance = get_balance(&mut t.ctx, &deposit_pk).await;
+    let other_owner = BidAccounts {
+        token_auction_owner: buyer_kp.pubkey(),
+        ..bid_accounts
+    };
+    let result = t.send(&[signature(), order_bid(&other_owner)], &[]).await;
+    expect_auction_error(
+        "order bid to other owner",
+        result,
+        AuctionError::InvalidAuctionTokenOwnerAddress,
+    );
+    assert_eq!(get_balance(&mut t.ctx, &deposit_pk).await, deposit_balance);
+
+    t.send(&[signature(), order_bid(&bid_accounts)], &[])
+        .await
+        .expect("failed to make order bid");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 1);
+    assert_eq!(
+        deposit_balance - get_balance(&mut t.ctx, &deposit_pk).await,
+        PRICE_START
+    );
+
+    // Order is filled at most once
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t.send(&[signature(), order_bid(&bid_accounts)], &[]).await;
+    expect_auction_error(
+        "order filled twice",
+        result,
+        AuctionError::InvalidOrderNonce,
+    );
+}
+
+#[tokio::test]
+async fn dutch_auction_relayed_bids() {
+    let mut t = TestAuction::start(program_test_with_ed25519(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    // Buyer has no lamports, relayer pays for the order
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(0).await;
+    let (relayer_kp, _) = t.create_buyer(PRICE_START * 4).await;
+
+    let order = PurchaseOrder {
+        auction: auction_pk,
+        buyer: buyer_kp.pubkey(),
+        token_amount: 1,
+        max_price: PRICE_START,
+        expires_at: time_start + TIME_STEP,
+        nonce: 0,
+        relayed: true,
+    };
+    let bid_accounts = t.bid_accounts(&buyer_token_pk);
+    let relayed_bid = |order: &PurchaseOrder| {
+        [
+            ed25519_instruction(&buyer_kp, &order.message()),
+            auction_instruction::make_relayed_bid(order, &relayer_kp.pubkey(), &bid_accounts)
+                .expect("failed to create MakeRelayedBid instruction"),
+        ]
+    };
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let balance = get_balance(&mut t.ctx, &t.token_auction_owner).await;
+    t.send(&relayed_bid(&order), &[&relayer_kp])
+        .await
+        .expect("failed to make relayed bid");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 1);
+    assert_eq!(
+        get_balance(&mut t.ctx, &t.token_auction_owner).await - balance,
+        PRICE_START
+    );
+
+    // Order is filled at most once
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t.send(&relayed_bid(&order), &[&relayer_kp]).await;
+    expect_auction_error(
+        "order filled twice",
+        result,
+        AuctionError::InvalidOrderNonce,
+    );
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 1).await;
+    let order = PurchaseOrder { nonce: 1, ..order };
+    let result = t.send(&relayed_bid(&order), &[&relayer_kp]).await;
+    expect_auction_error("order expired", result, AuctionError::OrderExpired);
+}
+
+#[tokio::test]
+async fn dutch_auction_refunds() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            uniform_price: true,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START * 4).await;
+    let (bid_receipt_pk, _) = BidReceipt::find_address(&auction_pk, &buyer_kp.pubkey());
+
+    let create_bid_receipt =
+        auction_instruction::create_bid_receipt(&auction_pk, &buyer_kp.pubkey())
+            .expect("failed to create CreateBidReceipt instruction");
+    t.send(&[create_bid_receipt], &[&buyer_kp])
+        .await
+        .expect("failed to create bid receipt");
+
+    let bid_accounts = BidAccounts {
+        bid_receipt: Some(bid_receipt_pk),
+        ..t.bid_accounts(&buyer_token_pk)
+    };
+    let make_bid = |bid_accounts| {
+        auction_instruction::make_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            bid_accounts,
+            1,
+            &BidOptions::default(),
+        )
+        .expect("failed to create MakeBid instruction")
+    };
+
+    // Receipt is not credited when payee is not auction account
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let other_owner = BidAccounts {
+        token_auction_owner: buyer_kp.pubkey(),
+        ..bid_accounts
+    };
+    let result = t.send(&[make_bid(&other_owner)], &[&buyer_kp]).await;
+    expect_auction_error(
+        "bid to other owner",
+        result,
+        AuctionError::InvalidAuctionTokenOwnerAddress,
+    );
+    let acc = get_account(&mut t.ctx, bid_receipt_pk).await;
+    let bid_receipt = BidReceipt::unpack(acc.data()).expect("Valid packed data");
+    assert_eq!((bid_receipt.token_amount, bid_receipt.paid), (0, 0));
+
+    // Both tokens are paid at clearing price of the second bid
+    t.send(&[make_bid(&bid_accounts)], &[&buyer_kp])
+        .await
+        .expect("failed to make a bid");
+    warp_to_price_step(&mut t.ctx, &auction_pk, 2).await;
+    t.send(&[make_bid(&bid_accounts)], &[&buyer_kp])
+        .await
+        .expect("failed to make a bid");
+    assert_eq!(
+        t.get_auction().await.clearing_price,
+        PRICE_START - PRICE_STEP * 2
+    );
+
+    let claim_refund = |token_auction_owner_pk: &Pubkey| {
+        auction_instruction::claim_refund(&auction_pk, &buyer_kp.pubkey(), token_auction_owner_pk)
+            .expect("failed to create ClaimRefund instruction")
+    };
+    let token_auction_owner_pk = t.token_auction_owner;
+    let result = t.send(&[claim_refund(&token_auction_owner_pk)], &[]).await;
+    expect_auction_error("refund before finish", result, AuctionError::NotFinished);
+
+    let steps = t.get_auction().await.steps_total() as i64;
+    warp_to_price_step(&mut t.ctx, &auction_pk, steps).await;
+    let result = t.send(&[claim_refund(&buyer_kp.pubkey())], &[]).await;
+    expect_auction_error(
+        "refund from other owner",
+        result,
+        AuctionError::InvalidAuctionTokenOwnerAddress,
+    );
+    let balance = get_balance(&mut t.ctx, &buyer_kp.pubkey()).await;
+    t.send(&[claim_refund(&token_auction_owner_pk)], &[])
+        .await
+        .expect("failed to claim refund");
+    assert_eq!(
+        get_balance(&mut t.ctx, &buyer_kp.pubkey()).await - balance,
+        PRICE_STEP * 2
+    );
+
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t.send(&[claim_refund(&token_auction_owner_pk)], &[]).await;
+    expect_auction_error("refund twice", result, AuctionError::RefundClaimed);
+}
+
+#[tokio::test]
+async fn dutch_auction_free_claims() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &t.seller.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            allow_zero_price: true,
+            free_claim_limit: 5,
+            free_claim_end: time_start + TIME_STEP * 12,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START).await;
+    let (late_buyer_kp, late_buyer_token_pk) = t.create_buyer(PRICE_START).await;
+
+    let token_pk = t.token.pubkey();
+    let auction_token_pk = t.token_auction;
+    let token_auction_owner_pk = t.token_auction_owner;
+    let claim_free = |wallet_pk: &Pubkey, token_customer_pk: &Pubkey| {
+        auction_instruction::claim_free(
+            &auction_pk,
+            wallet_pk,
+            &token_pk,
+            &auction_token_pk,
+            &token_auction_owner_pk,
+            token_customer_pk,
+            None,
+        )
+        .expect("failed to create ClaimFree instruction")
+    };
+
+    // Claims open once price reached zero
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    let result = t
+        .send(
+            &[claim_free(&buyer_kp.pubkey(), &buyer_token_pk)],
+            &[&buyer_kp],
+        )
+        .await;
+    expect_auction_error("claim at positive price", result, AuctionError::NotFinished);
+
+    let steps = t.get_auction().await.steps_total() as i64;
+    warp_to_price_step(&mut t.ctx, &auction_pk, steps).await;
+    t.send(
+        &[claim_free(&buyer_kp.pubkey(), &buyer_token_pk)],
+        &[&buyer_kp],
+    )
+    .await
+    .expect("failed to claim free tokens");
+    assert_eq!(t.token_balance(&buyer_token_pk).await, 5);
+
+    move_to_next_slot(&mut t.ctx).await;
+    let result = t
+        .send(
+            &[claim_free(&buyer_kp.pubkey(), &buyer_token_pk)],
+            &[&buyer_kp],
+        )
+        .await;
+    expect_auction_error("claim twice", result, AuctionError::AlreadyClaimed);
+
+    warp_to_price_step(&mut t.ctx, &auction_pk, 12).await;
+    let result = t
+        .send(
+            &[claim_free(&late_buyer_kp.pubkey(), &late_buyer_token_pk)],
+            &[&late_buyer_kp],
+        )
+        .await;
+    expect_auction_error("claim after window", result, AuctionError::FreeClaimsEnded);
+}
+
+#[tokio::test]
+async fn dutch_auction_multisig_withdraw() {
+    let mut t = TestAuction::start(program_test(), TOKEN_AMOUNT, TOKEN_DECIMALS).await;
+    let auction_pk = t.auction.pubkey();
+
+    // 2 of 3 multisig as auction authority
+    let multisig_kp = Keypair::new();
+    let signer_kps = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let signer_pks = signer_kps.iter().map(|kp| kp.pubkey()).collect::<Vec<_>>();
+    let rent = t
+        .ctx
+        .banks_client
+        .get_rent()
+        .await
+        .expect("get_rent failed");
+    let payer_pk = t.payer.pubkey();
+    t.send(
+        &[
+            system_instruction::create_account(
+                &payer_pk,
+                &multisig_kp.pubkey(),
+                rent.minimum_balance(Multisig::LEN),
+                Multisig::LEN as u64,
+                &spl_token::id(),
+            ),
+            initialize_multisig(
+                &spl_token::id(),
+                &multisig_kp.pubkey(),
+                &signer_pks.iter().collect::<Vec<_>>(),
+                2,
+            )
+            .expect("failed to create InitializeMultisig instruction"),
+        ],
+        &[&multisig_kp],
+    )
+    .await
+    .expect("failed to create multisig");
+
+    let time_start = get_unix_timestamp(&mut t.ctx).await + TIME_STEP;
+    t.initialize(
+        &multisig_kp.pubkey(),
+        &AuctionConfig {
+            token_amount: TOKEN_AMOUNT,
+            time_start,
+            time_step: TIME_STEP,
+            price_start: PRICE_START,
+            price_step: PRICE_STEP,
+            ..AuctionConfig::default()
+        },
+    )
+    .await
+    .expect("failed to initialize auction");
+
+    let (buyer_kp, buyer_token_pk) = t.create_buyer(PRICE_START).await;
+    let bid_accounts = t.bid_accounts(&buyer_token_pk);
+    warp_to_price_step(&mut t.ctx, &auction_pk, 0).await;
+    t.send(
+        &[auction_instruction::make_bid(
+            &auction_pk,
+            &buyer_kp.pubkey(),
+            &bid_accounts,
+            1,
+            &BidOptions::default(),
+        )
+        .expect("failed to create MakeBid instruction")],
+        &[&buyer_kp],
+    )
+    .await
+    .expect("failed to make bid");
+
+    let steps = t.get_auction().await.steps_total() as i64;
+    warp_to_price_step(&mut t.ctx, &auction_pk, steps).await;
+
+    let dest_pk = Keypair::new().pubkey();
+    let token_pk = t.token.pubkey();
+    let token_auction_owner_pk = t.token_auction_owner;
+    let withdraw_sol = |multisig_signers: &[Pubkey]| {
+        auction_instruction::withdraw_sol(
+            &auction_pk,
+            &AuthoritySigners {
+                authority: multisig_kp.pubkey(),
+                multisig_signers: multisig_signers.to_vec(),
+                ..AuthoritySigners::default()
+            },
+            &token_pk,
+            &token_auction_owner_pk,
+            &dest_pk,
+        )
+        .expect("failed to create WithdrawSOL instruction")
+    };
+
+    // One signature is below threshold
+    let result = t
+        .send(&[withdraw_sol(&signer_pks[..1])], &[&signer_kps[0]])
+        .await;
+    expect_instruction_error(
+        "withdraw with one signer",
+        result,
+        InstructionError::MissingRequiredSignature,
+    );
+
+    let balance = get_balance(&mut t.ctx, &token_auction_owner_pk).await;
+    t.send(
+        &[withdraw_sol(&signer_pks[1..])],
+        &[&signer_kps[1], &signer_kps[2]],
+    )
+    .await
+    .expect("failed to withdraw SOL");
+    assert_eq!(get_balance(&mut t.ctx, &dest_pk).await, balance);
+}
+
+#[cfg(feature = "test-clock")]
+#[tokio::test]
+async fn dutch_auction_clock_override() {
+    let program_test = ProgramTest::new(
+        "dutch_auction",
+        dutch_auction::id(),
+        processor!(Processor::process),
+    );
+    let mut ctx = program_test.start_with_context().await;
+    let payer = Keypair::from_bytes(&ctx.payer.to_bytes()).expect("invalid payer");
+    let session_key_kp = Keypair::new();
+
+    let now = get_unix_timestamp(&mut ctx).await;
+    let set_clock =
+        auction_instruction::set_clock(&payer.pubkey(), now + 1000).expect("set_clock instruction");
+    send_tx(&mut ctx, &[set_clock], &[&payer])
+        .await
+        .expect("set_clock failed");
+
+    // Session which expires before overridden time
+    let mut authorize_session = auction_instruction::authorize_session(
+        &payer.pubkey(),
+        &session_key_kp.pubkey(),
+        0,
+        now + 500,
+    )
+    .expect("authorize_session instruction");
+    let without_override = authorize_session.clone();
+    let (clock_override_pk, _) = dutch_auction::state::ClockOverride::find_address();
+    authorize_session
+        .accounts
+        .push(solana_sdk::instruction::AccountMeta::new_readonly(
+            clock_override_pk,
+            false,
+        ));
+    let result = send_tx(&mut ctx, &[authorize_session], &[&payer]).await;
+    expect_auction_error("clock override", result, AuctionError::SessionExpired);
+
+    // Clock sysvar is used when override account is not passed
+    send_tx(&mut ctx, &[without_override], &[&payer])
+        .await
+        .expect("authorize_session failed");
 }
 
 async fn create_nft(
@@ -227,7 +1314,6 @@ async fn auction_initialize(
     token_kp: &Keypair,
     auction_kp: &Keypair,
     auction_token_owner_pk: &Pubkey,
-    auction_token_pk: &Pubkey,
     auction_owner_kp: &Keypair,
     auction_owner_token_pk: &Pubkey,
 ) {
@@ -254,15 +1340,19 @@ async fn auction_initialize(
             &auction_owner_kp.pubkey(),
             &payer.pubkey(),
             &token_kp.pubkey(),
-            auction_owner_token_pk,
-            auction_token_pk,
             auction_token_owner_pk,
-            &auction_owner_kp.pubkey(),
-            TOKEN_AMOUNT,
-            time_start + TIME_STEP,
-            TIME_STEP,
-            PRICE_START,
-            PRICE_STEP,
+            Some(&TokenSource {
+                account: *auction_owner_token_pk,
+                authority: auction_owner_kp.pubkey(),
+            }),
+            &AuctionConfig {
+                token_amount: TOKEN_AMOUNT,
+                time_start: time_start + TIME_STEP,
+                time_step: TIME_STEP,
+                price_start: PRICE_START,
+                price_step: PRICE_STEP,
+                ..AuctionConfig::default()
+            },
         )
         .expect("failed to create InitializeAuction instruction")],
         &[payer, auction_owner_kp],
@@ -295,6 +1385,14 @@ async fn trade(
     .await
     .expect("failed to create customer account for tokens");
 
+    let bid_accounts = BidAccounts {
+        token: token_kp.pubkey(),
+        token_auction: *auction_token_pk,
+        token_auction_owner: *auction_token_owner_pk,
+        token_customer: *customer_token_pk,
+        ..BidAccounts::default()
+    };
+
     macro_rules! assert_error {
         ($fut:expr, $expected:expr) => {
             let error = $fut.await.expect_err("expected error");
@@ -320,11 +1418,9 @@ async fn trade(
             &[auction_instruction::make_bid(
                 &auction_pk,
                 &payer.pubkey(),
-                &token_kp.pubkey(),
-                auction_token_pk,
-                auction_token_owner_pk,
-                customer_token_pk,
+                &bid_accounts,
                 1,
+                &BidOptions::default()
             )
             .expect("failed to create MakeBid instruction")],
             &[payer],
@@ -340,11 +1436,9 @@ async fn trade(
         &[auction_instruction::make_bid(
             &auction_pk,
             &payer.pubkey(),
-            &token_kp.pubkey(),
-            auction_token_pk,
-            auction_token_owner_pk,
-            customer_token_pk,
+            &bid_accounts,
             1,
+            &BidOptions::default(),
         )
         .expect("failed to create MakeBid instruction")],
         &[payer],
@@ -360,11 +1454,12 @@ async fn trade(
             ctx,
             &[auction_instruction::withdraw_tokens(
                 &auction_pk,
-                &auction_owner_kp.pubkey(),
+                &AuthoritySigners::new(auction_owner_kp.pubkey()),
                 &token_kp.pubkey(),
-                auction_token_pk,
                 auction_token_owner_pk,
                 auction_owner_token_pk,
+                None,
+                false
             )
             .expect("failed to create WithdrawToken")],
             &[payer, auction_owner_kp],
@@ -379,10 +1474,10 @@ async fn trade(
             ctx,
             &[auction_instruction::withdraw_sol(
                 &auction_pk,
-                &auction_owner_kp.pubkey(),
+                &AuthoritySigners::new(auction_owner_kp.pubkey()),
                 &token_kp.pubkey(),
                 auction_token_owner_pk,
-                &auction_owner_kp.pubkey(),
+                &auction_owner_kp.pubkey()
             )
             .expect("failed to create WithdrawToken")],
             &[payer, auction_owner_kp],
@@ -396,11 +1491,9 @@ async fn trade(
         &[auction_instruction::make_bid(
             &auction_pk,
             &payer.pubkey(),
-            &token_kp.pubkey(),
-            auction_token_pk,
-            auction_token_owner_pk,
-            customer_token_pk,
+            &bid_accounts,
             TOKEN_AMOUNT,
+            &BidOptions::default(),
         )
         .expect("failed to create MakeBid instruction")],
         &[payer],
@@ -415,11 +1508,9 @@ async fn trade(
             &[auction_instruction::make_bid(
                 &auction_pk,
                 &payer.pubkey(),
-                &token_kp.pubkey(),
-                auction_token_pk,
-                auction_token_owner_pk,
-                customer_token_pk,
+                &bid_accounts,
                 1,
+                &BidOptions::default()
             )
             .expect("failed to create MakeBid instruction")],
             &[payer],
@@ -427,10 +1518,9 @@ async fn trade(
         AuctionError::EverythingSoldOut
     );
 
-    let mut steps = PRICE_START.div_euclid(PRICE_STEP) as i64;
-    if PRICE_START.rem_euclid(PRICE_STEP) > 0 {
-        steps += 1;
-    }
+    let acc = get_account(ctx, *auction_pk).await;
+    let auction = Auction::unpack(acc.data()).expect("Valid packed data");
+    let steps = auction.steps_total() as i64;
     move_forward(ctx, TIME_STEP * (steps - 1)).await;
 
     // Auction finished
@@ -440,11 +1530,9 @@ async fn trade(
             &[auction_instruction::make_bid(
                 &auction_pk,
                 &payer.pubkey(),
-                &token_kp.pubkey(),
-                auction_token_pk,
-                auction_token_owner_pk,
-                customer_token_pk,
+                &bid_accounts,
                 1,
+                &BidOptions::default()
             )
             .expect("failed to create MakeBid instruction")],
             &[payer],
@@ -457,11 +1545,12 @@ async fn trade(
         ctx,
         &[auction_instruction::withdraw_tokens(
             &auction_pk,
-            &auction_owner_kp.pubkey(),
+            &AuthoritySigners::new(auction_owner_kp.pubkey()),
             &token_kp.pubkey(),
-            auction_token_pk,
             auction_token_owner_pk,
             auction_owner_token_pk,
+            None,
+            false,
         )
         .expect("failed to create WithdrawToken")],
         &[payer, auction_owner_kp],
@@ -474,7 +1563,7 @@ async fn trade(
         ctx,
         &[auction_instruction::withdraw_sol(
             &auction_pk,
-            &auction_owner_kp.pubkey(),
+            &AuthoritySigners::new(auction_owner_kp.pubkey()),
             &token_kp.pubkey(),
             auction_token_owner_pk,
             &auction_owner_kp.pubkey(),
@@ -512,31 +1601,6 @@ async fn verify(
     assert_eq!(data.amount, TOKEN_AMOUNT);
 }
 
-async fn get_unix_timestamp(ctx: &mut ProgramTestContext) -> UnixTimestamp {
-    let account = ctx
-        .banks_client
-        .get_account(sysvar::clock::id())
-        .await
-        .expect("failed to call get_account")
-        .expect("Clock sysvar not precent");
-    from_account::<Clock, _>(&account)
-        .expect("failed to deserialize Clock sysvar")
-        .unix_timestamp
-}
-
-async fn move_forward(ctx: &mut ProgramTestContext, shift: UnixTimestamp) {
-    let required_time = get_unix_timestamp(ctx).await + shift;
-    while required_time > get_unix_timestamp(ctx).await {
-        move_to_next_slot(ctx).await;
-    }
-}
-
-async fn move_to_next_slot(ctx: &mut ProgramTestContext) {
-    let slot = ctx.banks_client.get_root_slot().await;
-    let new_slot = slot.expect("failed to get Slot") + 1;
-    ctx.warp_to_slot(new_slot + 1).expect("failed to warp");
-}
-
 async fn print_account<T>(ctx: &mut ProgramTestContext, name: &str, key: Pubkey)
 where
     T: std::fmt::Debug + IsInitialized + Pack,
@@ -547,14 +1611,6 @@ where
     println!("Unpacked data: {:?}", T::unpack(&acc.data()));
 }
 
-async fn get_account(ctx: &mut ProgramTestContext, key: Pubkey) -> Account {
-    ctx.banks_client
-        .get_account(key)
-        .await
-        .expect("failed to call get_account")
-        .expect("account not found")
-}
-
 #[derive(Debug)]
 struct EmptyData;
 
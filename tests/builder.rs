@@ -0,0 +1,155 @@
+use solana_sdk::pubkey::Pubkey;
+
+use dutch_auction::{
+    error::BuilderError,
+    instruction::{
+        self as auction_instruction, AuctionConfig, AuthoritySigners, BidAccounts, BidOptions,
+        BundleFill, MAX_BUNDLE_SIZE,
+    },
+    merkle::MAX_MERKLE_PROOF_LEN,
+    state::PriceBreakpoint,
+};
+
+#[test]
+fn builder_errors() {
+    let auction_pk = Pubkey::new_unique();
+    let wallet_pk = Pubkey::new_unique();
+
+    assert_eq!(
+        auction_instruction::poke(&Pubkey::default()),
+        Err(BuilderError::DefaultPubkey("auction"))
+    );
+    assert_eq!(
+        auction_instruction::deposit_funds(&wallet_pk, 0),
+        Err(BuilderError::ZeroAmount("lamports"))
+    );
+    let bid_accounts = BidAccounts {
+        token: Pubkey::new_unique(),
+        token_auction: Pubkey::new_unique(),
+        token_auction_owner: Pubkey::new_unique(),
+        token_customer: Pubkey::new_unique(),
+        ..BidAccounts::default()
+    };
+    assert_eq!(
+        auction_instruction::make_bid(
+            &auction_pk,
+            &wallet_pk,
+            &BidAccounts {
+                token_customer: Pubkey::default(),
+                ..bid_accounts
+            },
+            1,
+            &BidOptions::default(),
+        ),
+        Err(BuilderError::DefaultPubkey("token_customer"))
+    );
+    assert_eq!(
+        auction_instruction::make_bid(
+            &auction_pk,
+            &wallet_pk,
+            &bid_accounts,
+            1,
+            &BidOptions {
+                merkle_proof: vec![[0; 32]; MAX_MERKLE_PROOF_LEN + 1],
+                ..BidOptions::default()
+            },
+        ),
+        Err(BuilderError::MerkleProofTooLong)
+    );
+
+    let initialize_auction = |time_step, assume_prefunded| {
+        auction_instruction::initialize_auction(
+            &auction_pk,
+            &wallet_pk,
+            &wallet_pk,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            None,
+            &AuctionConfig {
+                token_amount: 100,
+                time_start: 1_625_000_000,
+                time_step,
+                price_start: 10,
+                price_step: 1,
+                assume_prefunded,
+                ..AuctionConfig::default()
+            },
+        )
+    };
+    assert_eq!(
+        initialize_auction(60, false),
+        Err(BuilderError::DefaultPubkey("token_source"))
+    );
+    assert_eq!(
+        initialize_auction(0, true),
+        Err(BuilderError::InvalidTimeStep)
+    );
+    // Source accounts are not used for prefunded auction
+    assert!(initialize_auction(60, true).is_ok());
+
+    let fill = BundleFill {
+        auction: auction_pk,
+        bid_accounts,
+        token_amount: 1,
+    };
+    assert_eq!(
+        auction_instruction::make_bundle_bid(&wallet_pk, &[], 0),
+        Err(BuilderError::InvalidBundleSize)
+    );
+    assert_eq!(
+        auction_instruction::make_bundle_bid(&wallet_pk, &[fill; MAX_BUNDLE_SIZE + 1], 0),
+        Err(BuilderError::InvalidBundleSize)
+    );
+    assert_eq!(
+        auction_instruction::make_bundle_bid(
+            &wallet_pk,
+            &[
+                fill,
+                BundleFill {
+                    token_amount: 0,
+                    ..fill
+                }
+            ],
+            0
+        ),
+        Err(BuilderError::ZeroAmount("token_amount"))
+    );
+
+    assert_eq!(
+        auction_instruction::set_price_schedule(
+            &auction_pk,
+            &wallet_pk,
+            &[PriceBreakpoint {
+                offset: 10,
+                price: 5,
+            }]
+        ),
+        Err(BuilderError::InvalidPriceSchedule)
+    );
+}
+
+#[test]
+fn multisig_withdraw_accounts() {
+    let auction_pk = Pubkey::new_unique();
+    let multisig_pk = Pubkey::new_unique();
+    let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let instruction = auction_instruction::withdraw_sol(
+        &auction_pk,
+        &AuthoritySigners {
+            multisig_signers: signers.to_vec(),
+            ..AuthoritySigners::new(multisig_pk)
+        },
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+    )
+    .unwrap();
+    assert_eq!(instruction.accounts[1].pubkey, multisig_pk);
+    assert!(!instruction.accounts[1].is_signer);
+    let appended = &instruction.accounts[instruction.accounts.len() - 2..];
+    for (meta, signer) in appended.iter().zip(signers.iter()) {
+        assert_eq!(&meta.pubkey, signer);
+        assert!(meta.is_signer);
+    }
+}
@@ -0,0 +1,99 @@
+use num_traits::FromPrimitive;
+use solana_sdk::program_error::ProgramError;
+
+use dutch_auction::error::{self, AuctionError};
+
+// Exhaustive, so new variant without `CODE_*` constant does not compile.
+fn code(error: &AuctionError) -> u32 {
+    match error {
+        AuctionError::AlreadyInUse => error::CODE_ALREADY_IN_USE,
+        AuctionError::InvalidInstruction => error::CODE_INVALID_INSTRUCTION,
+        AuctionError::InvalidInitializationTime => error::CODE_INVALID_INITIALIZATION_TIME,
+        AuctionError::InvalidAuctionTokenOwnerAddress => {
+            error::CODE_INVALID_AUCTION_TOKEN_OWNER_ADDRESS
+        }
+        AuctionError::InvalidAuctionTokenAddress => error::CODE_INVALID_AUCTION_TOKEN_ADDRESS,
+        AuctionError::NotStarted => error::CODE_NOT_STARTED,
+        AuctionError::Finished => error::CODE_FINISHED,
+        AuctionError::EverythingSoldOut => error::CODE_EVERYTHING_SOLD_OUT,
+        AuctionError::OwnerMismatch => error::CODE_OWNER_MISMATCH,
+        AuctionError::NotFinished => error::CODE_NOT_FINISHED,
+        AuctionError::InsufficientPrefundedTokens => error::CODE_INSUFFICIENT_PREFUNDED_TOKENS,
+        AuctionError::InvalidHookProgram => error::CODE_INVALID_HOOK_PROGRAM,
+        AuctionError::NotAllowlisted => error::CODE_NOT_ALLOWLISTED,
+        AuctionError::InvalidAllowlistEntry => error::CODE_INVALID_ALLOWLIST_ENTRY,
+        AuctionError::InvalidBlackoutWindow => error::CODE_INVALID_BLACKOUT_WINDOW,
+        AuctionError::InBlackout => error::CODE_IN_BLACKOUT,
+        AuctionError::InvalidSession => error::CODE_INVALID_SESSION,
+        AuctionError::SessionExpired => error::CODE_SESSION_EXPIRED,
+        AuctionError::InvalidDeposit => error::CODE_INVALID_DEPOSIT,
+        AuctionError::PriceLockDisabled => error::CODE_PRICE_LOCK_DISABLED,
+        AuctionError::InvalidQuoteTime => error::CODE_INVALID_QUOTE_TIME,
+        AuctionError::InvalidLaunchMint => error::CODE_INVALID_LAUNCH_MINT,
+        AuctionError::StepSoldOut => error::CODE_STEP_SOLD_OUT,
+        AuctionError::InvalidAdminLog => error::CODE_INVALID_ADMIN_LOG,
+        AuctionError::InvalidAmount => error::CODE_INVALID_AMOUNT,
+        AuctionError::BundleCostExceeded => error::CODE_BUNDLE_COST_EXCEEDED,
+        AuctionError::BundleNotFilled => error::CODE_BUNDLE_NOT_FILLED,
+        AuctionError::InvalidTakerFee => error::CODE_INVALID_TAKER_FEE,
+        AuctionError::UpdatesDisabled => error::CODE_UPDATES_DISABLED,
+        AuctionError::UpdateNotReady => error::CODE_UPDATE_NOT_READY,
+        AuctionError::InvalidPendingUpdate => error::CODE_INVALID_PENDING_UPDATE,
+        AuctionError::DuplicateListing => error::CODE_DUPLICATE_LISTING,
+        AuctionError::InvalidListingMarker => error::CODE_INVALID_LISTING_MARKER,
+        AuctionError::InvalidBuyerProfile => error::CODE_INVALID_BUYER_PROFILE,
+        AuctionError::InsufficientBuyerHistory => error::CODE_INSUFFICIENT_BUYER_HISTORY,
+        AuctionError::InvalidSettlementSummary => error::CODE_INVALID_SETTLEMENT_SUMMARY,
+        AuctionError::InvalidFreeClaim => error::CODE_INVALID_FREE_CLAIM,
+        AuctionError::AlreadyClaimed => error::CODE_ALREADY_CLAIMED,
+        AuctionError::InvalidCustomerTokenAddress => error::CODE_INVALID_CUSTOMER_TOKEN_ADDRESS,
+        AuctionError::InvalidTipRecipient => error::CODE_INVALID_TIP_RECIPIENT,
+        AuctionError::DeniedProgram => error::CODE_DENIED_PROGRAM,
+        AuctionError::AlreadyStarted => error::CODE_ALREADY_STARTED,
+        AuctionError::InvalidPriceMilestones => error::CODE_INVALID_PRICE_MILESTONES,
+        AuctionError::BuyNowDisabled => error::CODE_BUY_NOW_DISABLED,
+        AuctionError::InsufficientLamports => error::CODE_INSUFFICIENT_LAMPORTS,
+        AuctionError::InsufficientTokenBalance => error::CODE_INSUFFICIENT_TOKEN_BALANCE,
+        AuctionError::FrozenTokenAccount => error::CODE_FROZEN_TOKEN_ACCOUNT,
+        AuctionError::InvalidRentPayer => error::CODE_INVALID_RENT_PAYER,
+        AuctionError::InvalidPaymentMint => error::CODE_INVALID_PAYMENT_MINT,
+        AuctionError::InvalidAuctionPaymentAddress => error::CODE_INVALID_AUCTION_PAYMENT_ADDRESS,
+        AuctionError::InvalidDecayRate => error::CODE_INVALID_DECAY_RATE,
+        AuctionError::InvalidOrderSignature => error::CODE_INVALID_ORDER_SIGNATURE,
+        AuctionError::OrderExpired => error::CODE_ORDER_EXPIRED,
+        AuctionError::InvalidOrderNonce => error::CODE_INVALID_ORDER_NONCE,
+        AuctionError::OrderPriceExceeded => error::CODE_ORDER_PRICE_EXCEEDED,
+        AuctionError::InvalidPriceSchedule => error::CODE_INVALID_PRICE_SCHEDULE,
+        AuctionError::InvalidPriceCeiling => error::CODE_INVALID_PRICE_CEILING,
+        AuctionError::InvalidBidReceipt => error::CODE_INVALID_BID_RECEIPT,
+        AuctionError::RefundClaimed => error::CODE_REFUND_CLAIMED,
+        AuctionError::InvalidUniformPrice => error::CODE_INVALID_UNIFORM_PRICE,
+        AuctionError::SealedBidsOnly => error::CODE_SEALED_BIDS_ONLY,
+        AuctionError::InvalidSealedAuction => error::CODE_INVALID_SEALED_AUCTION,
+        AuctionError::InvalidSealedBid => error::CODE_INVALID_SEALED_BID,
+        AuctionError::SealedBidWindowClosed => error::CODE_SEALED_BID_WINDOW_CLOSED,
+        AuctionError::InvalidCommitment => error::CODE_INVALID_COMMITMENT,
+        AuctionError::InsufficientEscrow => error::CODE_INSUFFICIENT_ESCROW,
+        AuctionError::InvalidSealedBook => error::CODE_INVALID_SEALED_BOOK,
+        AuctionError::NotSettled => error::CODE_NOT_SETTLED,
+        AuctionError::AlreadySettled => error::CODE_ALREADY_SETTLED,
+        AuctionError::OrderTakerFeeNotSupported => error::CODE_ORDER_TAKER_FEE_NOT_SUPPORTED,
+        AuctionError::FreeClaimsEnded => error::CODE_FREE_CLAIMS_ENDED,
+        AuctionError::InvalidLaunchInitialization => error::CODE_INVALID_LAUNCH_INITIALIZATION,
+        AuctionError::InvalidQuote => error::CODE_INVALID_QUOTE,
+        AuctionError::InvalidSettlementPreview => error::CODE_INVALID_SETTLEMENT_PREVIEW,
+        AuctionError::InvalidRelayedOrder => error::CODE_INVALID_RELAYED_ORDER,
+        AuctionError::InvalidAuctionToken => error::CODE_INVALID_AUCTION_TOKEN,
+    }
+}
+
+#[test]
+fn error_codes() {
+    let mut value = 0;
+    while let Some(error) = AuctionError::from_u32(value) {
+        assert_eq!(code(&error), value, "{:?}", error);
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(value));
+        value += 1;
+    }
+    assert_eq!(value, error::CODE_INVALID_AUCTION_TOKEN + 1);
+}
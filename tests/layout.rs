@@ -0,0 +1,75 @@
+use solana_sdk::program_pack::Pack;
+
+use dutch_auction::{
+    instruction::AuctionInstruction,
+    state::Auction,
+    vectors::{self, TestVectors},
+};
+
+// Packed layouts are stored in accounts and transactions, any change here
+// would break already deployed auctions. If change is intended, fixtures
+// should be updated together with migration plan.
+
+#[test]
+fn auction_layout() {
+    let auction = vectors::auction();
+
+    let mut packed = vec![0; Auction::LEN];
+    Auction::pack(auction, &mut packed).expect("failed to pack Auction");
+    assert_fixture("auction", &vectors::hex(&packed));
+
+    let unpacked = Auction::unpack(&packed).expect("failed to unpack Auction");
+    assert_eq!(unpacked, auction);
+}
+
+#[test]
+fn instructions_layout() {
+    let instructions = vectors::instructions();
+
+    for (name, instruction) in instructions {
+        let packed = instruction.pack();
+        assert_fixture(name, &vectors::hex(&packed));
+
+        let unpacked = AuctionInstruction::unpack(&packed).expect("failed to unpack instruction");
+        assert_eq!(unpacked, instruction, "{}", name);
+    }
+}
+
+#[test]
+fn truncated_instructions() {
+    for (name, instruction) in vectors::instructions() {
+        let packed = instruction.pack();
+        for len in 0..packed.len() {
+            assert!(
+                AuctionInstruction::unpack(&packed[..len]).is_err(),
+                "{} truncated to {} bytes",
+                name,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn test_vectors() {
+    let test_vectors = TestVectors::generate();
+    for test_vector in test_vectors.accounts.iter() {
+        assert_fixture(test_vector.name, &test_vector.hex);
+    }
+    for test_vector in test_vectors.instructions.iter() {
+        assert_fixture(test_vector.name, &test_vector.hex);
+    }
+
+    let json = test_vectors.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).expect("invalid json");
+    assert_eq!(
+        value["instructions"][0]["decoded"]["InitializeAuction"]["token_amount"],
+        100
+    );
+}
+
+fn assert_fixture(name: &str, actual: &str) {
+    let path = format!("{}/tests/fixtures/{}.hex", env!("CARGO_MANIFEST_DIR"), name);
+    let expected = std::fs::read_to_string(&path).expect("failed to read fixture");
+    assert_eq!(actual, expected.trim(), "layout changed for {}", name);
+}
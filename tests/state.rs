@@ -0,0 +1,948 @@
+use solana_sdk::{
+    instruction::Instruction, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+};
+
+use dutch_auction::{
+    client::{diff_auction, estimate_landing_price, Commitment, FieldChange},
+    display::CompactAuction,
+    error::ParseError,
+    instruction::TokenSource,
+    merkle,
+    order::{ed25519_program, PurchaseOrder},
+    preview::{self, PreviewStep},
+    price::{Ascending, Exponential, Linear, Piecewise, PriceEngine},
+    spec::AuctionSpec,
+    state::{
+        format_price, format_token_amount, AdminAction, AdminLog, AdminLogEntry, AmountBasis,
+        Auction, BidReceipt, BuyerProfile, EscrowPolicy, FinalizationPolicy, PendingUpdate,
+        PriceBreakpoint, PriceCurve, PriceMilestone, PriceMilestones, PriceSchedule, Quote,
+        SealedBid, SealedBook, SettlementPreview, SettlementSummary, MAX_DENIED_PROGRAMS,
+    },
+};
+
+fn auction() -> Auction {
+    Auction {
+        is_initialized: true,
+        authority: Pubkey::new_unique(),
+        token: Pubkey::new_unique(),
+        time_start: 1_000,
+        time_step: 10,
+        price_start: 25,
+        price_step: 10,
+        token_amount_for_sale: 1,
+        escrow_policy: EscrowPolicy::IgnoreExtras,
+        price_cumulative: 0,
+        price_cumulative_updated_at: 1_000,
+        hook_program: COption::None,
+        allowlist_enabled: false,
+        blackout_period: 0,
+        blackout_offset: 0,
+        blackout_duration: 0,
+        blackout_pauses_price: false,
+        price_lock_skew: 0,
+        finalization_policy: FinalizationPolicy::ReturnToSeller,
+        max_tokens_per_step: 0,
+        step_sold_index: 0,
+        step_sold_amount: 0,
+        price_curve: PriceCurve::Linear,
+        taker_fee_bps: 0,
+        update_delay: 0,
+        listing_marker: false,
+        cosigner: COption::None,
+        buyer_profiles: false,
+        min_buyer_auctions: 0,
+        tokens_sold: 0,
+        proceeds: 0,
+        last_price: 0,
+        allow_zero_price: false,
+        free_claim_limit: 0,
+        tip_recipient: COption::None,
+        tips: 0,
+        denied_programs: [Pubkey::default(); MAX_DENIED_PROGRAMS],
+        price_milestones: false,
+        price_floor: 0,
+        ended_at: 0,
+        buy_now_price: 0,
+        merkle_root: [0; 32],
+        max_bid_amount: 0,
+        payment_mint: COption::None,
+        price_schedule: PriceSchedule::default(),
+        price_ceiling: 0,
+        uniform_price: false,
+        clearing_price: 0,
+        refunded: 0,
+        commit_end: 0,
+        reveal_end: 0,
+        time_end: 0,
+        free_claim_end: 0,
+    }
+}
+
+#[test]
+fn price_time_integral() {
+    let auction = auction();
+
+    // Prices by steps: 25, 15, 5, 0
+    assert_eq!(auction.price_time_integral(900), 0);
+    assert_eq!(auction.price_time_integral(1_000), 0);
+    assert_eq!(auction.price_time_integral(1_004), 4 * 25);
+    assert_eq!(auction.price_time_integral(1_010), 10 * 25);
+    assert_eq!(auction.price_time_integral(1_013), 10 * 25 + 3 * 15);
+    assert_eq!(auction.price_time_integral(1_030), 10 * (25 + 15 + 5));
+    assert_eq!(auction.price_time_integral(2_000), 10 * (25 + 15 + 5));
+}
+
+#[test]
+fn price_cumulative() {
+    let mut auction = auction();
+
+    auction.update_price_cumulative(1_013);
+    assert_eq!(auction.price_cumulative, 10 * 25 + 3 * 15);
+    assert_eq!(auction.price_cumulative_updated_at, 1_013);
+
+    // Time never goes back
+    auction.update_price_cumulative(1_005);
+    assert_eq!(auction.price_cumulative_updated_at, 1_013);
+
+    auction.update_price_cumulative(1_025);
+    assert_eq!(auction.price_cumulative, 10 * (25 + 15) + 5 * 5);
+}
+
+#[test]
+fn blackout_window() {
+    let mut auction = auction();
+    auction.blackout_period = 100;
+    auction.blackout_offset = 20;
+    auction.blackout_duration = 30;
+
+    assert!(!auction.in_blackout(1_019));
+    assert!(auction.in_blackout(1_020));
+    assert!(auction.in_blackout(1_049));
+    assert!(!auction.in_blackout(1_050));
+    assert!(auction.in_blackout(1_120));
+
+    // Price clock running
+    assert_eq!(auction.price_elapsed(1_060), 60);
+
+    // Price clock paused: blackouts in 1_020..1_050 and 1_120..1_150
+    auction.blackout_pauses_price = true;
+    assert_eq!(auction.price_elapsed(1_010), 10);
+    assert_eq!(auction.price_elapsed(1_030), 20);
+    assert_eq!(auction.price_elapsed(1_060), 30);
+    assert_eq!(auction.price_elapsed(1_130), 90);
+}
+
+#[test]
+fn steps() {
+    let mut auction = auction();
+    // price_start 25, price_step 10: prices 25, 15, 5
+    assert_eq!(auction.steps_total(), 3);
+    assert_eq!(auction.steps_elapsed(990), 0);
+    assert_eq!(auction.steps_elapsed(1_009), 0);
+    assert_eq!(auction.steps_elapsed(1_010), 1);
+    assert_eq!(auction.steps_elapsed(1_035), 3);
+    assert_eq!(auction.remaining(), 1);
+    assert_eq!(auction.price_at(1_010), Some(15));
+    assert_eq!(auction.price_at(1_030), None);
+
+    auction.price_start = 30;
+    assert_eq!(auction.steps_total(), 3);
+    auction.price_step = 0;
+    assert_eq!(auction.steps_total(), u64::MAX);
+}
+
+#[test]
+fn linear_price_engine() {
+    let engine = Linear {
+        price_start: 25,
+        price_step: 10,
+    };
+    assert_eq!(engine.price(0), Some(25));
+    assert_eq!(engine.price(2), Some(5));
+    assert_eq!(engine.price(3), None);
+    assert_eq!(engine.steps_total(), 3);
+    assert_eq!(engine.price_sum(2), 40);
+    assert_eq!(engine.price_sum(10), 45);
+
+    let engine = auction().price_engine();
+    assert_eq!(engine.price(1), Some(15));
+    assert_eq!(engine.price_sum(3), 45);
+}
+
+#[test]
+fn exponential_price_engine() {
+    let engine = Exponential {
+        price_start: 10_000,
+        decay_bps: 5_000,
+    };
+    assert_eq!(engine.price(0), Some(10_000));
+    assert_eq!(engine.price(2), Some(2_500));
+    assert_eq!(engine.price(13), Some(1));
+    assert_eq!(engine.price(14), None);
+    assert_eq!(engine.steps_total(), 14);
+    assert_eq!(engine.price_sum(2), 15_000);
+
+    let engine = Exponential {
+        price_start: u64::MAX,
+        decay_bps: 1,
+    };
+    assert!(engine.steps_total() < 1 << 20);
+    assert_eq!(engine.price(engine.steps_total()), None);
+
+    let engine = Exponential {
+        price_start: 10_000,
+        decay_bps: 0,
+    };
+    assert_eq!(engine.price(1_000), Some(10_000));
+    assert_eq!(engine.steps_total(), u64::MAX);
+
+    let mut auction = auction();
+    auction.price_curve = PriceCurve::Exponential;
+    auction.price_step = 10_000;
+    assert_eq!(auction.price_at(auction.time_start), Some(25));
+    assert_eq!(auction.steps_total(), 1);
+}
+
+#[test]
+fn ascending_price_engine() {
+    let engine = Ascending {
+        price_start: 10,
+        price_step: 5,
+        price_ceiling: 22,
+    };
+    assert_eq!(engine.price(0), Some(10));
+    assert_eq!(engine.price(2), Some(20));
+    assert_eq!(engine.price(3), None);
+    assert_eq!(engine.steps_total(), 3);
+    assert_eq!(engine.price_sum(2), 25);
+    assert_eq!(engine.price_sum(10), 45);
+
+    assert!(Auction::is_valid_ascending(10, 10));
+    assert!(!Auction::is_valid_ascending(0, 10));
+    assert!(!Auction::is_valid_ascending(11, 10));
+
+    let mut auction = auction();
+    auction.price_curve = PriceCurve::Ascending;
+    auction.price_ceiling = 45;
+    assert_eq!(auction.price_at(auction.time_start + 10), Some(35));
+    assert_eq!(auction.price_at(auction.time_start + 30), None);
+    auction.price_step = 0;
+    assert_eq!(auction.steps_total(), u64::MAX);
+}
+
+#[test]
+fn piecewise_price_engine() {
+    let breakpoints = [
+        PriceBreakpoint {
+            offset: 0,
+            price: 30,
+        },
+        PriceBreakpoint {
+            offset: 25,
+            price: 20,
+        },
+        PriceBreakpoint {
+            offset: 30,
+            price: 10,
+        },
+        PriceBreakpoint {
+            offset: 50,
+            price: 0,
+        },
+    ];
+    assert!(PriceSchedule::is_valid(&breakpoints));
+    assert!(!PriceSchedule::is_valid(&breakpoints[1..]));
+    assert!(!PriceSchedule::is_valid(&[breakpoints[0], breakpoints[0]]));
+    assert!(!PriceSchedule::is_valid(&[breakpoints[2], breakpoints[1]]));
+    assert!(!PriceSchedule::is_valid(&[]));
+
+    // Breakpoints at 25 and 30 seconds take effect at step 3
+    let engine = Piecewise::new(10, &breakpoints);
+    assert_eq!(engine.breakpoints(), &[(0, 30), (3, 10), (5, 0)]);
+    assert_eq!(engine.price(2), Some(30));
+    assert_eq!(engine.price(4), Some(10));
+    assert_eq!(engine.price(5), None);
+    assert_eq!(engine.steps_total(), 5);
+    assert_eq!(engine.price_sum(4), 100);
+    assert_eq!(engine.price_sum(10), 110);
+
+    let engine = Piecewise::new(10, &breakpoints[..2]);
+    assert_eq!(engine.steps_total(), u64::MAX);
+    assert_eq!(engine.price(1_000), Some(20));
+
+    let mut auction = auction();
+    auction.price_curve = PriceCurve::Piecewise;
+    auction.price_schedule = PriceSchedule::new(&breakpoints).unwrap();
+    assert_eq!(auction.price_at(auction.time_start + 35), Some(10));
+    assert_eq!(auction.price_at(auction.time_start + 50), None);
+}
+
+#[test]
+fn step_limit() {
+    let mut auction = auction();
+    assert_eq!(auction.step_tokens_available(1_000), u64::MAX);
+
+    // time_step is 10 seconds
+    auction.max_tokens_per_step = 5;
+    assert_eq!(auction.step_tokens_available(1_000), 5);
+    auction.record_step_sale(1_001, 3);
+    assert_eq!(auction.step_tokens_available(1_009), 2);
+    auction.record_step_sale(1_009, 2);
+    assert_eq!(auction.step_tokens_available(1_009), 0);
+
+    // Counter reset in next step
+    assert_eq!(auction.step_tokens_available(1_010), 5);
+    auction.record_step_sale(1_015, 1);
+    assert_eq!(auction.step_sold_index, 1);
+    assert_eq!(auction.step_tokens_available(1_019), 4);
+}
+
+#[test]
+fn taker_fee() {
+    let mut auction = auction();
+    assert_eq!(auction.taker_fee(1_000), 0);
+    auction.taker_fee_bps = 250;
+    assert_eq!(auction.taker_fee(1_000), 25);
+    assert_eq!(auction.taker_fee(39), 0);
+    auction.taker_fee_bps = Auction::BPS_DENOMINATOR;
+    assert_eq!(auction.taker_fee(u64::MAX), u64::MAX);
+}
+
+#[test]
+fn denied_programs() {
+    let mut auction = auction();
+    let program = Pubkey::new_unique();
+    assert!(!auction.is_denied_program(&program));
+    assert!(!auction.is_denied_program(&Pubkey::default()));
+    auction.denied_programs[0] = program;
+    assert!(auction.is_denied_program(&program));
+    assert!(!auction.is_denied_program(&Pubkey::default()));
+}
+
+#[test]
+fn ended_early() {
+    let mut auction = auction();
+    auction.ended_at = 1_005;
+    assert_eq!(auction.price_at(1_000), Some(25));
+    assert!(!auction.is_ended(1_004));
+    assert!(auction.is_ended(1_005));
+    assert_eq!(auction.price_at(1_005), None);
+}
+
+#[test]
+fn fill_cost() {
+    assert_eq!(Auction::fill_cost(1_000, 3), Some(3_000));
+    assert_eq!(Auction::fill_cost(u64::MAX, 1), Some(u64::MAX));
+    assert_eq!(Auction::fill_cost(1_000_000_000_000, 1_000_000_000), None);
+}
+
+#[test]
+fn admin_log() {
+    let mut admin_log = AdminLog {
+        is_initialized: true,
+        ..AdminLog::default()
+    };
+    let entry = |time| AdminLogEntry {
+        action: AdminAction::AddToAllowlist,
+        time,
+        key: Pubkey::new_unique(),
+        amount: 0,
+    };
+
+    admin_log.append(entry(1));
+    admin_log.append(entry(2));
+    let times = |log: &AdminLog| log.recent().iter().map(|e| e.time).collect::<Vec<_>>();
+    assert_eq!(times(&admin_log), vec![1, 2]);
+
+    // Oldest entries are overwritten
+    for time in 3..=20 {
+        admin_log.append(entry(time));
+    }
+    assert_eq!(admin_log.count, 20);
+    assert_eq!(times(&admin_log), (5..=20).collect::<Vec<_>>());
+
+    let mut packed = vec![0; AdminLog::LEN];
+    AdminLog::pack(admin_log, &mut packed).expect("failed to pack AdminLog");
+    assert_eq!(AdminLog::unpack(&packed), Ok(admin_log));
+}
+
+#[test]
+fn pending_update() {
+    let pending_update = PendingUpdate {
+        is_initialized: true,
+        apply_after: 1_003_600,
+        max_tokens_per_step: 20,
+        taker_fee_bps: 250,
+        rent_payer: Pubkey::new_unique(),
+    };
+    let mut packed = vec![0; PendingUpdate::LEN];
+    PendingUpdate::pack(pending_update, &mut packed).expect("failed to pack PendingUpdate");
+    assert_eq!(PendingUpdate::unpack(&packed), Ok(pending_update));
+}
+
+#[test]
+fn settlement_summary() {
+    let settlement_summary = SettlementSummary {
+        is_initialized: true,
+        auction: Pubkey::new_unique(),
+        clearing_price: 50,
+        tokens_sold: 1_000,
+        proceeds: 75_000,
+        settled_at: 1_003_600,
+    };
+    let mut packed = vec![0; SettlementSummary::LEN];
+    SettlementSummary::pack(settlement_summary, &mut packed)
+        .expect("failed to pack SettlementSummary");
+    assert_eq!(SettlementSummary::unpack(&packed), Ok(settlement_summary));
+}
+
+#[test]
+fn quote() {
+    let quote = Quote {
+        is_initialized: true,
+        auction: Pubkey::new_unique(),
+        token_amount: 100,
+        price: 50,
+        lamports: 5_025,
+        quoted_at: 1_000_060,
+    };
+    let mut packed = vec![0; Quote::LEN];
+    Quote::pack(quote, &mut packed).expect("failed to pack Quote");
+    assert_eq!(Quote::unpack(&packed), Ok(quote));
+}
+
+#[test]
+fn settlement_preview() {
+    let settlement_preview = SettlementPreview {
+        is_initialized: true,
+        auction: Pubkey::new_unique(),
+        tokens: 250,
+        finalization_policy: FinalizationPolicy::SendTo(Pubkey::new_unique()),
+        proceeds: 75_000,
+        refunds_owed: 1_500,
+        payment_proceeds: 0,
+        previewed_at: 1_003_600,
+    };
+    let mut packed = vec![0; SettlementPreview::LEN];
+    SettlementPreview::pack(settlement_preview, &mut packed)
+        .expect("failed to pack SettlementPreview");
+    assert_eq!(SettlementPreview::unpack(&packed), Ok(settlement_preview));
+}
+
+#[test]
+fn price_milestones() {
+    let milestone = |sold_bps, floor_bps| PriceMilestone {
+        sold_bps,
+        floor_bps,
+    };
+    assert!(PriceMilestones::is_valid(&[milestone(5_000, 10_000)]));
+    assert!(!PriceMilestones::is_valid(&[]));
+    assert!(!PriceMilestones::is_valid(&[milestone(0, 10_000)]));
+    assert!(!PriceMilestones::is_valid(&[milestone(5_000, 10_001)]));
+    assert!(!PriceMilestones::is_valid(&[
+        milestone(5_000, 5_000),
+        milestone(5_000, 10_000)
+    ]));
+
+    let mut price_milestones = PriceMilestones {
+        is_initialized: true,
+        count: 2,
+        reached: 0,
+        milestones: [
+            milestone(2_500, 5_000),
+            milestone(5_000, 10_000),
+            PriceMilestone::default(),
+            PriceMilestone::default(),
+        ],
+    };
+    let mut packed = vec![0; PriceMilestones::LEN];
+    PriceMilestones::pack(price_milestones, &mut packed).expect("failed to pack PriceMilestones");
+    assert_eq!(PriceMilestones::unpack(&packed), Ok(price_milestones));
+
+    let mut auction = auction();
+    auction.token_amount_for_sale = 80;
+    auction.tokens_sold = 20;
+    auction.last_price = 60;
+    assert!(!price_milestones.apply(&mut auction));
+    assert_eq!(auction.price_floor, 0);
+
+    auction.token_amount_for_sale = 40;
+    auction.tokens_sold = 60;
+    assert!(price_milestones.apply(&mut auction));
+    assert_eq!(price_milestones.reached, 2);
+    assert_eq!(auction.price_floor, 60);
+    // Floor is applied only while price curve is live
+    assert_eq!(auction.price_at(1_000), Some(60));
+    assert_eq!(auction.price_at(1_030), None);
+}
+
+#[test]
+fn buyer_profile() {
+    let auction_a = Pubkey::new_unique();
+    let auction_b = Pubkey::new_unique();
+    let mut buyer_profile = BuyerProfile::default();
+    buyer_profile.record_fill(&auction_a, 10);
+    buyer_profile.record_fill(&auction_a, 5);
+    assert_eq!(buyer_profile.auctions, 1);
+    buyer_profile.record_fill(&auction_b, 20);
+    assert_eq!(buyer_profile.auctions, 2);
+    assert_eq!(buyer_profile.volume, 35);
+    assert_eq!(buyer_profile.last_auction, auction_b);
+}
+
+#[test]
+fn amount_basis() {
+    assert_eq!(AmountBasis::BaseUnits.to_base_units(5, 2), Some(5));
+    assert_eq!(AmountBasis::WholeTokens.to_base_units(5, 2), Some(500));
+    assert_eq!(AmountBasis::WholeTokens.to_base_units(5, 0), Some(5));
+    assert_eq!(AmountBasis::WholeTokens.to_base_units(u64::MAX, 1), None);
+    assert_eq!(AmountBasis::WholeTokens.to_base_units(1, 20), None);
+}
+
+#[test]
+fn format_amounts() {
+    assert_eq!(format_price(0), "0");
+    assert_eq!(format_price(1), "0.000000001");
+    assert_eq!(format_price(1_500_000_000), "1.5");
+    assert_eq!(format_price(10_000_000_000), "10");
+    assert_eq!(format_price(u64::MAX), "18446744073.709551615");
+
+    assert_eq!(format_token_amount(100, 0), "100");
+    assert_eq!(format_token_amount(100, 2), "1");
+    assert_eq!(format_token_amount(105, 2), "1.05");
+    assert_eq!(format_token_amount(5, 3), "0.005");
+    assert_eq!(
+        format_token_amount(u64::MAX, 25),
+        "0.0000018446744073709551615"
+    );
+}
+
+#[test]
+fn auction_diff() {
+    let before = auction();
+    assert_eq!(diff_auction(&before, &before), vec![]);
+
+    let after = Auction {
+        token_amount_for_sale: 0,
+        taker_fee_bps: 250,
+        ..before
+    };
+    let changes = diff_auction(&before, &after);
+    assert_eq!(
+        changes,
+        vec![
+            FieldChange {
+                field: "token_amount_for_sale",
+                before: "1".to_owned(),
+                after: "0".to_owned(),
+            },
+            FieldChange {
+                field: "taker_fee_bps",
+                before: "0".to_owned(),
+                after: "250".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(changes[0].to_string(), "token_amount_for_sale: 1 -> 0");
+}
+
+#[test]
+fn auction_preview() {
+    let preview = preview::render(&auction());
+    assert_eq!(preview.max_proceeds, 25);
+    assert_eq!(preview.duration, Some(30));
+    assert_eq!(
+        preview.steps,
+        vec![
+            PreviewStep {
+                step: 0,
+                time: 1_000,
+                price: 25,
+            },
+            PreviewStep {
+                step: 1,
+                time: 1_010,
+                price: 15,
+            },
+            PreviewStep {
+                step: 2,
+                time: 1_020,
+                price: 5,
+            },
+        ]
+    );
+    assert!(preview.rent.total() > preview.rent.auction);
+
+    let preview = preview::render(&Auction {
+        price_step: 0,
+        ..auction()
+    });
+    assert_eq!(preview.duration, None);
+    assert_eq!(preview.steps.len(), preview::MAX_PREVIEW_STEPS);
+}
+
+#[test]
+fn landing_estimate() {
+    let auction = auction();
+    // Lands in [1_009.7, 1_010.1), step changes at 1_010
+    let estimate = estimate_landing_price(&auction, 1_009, Commitment::Processed, 700);
+    assert_eq!(estimate.max_price, Some(25));
+    assert_eq!(estimate.min_price, Some(15));
+    assert!((estimate.next_step_probability - 0.25).abs() < 1e-9);
+
+    let estimate = estimate_landing_price(&auction, 1_001, Commitment::Processed, 100);
+    assert_eq!(estimate.max_price, estimate.min_price);
+    assert_eq!(estimate.next_step_probability, 0.0);
+}
+
+#[test]
+fn enum_display_roundtrip() {
+    let owner = Pubkey::new_from_array([3; 32]);
+    let policies = [
+        FinalizationPolicy::ReturnToSeller,
+        FinalizationPolicy::Burn,
+        FinalizationPolicy::SendTo(owner),
+    ];
+    for policy in policies.iter() {
+        assert_eq!(policy.to_string().parse(), Ok(*policy));
+    }
+    assert_eq!(
+        FinalizationPolicy::SendTo(owner).to_string(),
+        format!("send-to:{}", owner)
+    );
+
+    for policy in [EscrowPolicy::IgnoreExtras, EscrowPolicy::AddExtrasToSale].iter() {
+        assert_eq!(policy.to_string().parse(), Ok(*policy));
+    }
+    for basis in [AmountBasis::BaseUnits, AmountBasis::WholeTokens].iter() {
+        assert_eq!(basis.to_string().parse(), Ok(*basis));
+    }
+    assert_eq!(PriceCurve::Linear.to_string(), "linear");
+    assert_eq!("linear".parse(), Ok(PriceCurve::Linear));
+    assert_eq!("exponential".parse(), Ok(PriceCurve::Exponential));
+    assert_eq!("piecewise".parse(), Ok(PriceCurve::Piecewise));
+    assert_eq!(PriceCurve::Ascending.to_string(), "ascending");
+    assert_eq!(AdminAction::WithdrawSOL.to_string(), "withdraw-sol");
+    assert_eq!(
+        "end-auction-early".parse(),
+        Ok(AdminAction::EndAuctionEarly)
+    );
+}
+
+#[test]
+fn enum_parse_invalid() {
+    assert_eq!(
+        "Linear".parse::<PriceCurve>(),
+        Err(ParseError {
+            kind: "price curve",
+            value: "Linear".to_owned(),
+        })
+    );
+    assert!("send-to:".parse::<FinalizationPolicy>().is_err());
+    assert!("send-to:xyz".parse::<FinalizationPolicy>().is_err());
+}
+
+#[test]
+fn compact_auction_debug() {
+    let mut auction = auction();
+    let compact = format!("{:?}", CompactAuction(&auction));
+    assert!(!compact.contains('\n'));
+    assert_eq!(
+        compact,
+        format!(
+            "Auction {{ token: {}, start: 1000, step: 10, curve: linear, price: 25/10, \
+             remaining: 1, sold: 0, proceeds: 0 }}",
+            auction.token
+        )
+    );
+
+    auction.ended_at = 1_005;
+    assert!(format!("{:?}", CompactAuction(&auction)).ends_with(", ended_at: 1005 }"));
+}
+
+#[test]
+fn starts_in() {
+    let mut auction = auction();
+    assert_eq!(auction.starts_in(958), Some(42));
+    assert_eq!(auction.starts_in(1_000), None);
+    assert_eq!(auction.starts_in(1_100), None);
+
+    auction.ended_at = 900;
+    assert_eq!(auction.starts_in(958), None);
+}
+
+#[test]
+fn time_end() {
+    let mut auction = auction();
+    auction.time_end = 1_015;
+    // Price is still positive at end
+    assert_eq!(auction.price_at(1_014), Some(15));
+    assert_eq!(auction.price_at(1_015), None);
+    assert!(auction.is_ended(1_015));
+
+    auction.time_end = 0;
+    assert_eq!(auction.price_at(1_015), Some(15));
+    assert!(!auction.is_ended(1_015));
+}
+
+#[test]
+fn auction_flags() {
+    let auction = Auction {
+        is_initialized: true,
+        uniform_price: true,
+        ..Auction::default()
+    };
+    let mut packed = vec![0; Auction::LEN];
+    Auction::pack(auction, &mut packed).expect("failed to pack Auction");
+    assert_eq!(packed[0], 0b1000_0001);
+    assert_eq!(Auction::unpack(&packed), Ok(auction));
+}
+
+#[test]
+fn free_claim_end() {
+    let mut auction = auction();
+    auction.free_claim_end = 1_100;
+    assert!(!auction.in_free_claims(1_050));
+
+    auction.allow_zero_price = true;
+    assert!(auction.in_free_claims(1_050));
+    assert!(auction.in_free_claims(1_099));
+    assert!(!auction.in_free_claims(1_100));
+}
+
+#[test]
+fn merkle_allowlist() {
+    let wallets = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let root = merkle::root(&wallets);
+    for (index, wallet) in wallets.iter().enumerate() {
+        let proof = merkle::proof(&wallets, index).expect("wallet in tree");
+        assert!(merkle::verify(&root, wallet, &proof));
+        assert!(!merkle::verify(&root, &Pubkey::new_unique(), &proof));
+    }
+    assert_eq!(merkle::proof(&wallets, 5), None);
+
+    let single = [wallets[0]];
+    assert_eq!(merkle::root(&single), merkle::leaf(&wallets[0]));
+    assert!(merkle::verify(&merkle::root(&single), &wallets[0], &[]));
+    assert_eq!(merkle::root(&[]), [0; 32]);
+
+    let mut auction = auction();
+    assert!(!auction.has_merkle_allowlist());
+    auction.merkle_root = root;
+    assert!(auction.has_merkle_allowlist());
+}
+
+#[test]
+fn bid_tokens_available() {
+    let mut auction = auction();
+    assert_eq!(auction.bid_tokens_available(), u64::MAX);
+    auction.max_bid_amount = 3;
+    assert_eq!(auction.bid_tokens_available(), 3);
+}
+
+// Same layout as produced by `solana_sdk::ed25519_instruction`, signature is
+// not checked by auction program.
+fn ed25519_instruction(signer: &Pubkey, message: &[u8]) -> Instruction {
+    let mut data = vec![1, 0];
+    for value in &[
+        48u16,
+        u16::MAX,
+        16,
+        u16::MAX,
+        112,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(&[0; 64]);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+#[test]
+fn purchase_order_signature() {
+    let order = PurchaseOrder {
+        auction: Pubkey::new_unique(),
+        buyer: Pubkey::new_unique(),
+        token_amount: 10,
+        max_price: 5,
+        expires_at: 1_000,
+        nonce: 0,
+        relayed: false,
+    };
+    let message = order.message();
+    assert_eq!(&message[..32], dutch_auction::id().as_ref());
+    assert!(order.is_verified_by(&ed25519_instruction(&order.buyer, &message)));
+
+    assert!(!order.is_verified_by(&ed25519_instruction(&Pubkey::new_unique(), &message)));
+    let other = PurchaseOrder {
+        max_price: 6,
+        ..order
+    };
+    assert!(!other.is_verified_by(&ed25519_instruction(&order.buyer, &message)));
+    // Deposit order is not valid as relayed order
+    let relayed = PurchaseOrder {
+        relayed: true,
+        ..order
+    };
+    assert!(!relayed.is_verified_by(&ed25519_instruction(&order.buyer, &message)));
+
+    let mut instruction = ed25519_instruction(&order.buyer, &message);
+    instruction.program_id = Pubkey::new_unique();
+    assert!(!order.is_verified_by(&instruction));
+    // Message from other instruction is not supported
+    let mut instruction = ed25519_instruction(&order.buyer, &message);
+    instruction.data[14..16].copy_from_slice(&0u16.to_le_bytes());
+    assert!(!order.is_verified_by(&instruction));
+}
+
+#[test]
+fn auction_spec_roundtrip() {
+    let auction = Auction {
+        token_amount_for_sale: 7,
+        tokens_sold: 3,
+        cosigner: COption::Some(Pubkey::new_unique()),
+        finalization_policy: FinalizationPolicy::SendTo(Pubkey::new_unique()),
+        merkle_root: [0xab; 32],
+        price_curve: PriceCurve::Piecewise,
+        price_schedule: PriceSchedule::new(&[
+            PriceBreakpoint {
+                offset: 0,
+                price: 30,
+            },
+            PriceBreakpoint {
+                offset: 20,
+                price: 10,
+            },
+        ])
+        .unwrap(),
+        ..auction()
+    };
+    let spec = AuctionSpec::export(&auction);
+    assert_eq!(spec.token_amount, 10);
+    assert_eq!(spec.price_schedule.len(), 2);
+    let json = spec.to_json();
+    assert!(json.contains("\"price_curve\": \"piecewise\""));
+    assert!(json.contains(&format!("\"cosigner\": \"{}\"", auction.cosigner.unwrap())));
+    assert!(json.contains("\"hook_program\": null"));
+    assert_eq!(AuctionSpec::from_json(&json).unwrap(), spec);
+    assert!(AuctionSpec::from_json(&json.replace("piecewise", "cubic")).is_err());
+
+    let instructions = spec
+        .import(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &TokenSource {
+                account: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+            },
+        )
+        .unwrap();
+    assert_eq!(instructions.len(), 2);
+}
+
+#[test]
+fn uniform_price_refunds() {
+    let mut auction = Auction {
+        uniform_price: true,
+        ..auction()
+    };
+    let mut receipts = vec![];
+    for (token_amount, price) in [(2, 25), (1, 15), (3, 20)].iter() {
+        auction.record_clearing_price(*price);
+        let cost = Auction::fill_cost(*token_amount, *price).unwrap();
+        auction.tokens_sold += token_amount;
+        auction.proceeds += cost;
+        receipts.push(BidReceipt {
+            is_initialized: true,
+            token_amount: *token_amount,
+            paid: cost,
+            refunded: false,
+        });
+    }
+    assert_eq!(auction.clearing_price, 15);
+    assert_eq!(auction.refunds_outstanding(), 50 + 15 + 60 - 6 * 15);
+
+    let refunds = receipts
+        .iter()
+        .map(|receipt| receipt.refund(auction.clearing_price))
+        .collect::<Vec<_>>();
+    assert_eq!(refunds, vec![20, 0, 15]);
+    auction.refunded = 20;
+    assert_eq!(auction.refunds_outstanding(), 15);
+
+    auction.uniform_price = false;
+    assert_eq!(auction.refunds_outstanding(), 0);
+
+    let mut packed = vec![0; BidReceipt::LEN];
+    BidReceipt::pack(receipts[0], &mut packed).expect("failed to pack BidReceipt");
+    assert_eq!(BidReceipt::unpack(&packed), Ok(receipts[0]));
+}
+
+#[test]
+fn sealed_book_settlement() {
+    let auction = auction();
+
+    // Levels by steps: 25, 15, 5
+    assert_eq!(SealedBook::level_of(&auction, 30), 0);
+    assert_eq!(SealedBook::level_of(&auction, 20), 1);
+    assert_eq!(SealedBook::level_of(&auction, 15), 1);
+    assert_eq!(SealedBook::level_of(&auction, 3), SealedBid::NO_LEVEL);
+
+    let mut book = SealedBook {
+        is_initialized: true,
+        ..SealedBook::default()
+    };
+    let bids = [(4, 30), (6, 20), (3, 15), (5, 5)]
+        .iter()
+        .map(|(token_amount, price)| {
+            let level = SealedBook::level_of(&auction, *price);
+            book.demand[level as usize] += token_amount;
+            SealedBid {
+                is_initialized: true,
+                revealed: true,
+                token_amount: *token_amount,
+                price: *price,
+                level,
+                ..SealedBid::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(&book.demand[..3], &[4, 9, 5]);
+
+    book.settle(&auction, 9);
+    assert_eq!(book.clearing_level, 1);
+    assert_eq!(book.clearing_price, 15);
+    assert_eq!(book.clearing_supply, 5);
+    assert_eq!(book.tokens_owed, 9);
+
+    let fills = bids.iter().map(|bid| book.fill(bid)).collect::<Vec<_>>();
+    assert_eq!(fills, vec![4, 3, 1, 0]);
+    for (bid, fill) in bids.iter().zip(fills.iter()) {
+        book.record_claim(bid, *fill);
+    }
+    // Rounding remainder released after the last claim at clearing level
+    assert_eq!(book.clearing_demand_left, 0);
+    assert_eq!(book.tokens_owed, 0);
+
+    let mut packed = vec![0; SealedBook::LEN];
+    SealedBook::pack(book, &mut packed).expect("failed to pack SealedBook");
+    assert_eq!(SealedBook::unpack(&packed), Ok(book));
+    let mut packed = vec![0; SealedBid::LEN];
+    SealedBid::pack(bids[1], &mut packed).expect("failed to pack SealedBid");
+    assert_eq!(SealedBid::unpack(&packed), Ok(bids[1]));
+
+    let mut book = SealedBook::default();
+    book.settle(&auction, 9);
+    assert!(book.settled);
+    assert_eq!(book.clearing_level, SealedBid::NO_LEVEL);
+}
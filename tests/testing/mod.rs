@@ -0,0 +1,345 @@
+//! Helpers shared by program tests.
+#![allow(dead_code)]
+
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{from_account, Account, ReadableAccount},
+    account_info::AccountInfo,
+    clock::{Clock, UnixTimestamp, DEFAULT_MS_PER_SLOT},
+    decode_error::DecodeError,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::{keypair::Keypair, signers::Signers},
+    system_instruction, sysvar,
+    transaction::{Transaction, TransactionError},
+    transport::{Result as RpcResult, TransportError},
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::{
+    instruction::{initialize_mint, mint_to_checked},
+    state::{Account as TokenAccount, Mint},
+};
+
+use dutch_auction::{
+    error::AuctionError,
+    instruction::{self as auction_instruction, AuctionConfig, BidAccounts, TokenSource},
+    order::ed25519_program,
+    processor::Processor,
+    state::Auction,
+};
+
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "dutch_auction",
+        dutch_auction::id(),
+        processor!(Processor::process),
+    )
+}
+
+// Runtime of this SDK version has no ed25519 program, stand-in accepts every
+// signature, so only lookup of buyer signature by auction program is tested.
+fn process_ed25519(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _input: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
+
+pub fn program_test_with_ed25519() -> ProgramTest {
+    let mut program_test = program_test();
+    program_test.add_program(
+        "ed25519_program",
+        ed25519_program::id(),
+        processor!(process_ed25519),
+    );
+    program_test
+}
+
+/// Ed25519 program instruction with single signature of `message`, layout
+/// expected by `PurchaseOrder::is_verified_by`.
+pub fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let mut data = vec![1, 0];
+    for value in &[
+        48u16,
+        u16::MAX,
+        16,
+        u16::MAX,
+        112,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Program test with `token` minted to `seller` and auction account created
+/// but not initialized yet.
+pub struct TestAuction {
+    pub ctx: ProgramTestContext,
+    pub payer: Keypair,
+    pub token: Keypair,
+    pub auction: Keypair,
+    pub seller: Keypair,
+    pub seller_token: Pubkey,
+    pub token_auction_owner: Pubkey,
+    pub token_auction: Pubkey,
+}
+
+impl TestAuction {
+    pub async fn start(program_test: ProgramTest, token_amount: u64, decimals: u8) -> Self {
+        let token = Keypair::new();
+        let (auction, token_auction_owner) = loop {
+            let kp = Keypair::new();
+            let derived =
+                Pubkey::create_program_address(&[kp.pubkey().as_ref()], &dutch_auction::id());
+            if let Ok(pk) = derived {
+                break (kp, pk);
+            }
+        };
+        let seller = Keypair::new();
+        let mut ctx = program_test.start_with_context().await;
+        let payer = Keypair::from_bytes(&ctx.payer.to_bytes()).expect("invalid payer");
+        let rent = ctx.banks_client.get_rent().await.expect("get_rent failed");
+
+        let mint_authority = Keypair::new();
+        let seller_token = get_associated_token_address(&seller.pubkey(), &token.pubkey());
+        send_tx(
+            &mut ctx,
+            &[
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &token.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                initialize_mint(
+                    &spl_token::id(),
+                    &token.pubkey(),
+                    &mint_authority.pubkey(),
+                    None,
+                    decimals,
+                )
+                .expect("failed to create InitializeMint instruction"),
+                create_associated_token_account(&payer.pubkey(), &seller.pubkey(), &token.pubkey()),
+                mint_to_checked(
+                    &spl_token::id(),
+                    &token.pubkey(),
+                    &seller_token,
+                    &mint_authority.pubkey(),
+                    &[],
+                    token_amount,
+                    decimals,
+                )
+                .expect("failed to create MintToChecked instruction"),
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &auction.pubkey(),
+                    rent.minimum_balance(Auction::LEN),
+                    Auction::LEN as u64,
+                    &dutch_auction::id(),
+                ),
+            ],
+            &[&payer, &token, &mint_authority, &auction],
+        )
+        .await
+        .expect("failed to create token and auction accounts");
+
+        Self {
+            ctx,
+            payer,
+            token_auction: get_associated_token_address(&token_auction_owner, &token.pubkey()),
+            token,
+            auction,
+            seller,
+            seller_token,
+            token_auction_owner,
+        }
+    }
+
+    /// Send transaction paid by test payer, `signers` should not include it.
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> RpcResult<()> {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        send_tx(&mut self.ctx, instructions, &keypairs).await
+    }
+
+    /// Initialize auction with tokens transferred from seller.
+    pub async fn initialize(
+        &mut self,
+        authority: &Pubkey,
+        config: &AuctionConfig,
+    ) -> RpcResult<()> {
+        let instruction = auction_instruction::initialize_auction(
+            &self.auction.pubkey(),
+            authority,
+            &self.payer.pubkey(),
+            &self.token.pubkey(),
+            &self.token_auction_owner,
+            Some(&TokenSource {
+                account: self.seller_token,
+                authority: self.seller.pubkey(),
+            }),
+            config,
+        )
+        .expect("failed to create InitializeAuction instruction");
+        send_tx(&mut self.ctx, &[instruction], &[&self.payer, &self.seller]).await
+    }
+
+    /// Funded wallet with associated token account.
+    pub async fn create_buyer(&mut self, lamports: u64) -> (Keypair, Pubkey) {
+        let wallet = Keypair::new();
+        let token_customer = get_associated_token_address(&wallet.pubkey(), &self.token.pubkey());
+        let instructions = [
+            system_instruction::transfer(&self.payer.pubkey(), &wallet.pubkey(), lamports),
+            create_associated_token_account(
+                &self.payer.pubkey(),
+                &wallet.pubkey(),
+                &self.token.pubkey(),
+            ),
+        ];
+        self.send(&instructions, &[])
+            .await
+            .expect("failed to create buyer");
+        (wallet, token_customer)
+    }
+
+    pub fn bid_accounts(&self, token_customer: &Pubkey) -> BidAccounts {
+        BidAccounts {
+            token: self.token.pubkey(),
+            token_auction: self.token_auction,
+            token_auction_owner: self.token_auction_owner,
+            token_customer: *token_customer,
+            ..BidAccounts::default()
+        }
+    }
+
+    pub async fn get_auction(&mut self) -> Auction {
+        let acc = get_account(&mut self.ctx, self.auction.pubkey()).await;
+        Auction::unpack(acc.data()).expect("Valid packed data")
+    }
+
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let acc = get_account(&mut self.ctx, *token_account).await;
+        TokenAccount::unpack(acc.data())
+            .expect("Valid packed data")
+            .amount
+    }
+}
+
+pub fn expect_auction_error(name: &str, result: RpcResult<()>, expected: AuctionError) {
+    match result.expect_err(name) {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(x),
+        )) => {
+            assert_eq!(
+                AuctionError::decode_custom_error_to_enum(x),
+                Some(expected),
+                "{}",
+                name
+            );
+        }
+        other => panic!("{}: unexpected error: {:?}", name, other),
+    };
+}
+
+pub fn expect_instruction_error(name: &str, result: RpcResult<()>, expected: InstructionError) {
+    match result.expect_err(name) {
+        TransportError::TransactionError(TransactionError::InstructionError(_, error)) => {
+            assert_eq!(error, expected, "{}", name);
+        }
+        other => panic!("{}: unexpected error: {:?}", name, other),
+    };
+}
+
+pub async fn send_tx<T: Signers>(
+    ctx: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signing_keypairs: &T,
+) -> RpcResult<()> {
+    let recent_blockhash = ctx.banks_client.get_recent_blockhash().await;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&ctx.payer.pubkey()),
+        signing_keypairs,
+        recent_blockhash.expect("get_recent_blockhash failed"),
+    );
+    ctx.banks_client.process_transaction(transaction).await
+}
+
+pub async fn get_unix_timestamp(ctx: &mut ProgramTestContext) -> UnixTimestamp {
+    let account = ctx
+        .banks_client
+        .get_account(sysvar::clock::id())
+        .await
+        .expect("failed to call get_account")
+        .expect("Clock sysvar not precent");
+    from_account::<Clock, _>(&account)
+        .expect("failed to deserialize Clock sysvar")
+        .unix_timestamp
+}
+
+pub async fn move_forward(ctx: &mut ProgramTestContext, shift: UnixTimestamp) {
+    let required_time = get_unix_timestamp(ctx).await + shift;
+    while required_time > get_unix_timestamp(ctx).await {
+        move_to_next_slot(ctx).await;
+    }
+}
+
+// Warp to the first second of price `step` with one big jump, then finish
+// slot-by-slot so we land on the first slot with required timestamp.
+pub async fn warp_to_price_step(ctx: &mut ProgramTestContext, auction: &Pubkey, step: i64) {
+    let acc = get_account(ctx, *auction).await;
+    let auction = Auction::unpack(acc.data()).expect("Valid packed data");
+    let required_time = auction.time_start + auction.time_step * step;
+
+    let current_time = get_unix_timestamp(ctx).await;
+    assert!(required_time >= current_time, "can not warp back in time");
+    let slots = ((required_time - current_time) as u64 * 1000) / DEFAULT_MS_PER_SLOT;
+    if slots > 1 {
+        let slot = ctx.banks_client.get_root_slot().await;
+        let new_slot = slot.expect("failed to get Slot") + slots - 1;
+        ctx.warp_to_slot(new_slot).expect("failed to warp");
+    }
+
+    while required_time > get_unix_timestamp(ctx).await {
+        move_to_next_slot(ctx).await;
+    }
+    assert_eq!(get_unix_timestamp(ctx).await, required_time);
+}
+
+pub async fn move_to_next_slot(ctx: &mut ProgramTestContext) {
+    let slot = ctx.banks_client.get_root_slot().await;
+    let new_slot = slot.expect("failed to get Slot") + 1;
+    ctx.warp_to_slot(new_slot + 1).expect("failed to warp");
+}
+
+pub async fn get_balance(ctx: &mut ProgramTestContext, key: &Pubkey) -> u64 {
+    let fut = ctx.banks_client.get_balance(*key);
+    fut.await.expect("get_balance failed")
+}
+
+pub async fn get_account(ctx: &mut ProgramTestContext, key: Pubkey) -> Account {
+    ctx.banks_client
+        .get_account(key)
+        .await
+        .expect("failed to call get_account")
+        .expect("account not found")
+}